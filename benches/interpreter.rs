@@ -0,0 +1,186 @@
+// these time how fast the chip8 runs roms with nothing throttling it, like a headless batch of roms does
+//
+// run them with : cargo bench --bench interpreter
+
+use std::{
+    fs,
+    hint::black_box
+};
+
+use criterion::{
+    criterion_group,
+    criterion_main,
+    BatchSize,
+    BenchmarkId,
+    Criterion,
+    Throughput
+};
+
+use rip8::{
+    Chip8,
+    Keyboard,
+    chip_8::BinaryOp,
+    rom
+};
+
+const SEED : u64 = 0x5eed;
+
+// every rom runs for 10 seconds of frames at 1000hz
+const FRAMES : usize = 600;
+const INSTRUCTIONS_PER_FRAME : usize = 16;
+
+fn fullgames() -> Vec<(String, Vec<u8>)> {
+    let mut roms = fs::read_dir("fullgames").unwrap()
+        .map(|entry| entry.unwrap().path())
+        .map(|path| (path.file_stem().unwrap().to_string_lossy().to_string(), rom::read_rom(&path.to_string_lossy()).unwrap()))
+        .collect::<Vec<(String, Vec<u8>)>>();
+
+    roms.sort();
+    roms
+}
+
+fn new_chip8(rom : &[u8]) -> Chip8 {
+    let mut chip8 = Chip8::with_seed(SEED);
+    chip8.load_font();
+    chip8.load_rom(rom).unwrap();
+    chip8
+}
+
+/// this runs a rom the way the emulator does, through processor_frame
+fn run_rom(mut chip8 : Chip8) -> Chip8 {
+    for _ in 0..FRAMES {
+        for _ in 0..INSTRUCTIONS_PER_FRAME {
+            if !chip8.processor_frame(Keyboard::None) {
+                return chip8
+            }
+        }
+        chip8.update_timers();
+    }
+
+    chip8
+}
+
+/// this runs a rom the same way as run_rom but with the chain of guards processor_frame used to decode with
+fn run_rom_guard_chain(mut chip8 : Chip8) -> Chip8 {
+    for _ in 0..FRAMES {
+        for _ in 0..INSTRUCTIONS_PER_FRAME {
+            if chip8.pc_reg as usize + 1 >= chip8.memory.len() {
+                return chip8
+            }
+
+            chip8.keyboard = Keyboard::None;
+            if chip8.waiting_for_vblank || chip8.hit_watchpoint() {
+                continue
+            }
+            if !chip8.watchpoints.is_empty() && chip8.check_execute_watchpoints() {
+                continue
+            }
+
+            let opcode = chip8.fetch_instruction();
+            guard_chain_execute(&mut chip8, opcode);
+        }
+        chip8.update_timers();
+    }
+
+    chip8
+}
+
+/// this is how opcodes were run before the handler table in instruction.rs, every arm masks the opcode again until one
+/// matches. it is only kept here so the benchmark has something to compare the table against
+fn guard_chain_execute(chip8 : &mut Chip8, opcode : u16) {
+    let x = ((opcode & 0x0F00) >> 8) as usize;
+    let y = ((opcode & 0x00F0) >> 4) as usize;
+
+    match opcode {
+        0x00E0 => chip8.clear_display_instruction(),
+        i if i & 0xF000 == 0x1000 => chip8.jump_instruction(i & 0x0FFF),
+        i if i & 0xF000 == 0x6000 => chip8.set_vx_reg_instruction(x, (i & 0x00FF) as u8),
+        i if i & 0xF000 == 0x7000 => chip8.add_reg_vx_instruction(x, (i & 0x00FF) as u8, false),
+        i if i & 0xF000 == 0xA000 => chip8.set_index_reg_instruction(i & 0x0FFF),
+        i if i & 0xF000 == 0xD000 => chip8.draw_sprite_instruction(chip8.v_regs[x], chip8.v_regs[y], (i & 0x000F) as u8),
+        0x00EE => chip8.return_instruction(),
+        i if i & 0xF000 == 0x2000 => chip8.call_instruction(i & 0x0FFF),
+        i if i & 0xF000 == 0x3000 ||
+             i & 0xF000 == 0x4000 ||
+             i & 0xF000 == 0x5000 ||
+             i & 0xF000 == 0x9000 ||
+             i & 0xF000 == 0xE000 => {
+            let first_reg_value = chip8.v_regs[x];
+            let second_reg_value = chip8.v_regs[y];
+            let key_code = chip8.keyboard.get_keycode();
+            let num = (i & 0x00FF) as u8;
+
+            match i & 0xF000 {
+                0x3000 => chip8.skipif_vx_reg_nn_instruction(first_reg_value, num, true),
+                0x4000 => chip8.skipif_vx_reg_nn_instruction(first_reg_value, num, false),
+                0x5000 => chip8.skipif_vx_reg_nn_instruction(first_reg_value, second_reg_value, true),
+                0x9000 => chip8.skipif_vx_reg_nn_instruction(first_reg_value, second_reg_value, false),
+                _ => match i & 0x00FF {
+                    0x009E => chip8.skipif_vx_reg_nn_instruction(first_reg_value & 0xF, key_code, true),
+                    0x00A1 => chip8.skipif_vx_reg_nn_instruction(first_reg_value & 0xF, key_code, false),
+                    _ => {}
+                }
+            }
+        },
+        i if i & 0xF000 == 0x8000 => {
+            let num = chip8.v_regs[y];
+
+            match i & 0x000F {
+                0x0000 => chip8.set_vx_reg_instruction(x, num),
+                0x0001 => chip8.bin_op_vx_reg_instruction(x, num, BinaryOp::Or),
+                0x0002 => chip8.bin_op_vx_reg_instruction(x, num, BinaryOp::And),
+                0x0003 => chip8.bin_op_vx_reg_instruction(x, num, BinaryOp::Xor),
+                0x0004 => chip8.add_reg_vx_instruction(x, num, true),
+                0x0005 => chip8.subtract_vx_reg_instruction(x, num, false),
+                0x0006 => chip8.shift_vx_register(x, num, true),
+                0x0007 => chip8.subtract_vx_reg_instruction(x, num, true),
+                0x000E => chip8.shift_vx_register(x, num, false),
+                _ => {}
+            }
+        },
+        i if i & 0xF000 == 0xB000 => chip8.jump_with_offset_instruction(x, i & 0x0FFF),
+        i if i & 0xF000 == 0xC000 => chip8.random_instruction(x, (i & 0x00FF) as u8),
+        i if i & 0xF000 == 0xF000 => match i & 0x00FF {
+            0x0007 => chip8.set_vx_reg_instruction(x, chip8.delay_timer_register),
+            0x0015 => chip8.set_delay_timer_reg_instruction(chip8.v_regs[x]),
+            0x0018 => chip8.set_sound_timer_reg_instruction(chip8.v_regs[x]),
+            0x001E => chip8.add_to_index_reg_instruction(chip8.v_regs[x] as u16),
+            0x000A => chip8.get_key_instruction(x),
+            0x0029 => chip8.set_index_to_font_char_instruction((chip8.v_regs[x] & 0xF) as usize),
+            0x0033 => chip8.bcd_instruction(x),
+            0x0055 => chip8.store_to_memory_instruction(x),
+            0x0065 => chip8.load_from_memory_instruction(x),
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+fn run_roms(criterion : &mut Criterion) {
+    let mut group = criterion.benchmark_group("run_rom");
+    group.throughput(Throughput::Elements((FRAMES * INSTRUCTIONS_PER_FRAME) as u64));
+
+    for (name, rom) in fullgames() {
+        // the two ways have to do the same work or timing them against each other doesn't say anything
+        let table = run_rom(new_chip8(&rom));
+        let guards = run_rom_guard_chain(new_chip8(&rom));
+        assert!(
+            table.pc_reg == guards.pc_reg && table.v_regs == guards.v_regs
+                && table.memory == guards.memory && table.display_buffer == guards.display_buffer,
+            "{} ran differently with the guard chain", name
+        );
+
+        // making the chip8 isn't timed, only running the rom
+        group.bench_with_input(BenchmarkId::new("processor_frame", &name), &rom, |bencher, rom| {
+            bencher.iter_batched(|| new_chip8(rom), |chip8| run_rom(black_box(chip8)), BatchSize::SmallInput)
+        });
+        group.bench_with_input(BenchmarkId::new("guard_chain", &name), &rom, |bencher, rom| {
+            bencher.iter_batched(|| new_chip8(rom), |chip8| run_rom_guard_chain(black_box(chip8)), BatchSize::SmallInput)
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, run_roms);
+criterion_main!(benches);
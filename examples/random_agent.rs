@@ -0,0 +1,57 @@
+use std::time::Instant;
+
+use rand::{
+    Rng,
+    SeedableRng
+};
+use rand_chacha::ChaCha8Rng;
+
+use rip8::{
+    Env,
+    Keyboard,
+    env
+};
+
+/// this plays a rom with random keys to show the env api and how many steps a second it runs
+///
+/// the score is the byte at an address that can be found with the debugger's memory search, with no address
+/// there is no reward
+///
+/// cargo run --release --example random_agent -- fullgames/br8kout.ch8 [score address in hex]
+fn main() -> Result<(), String> {
+    let rom_path = std::env::args().nth(1).unwrap_or(String::from("fullgames/br8kout.ch8"));
+    let rom = rip8::rom::read_rom(&rom_path)?;
+
+    let mut env = Env::new(&rom, 700)?.with_max_frames(60 * 60);
+
+    if let Some(address) = std::env::args().nth(2) {
+        match usize::from_str_radix(address.trim_start_matches("0x"), 16) {
+            Ok(address) => env = env.with_reward(env::reward_from_byte(address)),
+            Err(_) => return Err(format!("error : '{}' is not a hex address!", address))
+        }
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    let start = Instant::now();
+    let mut steps = 0;
+    let mut episodes = 0;
+    let mut total_reward = 0.0;
+
+    env.reset(episodes);
+    while start.elapsed().as_secs() < 5 {
+        // 0 to 15 are keys and 16 is no key
+        let step = env.step(Keyboard::from_keycode(rng.gen_range(0..=16)), 4);
+        total_reward += step.reward;
+        steps += 1;
+
+        if step.done {
+            episodes += 1;
+            env.reset(episodes);
+        }
+    }
+
+    let seconds = start.elapsed().as_secs_f64();
+    println!("{} steps in {:.1}s, {:.0} steps a second, {} episodes, {} reward", steps, seconds, steps as f64 / seconds, episodes, total_reward);
+
+    Ok(())
+}
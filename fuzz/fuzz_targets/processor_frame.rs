@@ -0,0 +1,66 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use rip8::{
+    Chip8,
+    Coverage,
+    Keyboard,
+    Profiler,
+    Quirks
+};
+
+// how many frames every input runs for and how many instructions are in a frame, 700hz at 60 frames a second
+const FRAMES : usize = 120;
+const INSTRUCTIONS_PER_FRAME : usize = 12;
+
+// an input is a couple of bytes saying how to run the rom and then the rom itself, so any rom with two bytes in
+// front of it is a seed :
+//
+// byte 0 is the quirks, one bit each for shift, load_store, jump, logic, clip and vblank
+// byte 1 is how many key bytes come next, frame N holds the key in key byte N % count and a key past 0xF is no key
+// everything after the keys is the rom
+fuzz_target!(|data : &[u8]| {
+    let (quirk_bits, key_count) = match data {
+        [quirk_bits, key_count, ..] => (*quirk_bits, *key_count as usize),
+        _ => return
+    };
+
+    let keys = &data[2..(2 + key_count).min(data.len())];
+    let rom = &data[2 + keys.len()..];
+    let rom = &rom[..rom.len().min(Chip8::MAX_ROM_SIZE)];
+
+    let mut chip8 = Chip8::with_seed(0);
+    chip8.quirks = Quirks {
+        shift : quirk_bits & 0b0000_0001 != 0,
+        load_store : quirk_bits & 0b0000_0010 != 0,
+        jump : quirk_bits & 0b0000_0100 != 0,
+        logic : quirk_bits & 0b0000_1000 != 0,
+        clip : quirk_bits & 0b0001_0000 != 0,
+        vblank : quirk_bits & 0b0010_0000 != 0,
+    };
+    chip8.load_font();
+    chip8.load_rom(rom).expect("a rom cut to the max size always fits");
+
+    // coverage and the profiler look at every instruction too so they get fuzzed along with the chip8
+    chip8.coverage = Some(Coverage::new(0, chip8.memory.len()));
+    let mut profiler = Profiler::new();
+
+    for frame in 0..FRAMES {
+        let keyboard = match keys {
+            [] => Keyboard::None,
+            keys => Keyboard::from_keycode(keys[frame % keys.len()])
+        };
+
+        for _ in 0..INSTRUCTIONS_PER_FRAME {
+            profiler.record(&chip8, keyboard);
+            if !chip8.processor_frame(keyboard) {
+                return
+            }
+        }
+
+        chip8.update_timers();
+    }
+
+    profiler.report(&chip8.memory);
+});
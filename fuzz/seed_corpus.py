@@ -0,0 +1,43 @@
+# this builds the seed corpus for the fuzz targets out of the roms in testroms and fullgames
+#
+# every rom goes in twice, once with rip8's quirks and no keys and once with octo's quirks and every key getting
+# pressed in turn. see fuzz_targets/processor_frame.rs for what the bytes in front of the rom mean
+#
+# run it from anywhere with : python3 fuzz/seed_corpus.py
+
+from pathlib import Path
+
+fuzz_folder = Path(__file__).resolve().parent
+rom_folders = [fuzz_folder.parent / "testroms", fuzz_folder.parent / "fullgames"]
+corpus_folder = fuzz_folder / "corpus" / "processor_frame"
+
+# the quirk bits are shift, load_store, jump, logic, clip and vblank from the lowest bit up
+RIP8_QUIRKS = 0b0001_0011
+OCTO_QUIRKS = 0b0000_0000
+
+# no key, then every key, then no key again
+EVERY_KEY = bytes([0xFF] + list(range(16)) + [0xFF])
+
+
+def read_rom(rom_path):
+    # .chip8 roms are radix text with an instruction on each line and ';;' comments, like rom::parse_radix reads
+    if rom_path.suffix == ".chip8":
+        program = bytearray()
+        for line in rom_path.read_text().splitlines():
+            line = line.strip()
+            if line and not line.startswith(";;"):
+                program += int(line, 16).to_bytes(2, "big")
+        return bytes(program)
+
+    return rom_path.read_bytes()
+
+
+corpus_folder.mkdir(parents=True, exist_ok=True)
+
+for rom_folder in rom_folders:
+    for rom_path in sorted(rom_folder.iterdir()):
+        rom = read_rom(rom_path)
+        (corpus_folder / f"{rom_path.stem}_rip8").write_bytes(bytes([RIP8_QUIRKS, 0]) + rom)
+        (corpus_folder / f"{rom_path.stem}_octo_keys").write_bytes(bytes([OCTO_QUIRKS, len(EVERY_KEY)]) + EVERY_KEY + rom)
+
+print(f"wrote the seed corpus to {corpus_folder}")
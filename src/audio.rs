@@ -0,0 +1,189 @@
+use std::sync::{
+    Arc,
+    atomic::{
+        AtomicBool,
+        AtomicU8,
+        Ordering
+    }
+};
+
+/// these are the shapes of tone the buzzer can make
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Waveform {
+    Square,
+    Sine,
+    Triangle,
+    Noise,
+}
+
+impl Waveform {
+    pub fn from_name(name : &str) -> Result<Waveform, String> {
+        match name {
+            "square" => Ok(Waveform::Square),
+            "sine" => Ok(Waveform::Sine),
+            "triangle" => Ok(Waveform::Triangle),
+            "noise" => Ok(Waveform::Noise),
+            _ => Err(format!("error : unknown waveform '{}', expected square, sine, triangle or noise", name))
+        }
+    }
+}
+
+/// these are the settings for the buzzer
+#[derive(Debug, Clone, Copy)]
+pub struct AudioConfig {
+    // the pitch of the tone in hz
+    pub frequency : f32,
+
+    pub waveform : Waveform,
+
+    // the volume from 0.0 to 1.0
+    pub volume : f32,
+}
+
+impl AudioConfig {
+    pub fn new() -> AudioConfig {
+        AudioConfig {
+            frequency : 440.0,
+            waveform : Waveform::Square,
+            volume : 0.25,
+        }
+    }
+}
+
+impl Default for AudioConfig {
+    fn default() -> AudioConfig {
+        AudioConfig::new()
+    }
+}
+
+/// this is the sound state that is shared between the emulator loop and the audio callback
+///
+/// the emulator loop writes the sound timer every frame and the audio callback reads it
+/// so the tone can start and stop in the middle of an audio buffer
+#[derive(Debug, Clone, Default)]
+pub struct SharedSound {
+    sound_timer : Arc<AtomicU8>,
+    muted : Arc<AtomicBool>,
+}
+
+impl SharedSound {
+    pub fn new() -> SharedSound {
+        SharedSound::default()
+    }
+
+    pub fn set_sound_timer(&self, value : u8) {
+        self.sound_timer.store(value, Ordering::Relaxed)
+    }
+
+    pub fn sound_timer(&self) -> u8 {
+        self.sound_timer.load(Ordering::Relaxed)
+    }
+
+    pub fn set_muted(&self, muted : bool) {
+        self.muted.store(muted, Ordering::Relaxed)
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted.load(Ordering::Relaxed)
+    }
+}
+
+/// this makes the samples for the buzzer
+///
+/// the length of the tone is counted in samples instead of frames so a buzz of n frames is always
+/// exactly n / 60 seconds long, and the tone fades in and out over a few milliseconds so it doesn't click
+#[derive(Debug, Clone)]
+pub struct ToneGenerator {
+    pub config : AudioConfig,
+
+    pub muted : bool,
+
+    sample_rate : u32,
+
+    // where in the current wave cycle the tone is from 0.0 to 1.0
+    phase : f32,
+
+    // the current volume of the envelope from 0.0 to 1.0
+    gain : f32,
+
+    // how much the envelope moves every sample
+    gain_step : f32,
+
+    // how many samples are left before the tone stops
+    samples_left : u32,
+
+    // the last sound timer value that was given
+    last_sound_timer : u8,
+
+    // the shift register that makes the noise waveform
+    noise_register : u16,
+}
+
+impl ToneGenerator {
+    // how long the tone takes to fade in and out in seconds
+    const ENVELOPE_TIME : f32 = 0.005;
+
+    pub fn new(config : AudioConfig, sample_rate : u32) -> ToneGenerator {
+        ToneGenerator {
+            config,
+            muted : false,
+            sample_rate,
+            phase : 0.0,
+            gain : 0.0,
+            gain_step : 1.0 / (Self::ENVELOPE_TIME * sample_rate as f32),
+            samples_left : 0,
+            last_sound_timer : 0,
+            noise_register : 0xACE1,
+        }
+    }
+
+    /// this fn tells the generator what the sound timer is now
+    ///
+    /// the timer counting down by itself doesn't change anything because the generator is already counting samples,
+    /// but the timer going up starts a new tone and the timer being cleared early stops it
+    pub fn set_sound_timer(&mut self, sound_timer : u8) {
+        let samples_per_frame = self.sample_rate / 60;
+
+        if sound_timer > self.last_sound_timer {
+            self.samples_left = sound_timer as u32 * samples_per_frame;
+        } else if sound_timer == 0 && self.last_sound_timer > 1 {
+            self.samples_left = 0;
+        }
+
+        self.last_sound_timer = sound_timer;
+    }
+
+    /// this fn gives back if the tone is on right now
+    pub fn is_playing(&self) -> bool {
+        self.samples_left != 0
+    }
+
+    pub fn next_sample(&mut self) -> f32 {
+        let target_gain = if self.samples_left != 0 && !self.muted { 1.0 } else { 0.0 };
+        self.samples_left = self.samples_left.saturating_sub(1);
+
+        if self.gain < target_gain {
+            self.gain = (self.gain + self.gain_step).min(target_gain);
+        } else if self.gain > target_gain {
+            self.gain = (self.gain - self.gain_step).max(target_gain);
+        }
+
+        let wave = match self.config.waveform {
+            Waveform::Square => if self.phase <= 0.5 { 1.0 } else { -1.0 },
+            Waveform::Sine => (self.phase * std::f32::consts::TAU).sin(),
+            Waveform::Triangle => 1.0 - 4.0 * (self.phase - 0.5).abs(),
+            Waveform::Noise => if self.noise_register & 1 == 1 { 1.0 } else { -1.0 },
+        };
+
+        self.phase += self.config.frequency / self.sample_rate as f32;
+        if self.phase >= 1.0 {
+            self.phase %= 1.0;
+
+            // the noise gets a new random bit every cycle so the frequency still changes its pitch
+            let bit = (self.noise_register ^ (self.noise_register >> 2) ^ (self.noise_register >> 3) ^ (self.noise_register >> 5)) & 1;
+            self.noise_register = (self.noise_register >> 1) | (bit << 15);
+        }
+
+        wave * self.gain * self.config.volume
+    }
+}
@@ -0,0 +1,204 @@
+use serde_json::Value;
+
+use crate::{
+    Keymap,
+    Palette,
+    Quirks,
+    octo
+};
+
+// every gif starts with this
+const GIF_MAGIC : [u8; 4] = *b"GIF8";
+
+/// this fn checks if a file is a gif, which is the only way octo cartridges come
+pub fn is_cartridge(bytes : &[u8]) -> bool {
+    bytes.starts_with(&GIF_MAGIC)
+}
+
+/// these are the settings octo saves in a cartridge next to the program
+#[derive(Debug, Clone)]
+pub struct CartridgeOptions {
+    // how many instructions octo runs every frame
+    pub tickrate : Option<u32>,
+
+    pub quirks : Quirks,
+
+    // the colors the cartridge wants, if it doesn't pick any this is none
+    pub palette : Option<Palette>,
+
+    // the keys the cartridge wants for the keypad, if it doesn't pick any this is none
+    pub keymap : Option<Keymap>,
+}
+
+/// this is a game in octo's cartridge format, a gif with the program and its settings hidden in the pixels
+///
+/// the gif is drawn with a palette where the bottom 2 bits of each color index don't change how it looks,
+/// those bits hold the data. reading the pixels of every frame in order 4 pixels make a byte, top bits first.
+/// the first 4 bytes are how long the data is and the data is json like this :
+///
+/// ```text
+/// { "program" : "<octo source>", "options" : { "tickrate" : 20, "shiftQuirks" : true, "fillColor" : "#FFCC00", ... } }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cartridge {
+    // the octo source the cartridge carries
+    pub program : String,
+
+    // the program assembled and ready to load
+    pub rom : Vec<u8>,
+
+    pub options : CartridgeOptions,
+}
+
+impl Cartridge {
+    // the colors octo uses when a cartridge only picks some of them
+    const OCTO_FILL_COLOR : [u8; 3] = [0xFF, 0xCC, 0x00];
+    const OCTO_BACKGROUND_COLOR : [u8; 3] = [0x99, 0x66, 0x00];
+
+    pub fn load(file_path : &str) -> Result<Cartridge, String> {
+        match std::fs::read(file_path) {
+            Ok(bytes) => Cartridge::from_bytes(&bytes),
+            _ => Err(format!("error : could not load cartridge '{}'!", file_path))
+        }
+    }
+
+    pub fn from_bytes(bytes : &[u8]) -> Result<Cartridge, String> {
+        let payload = Self::decode_payload(bytes)?;
+
+        let json = match serde_json::from_slice::<Value>(&payload) {
+            Ok(json) => json,
+            Err(error) => return Err(format!("error : the cartridge data is not valid json : {}", error))
+        };
+
+        let program = match json.get("program").and_then(Value::as_str) {
+            Some(program) => program.to_string(),
+            None => return Err(String::from("error : the cartridge has no program in it!"))
+        };
+
+        let options = Self::parse_options(json.get("options"))?;
+        let rom = octo::assemble(&program)?;
+
+        Ok(Cartridge { program, rom, options })
+    }
+
+    /// this fn pulls the bytes hidden in the bottom 2 bits of every pixel out of the gif
+    fn decode_payload(bytes : &[u8]) -> Result<Vec<u8>, String> {
+        let mut decode_options = gif::DecodeOptions::new();
+        decode_options.set_color_output(gif::ColorOutput::Indexed);
+
+        let mut decoder = match decode_options.read_info(bytes) {
+            Ok(decoder) => decoder,
+            Err(error) => return Err(format!("error : could not read cartridge gif : {}", error))
+        };
+
+        let mut pixels : Vec<u8> = Vec::new();
+        loop {
+            match decoder.read_next_frame() {
+                Ok(Some(frame)) => pixels.extend(frame.buffer.iter()),
+                Ok(None) => break,
+                Err(error) => return Err(format!("error : could not read cartridge gif : {}", error))
+            }
+        }
+
+        let data = pixels.chunks_exact(4)
+            .map(|pixels| pixels.iter().fold(0, |byte, pixel| (byte << 2) | (pixel & 0b11)))
+            .collect::<Vec<u8>>();
+
+        if data.len() < 4 {
+            return Err(String::from("error : the cartridge gif is too small to have a program in it!"))
+        }
+
+        let length = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+        // the length comes from the gif so on 32 bit targets it might not fit with the 4 added
+        match 4usize.checked_add(length).and_then(|end| data.get(4..end)) {
+            Some(payload) => Ok(payload.to_vec()),
+            None => Err(String::from("error : the cartridge data is cut off, this might not be an octo cartridge!"))
+        }
+    }
+
+    fn parse_options(options : Option<&Value>) -> Result<CartridgeOptions, String> {
+        let flag = |name : &str| options.and_then(|options| options.get(name)).and_then(Value::as_bool).unwrap_or(false);
+        let option = |name : &str| options.and_then(|options| options.get(name));
+
+        let quirks = Quirks {
+            shift : flag("shiftQuirks"),
+            load_store : flag("loadStoreQuirks"),
+            jump : flag("jumpQuirks"),
+            logic : flag("logicQuirks"),
+            clip : flag("clipQuirks"),
+            vblank : flag("vBlankQuirks"),
+        };
+
+        let tickrate = match option("tickrate") {
+            Some(tickrate) => match tickrate.as_u64() {
+                Some(tickrate) if tickrate > 0 => Some(tickrate as u32),
+                _ => return Err(format!("error : the cartridge has a bad tickrate '{}'!", tickrate))
+            },
+            None => None
+        };
+
+        let fill_color = option("fillColor").and_then(Value::as_str);
+        let background_color = option("backgroundColor").and_then(Value::as_str);
+
+        let palette = match (fill_color, background_color) {
+            (None, None) => None,
+            (fill_color, background_color) => Some(Palette {
+                on_color : match fill_color {
+                    Some(color) => Self::parse_color(color)?,
+                    None => Self::OCTO_FILL_COLOR
+                },
+                off_color : match background_color {
+                    Some(color) => Self::parse_color(color)?,
+                    None => Self::OCTO_BACKGROUND_COLOR
+                },
+            })
+        };
+
+        let keymap = match option("keys") {
+            Some(keys) => Some(Self::parse_keymap(keys)?),
+            None => None
+        };
+
+        Ok(CartridgeOptions { tickrate, quirks, palette, keymap })
+    }
+
+    /// this fn reads a color like "#FFCC00"
+    fn parse_color(color : &str) -> Result<[u8; 3], String> {
+        let hex = color.trim_start_matches('#');
+
+        match u32::from_str_radix(hex, 16) {
+            Ok(rgb) if hex.len() == 6 => Ok([(rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8]),
+            _ => Err(format!("error : the cartridge has a bad color '{}'!", color))
+        }
+    }
+
+    /// this fn reads a keymap like { "5" : ["w", "ArrowUp"], "6" : "e" }, the keys it leaves out stay where they are
+    fn parse_keymap(keys : &Value) -> Result<Keymap, String> {
+        let keys = match keys.as_object() {
+            Some(keys) => keys,
+            None => return Err(String::from("error : the cartridge keymap is not an object!"))
+        };
+
+        let mut keymap = Keymap::new();
+
+        for (key, names) in keys {
+            let key_code = match u8::from_str_radix(key, 16) {
+                Ok(key_code) if key_code <= 0xF => key_code as usize,
+                _ => return Err(format!("error : the cartridge keymap has a bad chip8 key '{}'!", key))
+            };
+
+            let names = match names {
+                Value::String(name) => vec![name.clone()],
+                Value::Array(names) => names.iter().filter_map(Value::as_str).map(|name| name.to_string()).collect(),
+                _ => return Err(format!("error : the cartridge keymap has bad keys for '{}'!", key))
+            };
+
+            // single letters are lowercase in the keymap so shift doesn't change which key it is
+            keymap.keys[key_code] = names.into_iter()
+                .map(|name| if name.chars().count() == 1 { name.to_lowercase() } else { name })
+                .collect();
+        }
+
+        Ok(keymap)
+    }
+}
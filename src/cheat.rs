@@ -0,0 +1,230 @@
+use std::{
+    fs::File,
+    io::{
+        BufRead,
+        BufReader,
+        Write
+    },
+    path::Path,
+};
+
+use crate::Chip8;
+
+/// these are the ways a memory search can narrow down its candidates, comparing memory now to the last snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchFilter {
+    // the byte is the same as it was
+    Equal,
+
+    // the byte is different than it was
+    Changed,
+
+    Increased,
+    Decreased,
+
+    // the byte is this value right now
+    Value(u8),
+}
+
+impl SearchFilter {
+    /// this fn reads a filter by name, anything that isn't a name is read as a hex value
+    pub fn parse(text : &str) -> Result<SearchFilter, String> {
+        match text {
+            "equal" | "eq" => Ok(SearchFilter::Equal),
+            "changed" | "ne" => Ok(SearchFilter::Changed),
+            "increased" | "gt" => Ok(SearchFilter::Increased),
+            "decreased" | "lt" => Ok(SearchFilter::Decreased),
+            value => match u8::from_str_radix(value.trim_start_matches("0x"), 16) {
+                Ok(value) => Ok(SearchFilter::Value(value)),
+                Err(_) => Err(format!("error : '{}' is not a search filter or a byte!", text))
+            }
+        }
+    }
+
+    fn keeps(&self, old_value : u8, new_value : u8) -> bool {
+        match self {
+            SearchFilter::Equal => new_value == old_value,
+            SearchFilter::Changed => new_value != old_value,
+            SearchFilter::Increased => new_value > old_value,
+            SearchFilter::Decreased => new_value < old_value,
+            SearchFilter::Value(value) => new_value == *value,
+        }
+    }
+}
+
+/// this is a search through memory for the address a game keeps something like its score or lives in
+///
+/// it starts with every address as a candidate and a snapshot of memory, then each filter throws out the candidates
+/// that don't match how memory changed since the last snapshot and takes a new one. EX : lose a life, filter by
+/// decreased, play a bit without dying, filter by equal, until only a few addresses are left
+#[derive(Debug, Clone)]
+pub struct MemorySearch {
+    pub snapshot : Vec<u8>,
+    pub candidates : Vec<usize>,
+}
+
+impl MemorySearch {
+    pub fn new(chip8 : &Chip8) -> MemorySearch {
+        MemorySearch {
+            snapshot : chip8.memory.clone(),
+            candidates : (0..chip8.memory.len()).collect(),
+        }
+    }
+
+    /// this fn keeps only the candidates that match the filter and gives back how many are left
+    pub fn filter(&mut self, chip8 : &Chip8, filter : SearchFilter) -> usize {
+        let snapshot = &self.snapshot;
+        self.candidates.retain(|address| filter.keeps(snapshot[*address], chip8.memory[*address]));
+        self.snapshot = chip8.memory.clone();
+
+        self.candidates.len()
+    }
+}
+
+/// this is one cheat, a value written to an address of memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cheat {
+    pub address : usize,
+    pub value : u8,
+
+    // a frozen cheat is written every frame so the game can't change it, otherwise it is a patch
+    // that is written once when the rom is loaded
+    pub frozen : bool,
+
+    pub name : String,
+}
+
+/// these are the cheats for one rom, they are saved in a file named after the rom's hash
+///
+/// a cheat file looks like this :
+///
+/// ```text
+/// rip8-cheats 1
+/// rom_hash 9f1c2b7a0e4d6c35
+/// freeze 2f0 03 lives
+/// patch 3a1 09 start on level 9
+/// ```
+#[derive(Debug, Clone)]
+pub struct CheatList {
+    pub rom_hash : u64,
+    pub cheats : Vec<Cheat>,
+}
+
+impl CheatList {
+    const HEADER : &'static str = "rip8-cheats 1";
+
+    // the folder cheat files are saved in
+    const CHEAT_FOLDER : &'static str = "cheats";
+
+    pub fn new(rom_hash : u64) -> CheatList {
+        CheatList { rom_hash, cheats : Vec::new() }
+    }
+
+    /// this fn gives back where the cheats for a rom are saved
+    pub fn file_path(rom_hash : u64) -> String {
+        format!("{}/{:016x}.cheats", Self::CHEAT_FOLDER, rom_hash)
+    }
+
+    /// this fn loads the saved cheats for a rom, a rom with no cheats saved gets an empty list
+    pub fn load_for_rom(rom_hash : u64) -> Result<CheatList, String> {
+        let file_path = Self::file_path(rom_hash);
+
+        if !Path::new(&file_path).exists() {
+            return Ok(CheatList::new(rom_hash))
+        }
+
+        let cheat_list = Self::load(&file_path)?;
+        if cheat_list.rom_hash != rom_hash {
+            return Err(format!("error : the cheats in '{}' are for a different rom!", file_path))
+        }
+
+        Ok(cheat_list)
+    }
+
+    pub fn load(file_path : &str) -> Result<CheatList, String> {
+        let file_handle = match File::open(file_path) {
+            Ok(file) => file,
+            _ => return Err(format!("error : could not open cheat file '{}'!", file_path))
+        };
+
+        let mut lines = BufReader::new(file_handle).lines().map_while(Result::ok);
+
+        if lines.next().as_deref() != Some(Self::HEADER) {
+            return Err(String::from("error : not a rip8 cheat file!"))
+        }
+
+        let mut cheat_list = CheatList::new(0);
+
+        for line in lines {
+            let mut words = line.split_whitespace();
+            let (kind, address, value) = match (words.next(), words.next(), words.next()) {
+                (None, _, _) => continue,
+                (Some("rom_hash"), Some(hash), None) => {
+                    cheat_list.rom_hash = match u64::from_str_radix(hash, 16) {
+                        Ok(hash) => hash,
+                        Err(_) => return Err(format!("error : bad rom hash in cheat file '{}'!", line))
+                    };
+                    continue
+                },
+                (Some(kind), Some(address), Some(value)) => (kind, address, value),
+                _ => return Err(format!("error : bad line in cheat file '{}'!", line))
+            };
+
+            let frozen = match kind {
+                "freeze" => true,
+                "patch" => false,
+                _ => return Err(format!("error : bad line in cheat file '{}'!", line))
+            };
+
+            let (address, value) = match (usize::from_str_radix(address, 16), u8::from_str_radix(value, 16)) {
+                (Ok(address), Ok(value)) => (address, value),
+                _ => return Err(format!("error : bad address or value in cheat file '{}'!", line))
+            };
+
+            cheat_list.cheats.push(Cheat { address, value, frozen, name : words.collect::<Vec<&str>>().join(" ") });
+        }
+
+        Ok(cheat_list)
+    }
+
+    /// this fn saves the cheats where load_for_rom will find them and gives back the file path
+    pub fn save(&self) -> Result<String, String> {
+        let file_path = Self::file_path(self.rom_hash);
+
+        if std::fs::create_dir_all(Self::CHEAT_FOLDER).is_err() {
+            return Err(format!("error : could not create the '{}' folder!", Self::CHEAT_FOLDER))
+        }
+
+        let mut text = format!("{}\nrom_hash {:016x}\n", Self::HEADER, self.rom_hash);
+        for cheat in self.cheats.iter() {
+            let kind = if cheat.frozen { "freeze" } else { "patch" };
+            let line = format!("{} {:03x} {:02x} {}", kind, cheat.address, cheat.value, cheat.name);
+            text += line.trim_end();
+            text += "\n";
+        }
+
+        match File::create(&file_path).and_then(|mut file| file.write_all(text.as_bytes())) {
+            Ok(_) => Ok(file_path),
+            _ => Err(format!("error : could not write cheat file '{}'!", file_path))
+        }
+    }
+
+    /// this fn writes the patches into memory, this should happen right after the rom is loaded
+    pub fn apply_patches(&self, chip8 : &mut Chip8) {
+        self.apply(chip8, false)
+    }
+
+    /// this fn writes the frozen cheats into memory, this should happen every frame
+    pub fn apply_frozen(&self, chip8 : &mut Chip8) {
+        self.apply(chip8, true)
+    }
+
+    // cheats write memory directly instead of through write_memory so they don't set off watchpoints
+    fn apply(&self, chip8 : &mut Chip8, frozen : bool) {
+        for cheat in self.cheats.iter().filter(|cheat| cheat.frozen == frozen) {
+            if let Some(byte) = chip8.memory.get_mut(cheat.address) {
+                *byte = cheat.value;
+            }
+        }
+    }
+}
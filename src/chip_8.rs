@@ -1,22 +1,21 @@
 use rand::{
     Rng, 
+    SeedableRng,
     thread_rng, 
-    rngs::ThreadRng
 };
 
-use std::{
-    fs::File,
-    io::{
-        BufRead,
-        Read,
-        BufReader
-    },
-    vec,
-};
+use rand_chacha::ChaCha8Rng;
+
+use std::vec;
 
 use crate::{
+    Coverage,
     Keyboard,
-    Font
+    Font,
+    Quirks,
+    Watchpoint,
+    WatchHit,
+    rom
 };
 
 pub enum BinaryOp {
@@ -25,6 +24,8 @@ pub enum BinaryOp {
     Xor,
 }
 
+/// a chip8 can be cloned to branch off a copy that carries on exactly the same, rng included
+#[derive(Clone)]
 pub struct Chip8 {
     // memory for the chip8 should be 4k
     // since I am using u16s for the instructions the size of the vector is 4096 / 2 = 2048
@@ -55,61 +56,106 @@ pub struct Chip8 {
     pub keyboard : Keyboard,
 
     // rng for the rng function
-    pub rng : ThreadRng,
+    // this is seeded so that a session can be replayed exactly from an input movie
+    pub rng : ChaCha8Rng,
+
+    // the seed the rng was created with
+    pub seed : u64,
 
     // font data
     pub font : Font,
+
+    // which behavior the chip8 has for the instructions interpreters disagree on
+    pub quirks : Quirks,
+
+    // with the vblank quirk a sprite being drawn stops the chip8 until the timers update at the next frame
+    pub waiting_for_vblank : bool,
+
+    // the memory ranges being watched by a debugger
+    pub watchpoints : Vec<Watchpoint>,
+
+    // the watchpoints the last instruction hit, while there are any the chip8 is paused
+    pub watch_hits : Vec<WatchHit>,
+
+    // the instruction at the pc already paused the chip8 on an execute watchpoint, so it runs next without pausing again
+    pub resuming_execute : bool,
+
+    // what memory the rom has run, read and written, this is only counted while it is some
+    pub coverage : Option<Coverage>,
 }
 
 impl Chip8 {
 
 
-    const SCREEN_HEIGHT : usize = 32;
-    const SCREEN_WIDTH : usize = 64;
+    pub const SCREEN_HEIGHT : usize = 32;
+    pub const SCREEN_WIDTH : usize = 64;
     const PROGRAM_MEMORY_SIZE : usize = 4096;
 
-    // 16bit bitmasks
-    const FXXX_BITMASK : u16 = 0xF000;
-    const XFXX_BITMASK : u16 = 0x0F00;
-    const XXFX_BITMASK : u16 = 0x00F0;
-    const XXXF_BITMASK : u16 = 0x000F;
-    const XFFF_BITMASK : u16 = 0x0FFF;
-    const XXFF_BITMASK : u16 = 0x00FF;
+    // where roms are loaded and start running, everything before this is left for the font
+    pub const PROGRAM_START : u16 = 512;
+
+    // how many calls deep the stack goes, the same as the cosmac vip
+    pub const STACK_SIZE : usize = 16;
+
+    // the biggest rom that fits in memory
+    pub const MAX_ROM_SIZE : usize = Self::PROGRAM_MEMORY_SIZE - Self::PROGRAM_START as usize;
 
     // 8bit bitmasks
     const BIT1_BITMASK : u8 = 0b0000_0001;
     const BIT8_BITMASK : u8 = 0b1000_0000;
 
     pub fn new() -> Chip8 {
+        Self::with_seed(thread_rng().gen())
+    }
+
+    /// this fn creates a chip8 with the rng seeded with a given seed
+    /// 
+    /// two chip8s with the same seed, rom and input will always produce the same results
+    pub fn with_seed(seed : u64) -> Chip8 {
         Chip8 {
             memory : vec![0; Self::PROGRAM_MEMORY_SIZE],
             display_buffer : vec![vec![false; Self::SCREEN_WIDTH]; Self::SCREEN_HEIGHT],
             stack : Vec::new(),
-            pc_reg : 512,
+            pc_reg : Self::PROGRAM_START,
             index_reg : 0,
             delay_timer_register : 0,
             sound_timer_register : 0,
             v_regs : vec![0; 16],
             keyboard : Keyboard::None,
-            rng : thread_rng(),
-            font : Font::new_standard()
+            rng : ChaCha8Rng::seed_from_u64(seed),
+            seed,
+            font : Font::new_standard(),
+            quirks : Quirks::new(),
+            waiting_for_vblank : false,
+            watchpoints : Vec::new(),
+            watch_hits : Vec::new(),
+            resuming_execute : false,
+            coverage : None,
         }
     }
 
 
-    /// this fn will load a rom from a binary file into the chip 8's memory
-    /// 
-    /// TODO : handle rom being to large
-    pub fn load_rom_from_bin(&mut self, file_path : &String) {
-        let file = BufReader::new(File::open(file_path).unwrap());
-        let mut index = self.pc_reg as usize;
-        for i in file.bytes() {
-            match i {
-                Ok(byte) => self.memory[index] = byte,
-                _ => break
-            }
-            index += 1
+    /// this fn will load a rom from its bytes into the chip 8's memory at location 512
+    ///
+    /// this is what every other way of loading a rom ends up calling, it doesn't need a file so roms can be embedded,
+    /// EX : chip8.load_rom(include_bytes!("../roms/pong.ch8"))
+    pub fn load_rom(&mut self, rom : &[u8]) -> Result<(), String> {
+        if rom.len() > Self::MAX_ROM_SIZE {
+            return Err(format!("error : the rom is {} bytes but there is only room for {} bytes!", rom.len(), Self::MAX_ROM_SIZE))
         }
+
+        let start = Self::PROGRAM_START as usize;
+        self.memory[start..start + rom.len()].copy_from_slice(rom);
+
+        Ok(())
+    }
+
+    /// this fn will load a rom from a binary file into the chip 8's memory
+    ///
+    /// the path can also be '-' for stdin, a .zip or a gzipped rom, see rom::read_rom
+    pub fn load_rom_from_bin(&mut self, file_path : &str) -> Result<(), String> {
+        let rom = rom::read_rom(file_path)?;
+        self.load_rom(&rom)
     }
 
     ///  this fn will load a rom at location 512 in memory to allow for font and sprite space at the beggining of memory
@@ -128,41 +174,21 @@ impl Chip8 {
     /// ;; draw the font char
     /// d015
     pub fn load_rom_from_radix(&mut self, file_path : &String) -> Result<(), String>{
-        let file_open_result = File::open(file_path);
-
-        let file_handle = match file_open_result {
-            Ok(file) => { file } 
-            _ => { return Err(String::from("error : could not load file at specified path!")) } 
+        let text = match std::fs::read_to_string(file_path) {
+            Ok(text) => text,
+            _ => return Err(String::from("error : could not load file at specified path!"))
         };
-        
-        // the rom will be loaded and started at location 512
-        let mut index = self.pc_reg as usize;
-        
-        let reader = BufReader::new(file_handle);
-        for (_line_index, line) in reader.lines().enumerate() {
-            if index > Self::PROGRAM_MEMORY_SIZE - 1 {
-                return Err(String::from("error : your program is too large! 4096 bytes of program memory maximum"))
-            }
 
-            let line = line.unwrap();
+        let program = rom::parse_radix(&text)?;
 
-            if line.starts_with(";;") || line.is_empty() {
-                continue
-            }
-
-            // figure out why this has to be 16 in the second arg
-            let instruction_result = u16::from_str_radix(&line, 16);
-            
-            let instruction = match instruction_result {
-                Ok(hex_number) => { hex_number }
-                _ => { return Err(String::from("error : instructions must be in radix hex like 'XXXX' !")) }
-            };
-
-            self.memory[index as usize] = (instruction >> 8) as u8; 
-            self.memory[index as usize + 1] = (instruction & Self::XXFF_BITMASK) as u8; 
-            index += 2;
+        // the rom will be loaded and started at location 512
+        let start = self.pc_reg as usize;
+        if start + program.len() > Self::PROGRAM_MEMORY_SIZE {
+            return Err(String::from("error : your program is too large! 4096 bytes of program memory maximum"))
         }
 
+        self.memory[start..start + program.len()].copy_from_slice(&program);
+
         Ok(())
     }
 
@@ -182,15 +208,24 @@ impl Chip8 {
     /// 
     /// will return false if there is no memory left to read
     pub fn processor_frame(&mut self, keyboard : Keyboard) -> bool {
-        if self.pc_reg as usize > Self::PROGRAM_MEMORY_SIZE - 1 { 
+        // an instruction is two bytes so the last byte of memory can't start one
+        if self.pc_reg as usize + 1 > Self::PROGRAM_MEMORY_SIZE - 1 { 
             return false;
         }
         
-        let instruction = self.fetch();
-
         self.keyboard = keyboard;
 
-        self.decode_and_execute(instruction);
+        if self.waiting_for_vblank || self.hit_watchpoint() {
+            return true;
+        }
+
+        // this is only worth doing when something is being watched, it is checked before every instruction
+        if !self.watchpoints.is_empty() && self.check_execute_watchpoints() {
+            return true;
+        }
+
+        let opcode = self.fetch_instruction();
+        self.decode_and_execute(opcode);
 
         true
     }
@@ -203,159 +238,23 @@ impl Chip8 {
 
     /// this is a function that will debug and execute a single instruction
     /// 
-    /// if the instruction does not match anything in the specified instruction list then it will act as a NOP
+    /// if the instruction does not match anything in the specified instruction list then the chip8 stays on it
     pub fn decode_and_execute(&mut self, instruction : u16) {
-        match instruction {
-            // 0x00E0 (clear screen) 
-            0x00E0 => self.clear_display_instruction(),
-
-            // 0x1NNN (jumps the program counter to a specific location)
-            i if i & Self::FXXX_BITMASK == 0x1000 => {
-                // call the jump instruction with NNN from 1NNN to tell the program counter where to jump
-                let location = i & Self::XFFF_BITMASK;
-                self.jump_instruction(location)
-            }
-
-            // 0x6XNN(set register VX)
-            i if i & Self::FXXX_BITMASK == 0x6000 => {
-                //
-                let reg = ((i & Self::XFXX_BITMASK) >> 8) as usize;
-                let num = (i & Self::XXFF_BITMASK) as u8;
-                self.set_vx_reg_instruction(reg, num);
-            }
-
-            // ---- TODO MAKE SURE THIS COVERS ADD WITH CARRY ----
-            //
-            // add value to register vx0
-            i if i & Self::FXXX_BITMASK == 0x7000 => {
-                //
-                let reg = (i & Self::XFXX_BITMASK) >> 8;
-                let num = i & Self::XXFF_BITMASK;
-                self.add_reg_vx_instruction(reg as usize, num as u8, false);
-            }
-
-            // set index register i instruction
-            i if i & Self::FXXX_BITMASK == 0xA000 => {
-                let number = i & Self::XFFF_BITMASK;
-                self.set_index_reg_instruction(number)
-            }
-            
-            // draw sprite instruction
-            i if i & Self::FXXX_BITMASK == 0xD000 => {
-                let x_coordinate = self.v_regs[((i & Self::XFXX_BITMASK) >> 8) as usize];
-                let y_coordinate = self.v_regs[((i & Self::XXFX_BITMASK) >> 4) as usize];
-                let sprite_height = (i & Self::XXXF_BITMASK) as u8;
-                self.draw_sprite_instruction(x_coordinate, y_coordinate, sprite_height);
-            },
-
-            // return instruction
-            0x00EE => self.return_instruction(),
-
-            // call instruction
-            i if i & Self::FXXX_BITMASK == 0x2000 => { 
-                let location = i & Self::XFFF_BITMASK;
-                self.call_instruction(location);
-            }
-
-            // for instructions : 3XNN 4XNN 5XY0 9XY0
-            i if i & Self::FXXX_BITMASK == 0x3000 || 
-                 i & Self::FXXX_BITMASK == 0x4000 || 
-                 i & Self::FXXX_BITMASK == 0x5000 || 
-                 i & Self::FXXX_BITMASK == 0x9000 || 
-                 i & Self::FXXX_BITMASK == 0xE000 => {
-                
-                // the first reg value in the instruction will be the value of the register with the index of the 2nd nybble in the instruction
-                let first_reg_value = self.v_regs[((i & Self::XFXX_BITMASK) >> 8) as usize];
-                
-                // the second reg value in the instruction will be the value of the register with the index of the 3rd nybble in the instruction
-                let second_reg_value = self.v_regs[((i & Self::XXFX_BITMASK) >> 4) as usize] as u8;
-
-                // keyboard keycode
-                let key_code = self.keyboard.get_keycode();
-
-                // the number to compare the first register too will be the last 2 nybbles in the instruction
-                let num = (i & Self::XXFF_BITMASK) as u8;
-
-                // these instructions are almost all the same and have one function for them
-                // the first item in each tuple returned here is the skip amount
-                // the second item in each tuple is weather the skip should occur if the reg and number are the same or not
-                match i & Self::FXXX_BITMASK {
-                    0x3000 => self.skipif_vx_reg_nn_instruction(first_reg_value, num, true),
-                    0x4000 => self.skipif_vx_reg_nn_instruction(first_reg_value, num, false),
-                    0x5000 => self.skipif_vx_reg_nn_instruction(first_reg_value, second_reg_value, true),
-                    0x9000 => self.skipif_vx_reg_nn_instruction(first_reg_value, second_reg_value, false),
-
-                    // TODO FIX THIS SOMETHING IS WRONG HERE
-                    j if j == 0xE000 => match i & Self::XXFF_BITMASK {
-                        0x009E => self.skipif_vx_reg_nn_instruction(first_reg_value, key_code, true),
-                        0x00A1 => self.skipif_vx_reg_nn_instruction(first_reg_value, key_code, false),
-                        _ => {}
-                    },
-
-                    _ => {}
-                };
-            }
-
-            i if i & Self::FXXX_BITMASK == 0x8000 => {
-                let reg = ((i & Self::XFXX_BITMASK) >> 8) as usize;
-                let num = self.v_regs[((i & Self::XXFX_BITMASK) >> 4) as usize];
-
-                match i & Self::XXXF_BITMASK {
-                    0x0000 => self.set_vx_reg_instruction(reg, num),
-
-                    // binary operation instructions
-                    0x0001 => self.bin_op_vx_reg_instruction(reg, num, BinaryOp::Or),
-                    0x0002 => self.bin_op_vx_reg_instruction(reg, num, BinaryOp::And),
-                    0x0003 => self.bin_op_vx_reg_instruction(reg, num, BinaryOp::Xor),
-                    // add with carry
-                    0x0004 => self.add_reg_vx_instruction(reg, num, true),
-                    // subtract with carry
-                    0x0005 => self.subtract_vx_reg_instruction(reg, num, false),
-                    // right shift instruction
-                    0x0006 => self.shift_vx_register(reg, true),
-                    // subtract with carry backwards
-                    0x0007 => self.subtract_vx_reg_instruction(reg, num, true),
-                    // left shift instruction
-                    0x000E => self.shift_vx_register(reg, false),
-                    _ => {}
-                }
-            }
-
-            i if i & Self::FXXX_BITMASK == 0xB000 => {
-                let reg = ((i & Self::XFXX_BITMASK) >> 8) as usize;
-                let offset = i & Self::XXFF_BITMASK;
-                self.jump_with_offset_instruction(reg, offset)
-            }
-
-            i if i & Self::FXXX_BITMASK == 0xC000 => {
-                let reg = ((i & Self::XFXX_BITMASK) >> 8) as usize;
-                let num = (i & Self::XXFF_BITMASK) as u8;
-                self.random_instruction(reg, num);
-            }
-
-            i if i & Self::FXXX_BITMASK == 0xF000 => {
-                let reg = ((i & Self::XFXX_BITMASK) >> 8) as usize;
-
-                match i & Self::XXFF_BITMASK {
-                    0x0007 => self.set_vx_reg_instruction(reg, self.delay_timer_register),
-                    0x0015 => self.set_delay_timer_reg_instruction(self.v_regs[reg]),
-                    0x0018 => self.set_sound_timer_reg_instruction(self.v_regs[reg]),
-                    0x001E => self.add_to_index_reg_instruction(self.v_regs[reg] as u16),
-                    0x000A => self.get_key_instruction(reg),
-                    0x0029 => self.set_index_to_font_char_instruction(self.v_regs[reg] as usize),
-                    0x0033 => self.bcd_instruction(reg),
-                    0x0055 => self.store_to_memory_instruction(reg),
-                    0x0065 => self.load_from_memory_instruction(reg),
-                    _ => {}
-                }
-            }
+        Self::OPCODE_HANDLERS[(instruction >> 12) as usize](self, instruction)
+    }
 
-            _ => {}
-        }
+    /// this fn gives back the address an instruction reaches with the index register and an offset
+    ///
+    /// it wraps around the end of memory like the 12 bit addresses of the chip8 do, so I near 0xFFF can't read or
+    /// write past memory
+    pub(crate) fn index_address(&self, offset : usize) -> usize {
+        (self.index_reg as usize + offset) % self.memory.len()
     }
 
     /// this fn is for updating the timers so that the timers can decrement once per frame which is detached from the chip8 clock 
     pub fn update_timers(&mut self) {
+        self.waiting_for_vblank = false;
+
         if self.delay_timer_register != 0 {
             self.delay_timer_register -= 1
         }
@@ -442,9 +341,12 @@ impl Chip8 {
         let x_coordinate = x_coordinate % Self::SCREEN_WIDTH as u8;
         let y_coordinate = y_coordinate % Self::SCREEN_HEIGHT as u8;
 
+        // vf ends up as 1 if any pixel of the sprite turned a pixel off and 0 if none did
+        self.v_regs[0xf] = 0;
+
         // this is the outer loop for the rows of the sprite
         'rows : for y_offset in 0..sprite_height {
-            let sprite_row = self.memory[(self.index_reg + y_offset as u16) as usize];
+            let sprite_row = self.read_memory(self.index_address(y_offset as usize));
 
             // because the rows are drawn on a display buffer that has the largest number to the right of the screen,
             // and the bytes are stored with the largest bits to the left (big endian),
@@ -465,6 +367,13 @@ impl Chip8 {
                 let x_coordinate = x_coordinate + x_offset;
                 let y_coordinate = y_coordinate + y_offset;
                 
+                // without the clip quirk the sprite wraps around to the other side of the screen
+                let (x_coordinate, y_coordinate) = if self.quirks.clip {
+                    (x_coordinate, y_coordinate)
+                } else {
+                    (x_coordinate % Self::SCREEN_WIDTH as u8, y_coordinate % Self::SCREEN_HEIGHT as u8)
+                };
+
                 // if the x coordinate is greater than or equal to the screen width stop drawing the current column
                 if x_coordinate >= Self::SCREEN_WIDTH as u8 {
                     break 'columns;
@@ -477,7 +386,6 @@ impl Chip8 {
                 // if there was a pixel already shaded on that position then set the shade to false and the vf flag reg to true
                 // else shade that pixel
                 } else if bit != 0 {
-                    self.v_regs[0xf] = 0;
                     if self.display_buffer[(y_coordinate) as usize][(x_coordinate) as usize] {
                         self.v_regs[0xf] = 1;
                         self.display_buffer[(y_coordinate) as usize][(x_coordinate) as usize] = false;
@@ -487,24 +395,34 @@ impl Chip8 {
                 }
             }
         }
+
+        self.waiting_for_vblank = self.quirks.vblank;
         self.pc_reg += 2
     }
     
+    /// this fn returns from a subroutine to the address on the top of the stack
     ///
+    /// a return with nothing on the stack has nowhere to go so the chip8 stays on it, the same as a rom ending in a
+    /// jump to itself
     /// 
-    /// for instructions : EE00
+    /// for instructions : 00EE
     pub fn return_instruction(&mut self) {
-        let return_address = self.stack.pop();
-        if let None = return_address {
-            panic!("error : stack underflow");
+        if let Some(return_address) = self.stack.pop() {
+            self.pc_reg = return_address;
         }
-        self.pc_reg = return_address.unwrap();
     }
 
-    /// 
+    /// this fn calls a subroutine and puts the address after the call on the stack
+    ///
+    /// a call with the stack already full stays on it the same way a return with nothing on the stack does, so a rom
+    /// that calls itself forever can't grow the stack forever
     /// 
     /// for instructions : 2NNN 
     pub fn call_instruction(&mut self, location : u16) {
+        if self.stack.len() >= Self::STACK_SIZE {
+            return
+        }
+
         // + 2 to make sure that it executes the NEXT instruction once a return is hit
         self.stack.push(self.pc_reg + 2);
         self.pc_reg = location;
@@ -543,6 +461,11 @@ impl Chip8 {
             // this MIGHT be wrong check later (probably fine tho because I think there is only binary xor no logical)
             BinaryOp::Xor => self.v_regs[reg] ^ num,
         };
+
+        if self.quirks.logic {
+            self.v_regs[0xf] = 0;
+        }
+
         self.pc_reg += 2
     }
 
@@ -550,16 +473,15 @@ impl Chip8 {
     /// 
     /// for instructions : 8XY4 7XNN 
     pub fn add_reg_vx_instruction(&mut self, reg : usize, num : u8, carry : bool) {
-        // wrapping add will add and account for overflows
+        // overflowing add will add and say if it carried
+        let (result, carried) = self.v_regs[reg].overflowing_add(num);
+        self.v_regs[reg] = result;
+
+        // the flag is set after the result so vf holds the flag when vf is the register being added to
         if carry {
-            match self.v_regs[reg].checked_add(num) {
-                Some(_number) => self.v_regs[0xf] = 0,
-                _ => self.v_regs[0xf] = 1
-            }
+            self.v_regs[0xf] = carried as u8;
         }
 
-        self.v_regs[reg] = self.v_regs[reg].wrapping_add(num);
-
         self.pc_reg += 2
     }
 
@@ -567,56 +489,51 @@ impl Chip8 {
     /// 
     /// for instructions : 8XY5 8XY7
     pub fn subtract_vx_reg_instruction(&mut self, reg : usize, num : u8, flipped : bool) {
-        self.v_regs[0xf] = 1; 
-        self.v_regs[reg] = if flipped {
-            match num.checked_sub(self.v_regs[reg]) {
-                Some(result) => result,
-                None => {
-                    self.v_regs[0xf] = 0; 
-                    num.wrapping_sub(self.v_regs[reg])
-                }
-            }
-        } else {
-            match self.v_regs[reg].checked_sub(num) {
-                Some(result) => result, 
-                None => {
-                    self.v_regs[0xf] = 0; 
-                    self.v_regs[reg].wrapping_sub(num)
-                }
-            }
-        };
+        let (minuend, subtrahend) = if flipped { (num, self.v_regs[reg]) } else { (self.v_regs[reg], num) };
+
+        // vf is 1 when there was no borrow, it is set after the result the same as with add
+        self.v_regs[reg] = minuend.wrapping_sub(subtrahend);
+        self.v_regs[0xf] = (minuend >= subtrahend) as u8;
 
         self.pc_reg += 2
     }
 
     /// this fn binary shifts the value in reg right and left
+    ///
+    /// num is the value of vy which gets shifted into reg unless the shift quirk is on
     /// 
     /// for instructions : 8XY6 8XYE
-    pub fn shift_vx_register(&mut self, reg : usize, right_shift : bool) {
-        self.v_regs[reg] = if right_shift {
-            self.v_regs[0xf] = self.v_regs[reg] & Self::BIT1_BITMASK;
-            self.v_regs[reg] >> 1
+    pub fn shift_vx_register(&mut self, reg : usize, num : u8, right_shift : bool) {
+        let value = if self.quirks.shift { self.v_regs[reg] } else { num };
+
+        // vf gets the bit that was shifted out as a 0 or 1, after the result the same as with add
+        let (result, shifted_out) = if right_shift {
+            (value >> 1, value & Self::BIT1_BITMASK)
         } else {
-            self.v_regs[0xf] = self.v_regs[reg] & Self::BIT8_BITMASK;
-            self.v_regs[reg] << 1
+            (value << 1, (value & Self::BIT8_BITMASK) >> 7)
         };
 
+        self.v_regs[reg] = result;
+        self.v_regs[0xf] = shifted_out;
+
         self.pc_reg += 2
     }
 
-    /// this fn will jump to a given location with the offset of whatever is in the given register usually v0
+    /// this fn will jump to a given location with the offset of whatever is in v0
+    ///
+    /// with the jump quirk the offset comes from vx instead, where x is the top nybble of the location
     /// 
-    /// for instructions : BXNN
-    pub fn jump_with_offset_instruction(&mut self, reg : usize, offset : u16) {
-        self.pc_reg += offset;
-        self.pc_reg += self.v_regs[reg] as u16; 
+    /// for instructions : BNNN BXNN
+    pub fn jump_with_offset_instruction(&mut self, reg : usize, location : u16) {
+        let reg = if self.quirks.jump { reg } else { 0 };
+        self.pc_reg = location + self.v_regs[reg] as u16;
     }
 
     /// this fn sets reg vx to a random number
     /// 
     /// for instructions : cxnn
     pub fn random_instruction(&mut self, reg : usize, num : u8) {
-        self.v_regs[reg] = self.rng.gen_range(0..u8::MAX) & num;
+        self.v_regs[reg] = self.rng.gen::<u8>() & num;
         self.pc_reg += 2
     }
 
@@ -633,15 +550,14 @@ impl Chip8 {
     /// for instructions fx33
     pub fn bcd_instruction(&mut self, reg : usize) {
         let reg_val = self.v_regs[reg];
-        let index = self.index_reg as usize;
 
         let first_byte_hundreds = reg_val / 100;
         let second_byte_tens = (reg_val - (first_byte_hundreds * 100) ) / 10;
         let third_byte_ones = (reg_val - (first_byte_hundreds * 100) - (second_byte_tens * 10) ) / 1;
 
-        self.memory[index] = first_byte_hundreds;
-        self.memory[index + 1] = second_byte_tens;
-        self.memory[index + 2] = third_byte_ones;
+        self.write_memory(self.index_address(0), first_byte_hundreds);
+        self.write_memory(self.index_address(1), second_byte_tens);
+        self.write_memory(self.index_address(2), third_byte_ones);
         
         self.pc_reg += 2
     }
@@ -652,11 +568,15 @@ impl Chip8 {
     /// 
     /// for instructions fx55
     pub fn store_to_memory_instruction(&mut self, reg : usize) {
-        for (i, val) in self.v_regs.iter().enumerate() {
-            self.memory[self.index_reg as usize + i] = *val;
+        for i in 0..self.v_regs.len() {
+            self.write_memory(self.index_address(i), self.v_regs[i]);
             if i == reg { break }
         }
 
+        if !self.quirks.load_store {
+            self.index_reg = self.index_reg.wrapping_add(reg as u16 + 1);
+        }
+
         self.pc_reg += 2
     }
 
@@ -666,12 +586,12 @@ impl Chip8 {
     /// 
     /// for instructions fx65
     pub fn load_from_memory_instruction(&mut self, reg : usize) {
-        if self.v_regs[0] == 0 {
-            self.v_regs[reg] = self.memory[self.index_reg as usize]
-        } else {
-            for i in self.index_reg as usize..=self.index_reg as usize + reg  {
-                self.v_regs[i - self.index_reg as usize] = self.memory[i]
-            }
+        for i in 0..=reg {
+            self.v_regs[i] = self.read_memory(self.index_address(i))
+        }
+
+        if !self.quirks.load_store {
+            self.index_reg = self.index_reg.wrapping_add(reg as u16 + 1);
         }
 
         self.pc_reg += 2
@@ -9,29 +9,26 @@ use sdl2::{
     keyboard::Keycode, AudioSubsystem, audio::{AudioCallback, AudioSpecDesired, AudioDevice}
 };
 
-use crate::Keyboard;
+use crate::{ Keyboard, Keymap, Hotkey, AudioConfig, SharedSound, ToneGenerator, Palette, Frontend, screenshot };
 
 static OFF_COLOR : Color = Color::RGB(255,255,255);
 static ON_COLOR : Color = Color::RGB(0,0,0);
 
-pub struct SquareWave {
-    phase_inc: f32,
-    phase: f32,
-    volume: f32
+pub struct Buzzer {
+    tone : ToneGenerator,
+    sound : SharedSound,
 }
 
-impl AudioCallback for SquareWave {
+impl AudioCallback for Buzzer {
     type Channel = f32;
 
     fn callback(&mut self, out: &mut [f32]) {
-        // Generate a square wave
+        // the sound timer is checked once per buffer, the tone generator counts the samples from there
+        self.tone.set_sound_timer(self.sound.sound_timer());
+        self.tone.muted = self.sound.is_muted();
+
         for x in out.iter_mut() {
-            *x = if self.phase <= 0.5 {
-                self.volume
-            } else {
-                -self.volume
-            };
-            self.phase = (self.phase + self.phase_inc) % 1.0;
+            *x = self.tone.next_sample();
         }
     }
 }
@@ -47,15 +44,24 @@ pub struct Chip8Window {
 
     pub audio_subsystem : AudioSubsystem,
     pub audio_spec : AudioSpecDesired,
-    pub audio_device : AudioDevice<SquareWave>
+    pub audio_device : AudioDevice<Buzzer>,
+
+    // the sound timer and mute state shared with the audio callback
+    pub sound : SharedSound,
+
+    // emulator controls pressed since the last time they were taken
+    pub hotkeys : Vec<Hotkey>,
+
+    // which keys are the chip8 keypad
+    pub keymap : Keymap,
 }
 
 impl Chip8Window {
     const SCREEN_WIDTH : usize = 64;
     const SCREEN_HEIGHT : usize = 32;
-    const PIXEL_SIZE : usize = 20;
+    pub const PIXEL_SIZE : usize = screenshot::WINDOW_PIXEL_SIZE;
 
-    pub fn new() -> Chip8Window {
+    pub fn new(audio_config : AudioConfig) -> Chip8Window {
         let sdl_context = sdl2::init().unwrap();
         
         let video_subsystem = sdl_context.video().unwrap();
@@ -67,15 +73,19 @@ impl Chip8Window {
             samples: None
         };
 
+        let sound = SharedSound::new();
+
         let audio_device = audio_subsystem.open_playback(None, &audio_spec, |spec| {
             // audio callback
-            SquareWave {
-                phase_inc: 440.0 / spec.freq as f32,
-                phase: 0.0,
-                volume: 0.25
+            Buzzer {
+                tone : ToneGenerator::new(audio_config, spec.freq as u32),
+                sound : sound.clone(),
             }
         }).unwrap();
 
+        // the device is always playing, the buzzer is silent whenever the sound timer is 0
+        audio_device.resume();
+
         let window = video_subsystem.window(
             "rip8", 
             (Self::SCREEN_WIDTH * Self::PIXEL_SIZE) as u32,
@@ -100,6 +110,10 @@ impl Chip8Window {
             audio_subsystem : audio_subsystem,
             audio_spec : audio_spec,
             audio_device : audio_device,
+            sound : sound,
+
+            hotkeys : Vec::new(),
+            keymap : Keymap::new(),
         }
     }
 
@@ -117,63 +131,92 @@ impl Chip8Window {
         }
     }
 
+    pub fn set_palette(&mut self, palette : Palette) {
+        self.off_color = Color::RGB(palette.off_color[0], palette.off_color[1], palette.off_color[2]);
+        self.on_color = Color::RGB(palette.on_color[0], palette.on_color[1], palette.on_color[2]);
+    }
 
+    /// this fn names a key the way the keymap does
+    fn key_name(keycode : Keycode) -> String {
+        match keycode {
+            Keycode::Up => String::from("ArrowUp"),
+            Keycode::Down => String::from("ArrowDown"),
+            Keycode::Left => String::from("ArrowLeft"),
+            Keycode::Right => String::from("ArrowRight"),
+            Keycode::Space => String::from(" "),
+            Keycode::Return => String::from("Enter"),
+            _ => keycode.name().to_lowercase()
+        }
+    }
+}
+
+impl Frontend for Chip8Window {
+    /// this fn gives back the colors the window is drawing with
+    fn palette(&self) -> Palette {
+        Palette {
+            off_color : [self.off_color.r, self.off_color.g, self.off_color.b],
+            on_color : [self.on_color.r, self.on_color.g, self.on_color.b],
+        }
+    }
+
+    /// this fn sets the text in the window's title bar
+    fn set_title(&mut self, title : &str) {
+        // the title can only fail to set if it has a nul byte in it
+        let _ = self.canvas.window_mut().set_title(title);
+    }
+
+    /// this fn gives back every hotkey that was pressed since the last time it was called
+    fn take_hotkeys(&mut self) -> Vec<Hotkey> {
+        std::mem::take(&mut self.hotkeys)
+    }
 
     // have this return a set of pressed keys back to the chip8
-    pub fn handle_input(&mut self) -> Keyboard {
+    //
+    // emulator hotkeys are not returned here, they get collected in self.hotkeys
+    fn handle_input(&mut self) -> Keyboard {
         for event in self.event_pump.poll_iter() {
             match event {
                 Event::Quit {..} |
                 Event::KeyDown { keycode: Some(Keycode::Escape), .. } => {
-                    std::process::exit(0);
-                },
-                Event::KeyDown { keycode: Some(Keycode::Num1), .. } => {
-                    return Keyboard::Key1
-                },
-                Event::KeyDown { keycode: Some(Keycode::Num2), .. } => {
-                    return Keyboard::Key2
-                },
-                Event::KeyDown { keycode: Some(Keycode::Num3), .. } => {
-                    return Keyboard::Key3
+                    self.hotkeys.push(Hotkey::Quit);
                 },
-                Event::KeyDown { keycode: Some(Keycode::Num4), .. } => {
-                    return Keyboard::KeyC
+                Event::KeyDown { keycode: Some(Keycode::P), repeat: false, .. } => {
+                    self.hotkeys.push(Hotkey::Pause);
                 },
-                Event::KeyDown { keycode: Some(Keycode::Q), .. } => {
-                    return Keyboard::Key4
+                Event::KeyDown { keycode: Some(Keycode::N), .. } => {
+                    self.hotkeys.push(Hotkey::FrameAdvance);
                 },
-                Event::KeyDown { keycode: Some(Keycode::W), .. } => {
-                    return Keyboard::Key5
+                Event::KeyDown { keycode: Some(Keycode::F5), repeat: false, .. } => {
+                    self.hotkeys.push(Hotkey::Reset);
                 },
-                Event::KeyDown { keycode: Some(Keycode::E), .. } => {
-                    return Keyboard::Key6
+                Event::KeyDown { keycode: Some(Keycode::Equals), .. } => {
+                    self.hotkeys.push(Hotkey::SpeedUp);
                 },
-                Event::KeyDown { keycode: Some(Keycode::R), .. } => {
-                    return Keyboard::KeyD
+                Event::KeyDown { keycode: Some(Keycode::Minus), .. } => {
+                    self.hotkeys.push(Hotkey::SlowDown);
                 },
-                Event::KeyDown { keycode: Some(Keycode::A), .. } => {
-                    return Keyboard::Key7
+                Event::KeyDown { keycode: Some(Keycode::M), repeat: false, .. } => {
+                    self.hotkeys.push(Hotkey::ToggleMute);
                 },
-                Event::KeyDown { keycode: Some(Keycode::S), .. } => {
-                    return Keyboard::Key8
+                Event::KeyDown { keycode: Some(Keycode::F12), repeat: false, .. } => {
+                    self.hotkeys.push(Hotkey::Screenshot { native : false });
                 },
-                Event::KeyDown { keycode: Some(Keycode::D), .. } => {
-                    return Keyboard::Key9
+                Event::KeyDown { keycode: Some(Keycode::F11), repeat: false, .. } => {
+                    self.hotkeys.push(Hotkey::Screenshot { native : true });
                 },
-                Event::KeyDown { keycode: Some(Keycode::F), .. } => {
-                    return Keyboard::KeyE
+                Event::KeyDown { keycode: Some(Keycode::F9), repeat: false, .. } => {
+                    self.hotkeys.push(Hotkey::ToggleCapture);
                 },
-                Event::KeyDown { keycode: Some(Keycode::Z), .. } => {
-                    return Keyboard::KeyA
+                Event::KeyDown { keycode: Some(Keycode::Tab), repeat: false, .. } => {
+                    self.hotkeys.push(Hotkey::FastForward(true));
                 },
-                Event::KeyDown { keycode: Some(Keycode::X), .. } => {
-                    return Keyboard::Key0
+                Event::KeyUp { keycode: Some(Keycode::Tab), .. } => {
+                    self.hotkeys.push(Hotkey::FastForward(false));
                 },
-                Event::KeyDown { keycode: Some(Keycode::C), .. } => {
-                    return Keyboard::KeyB
-                },
-                Event::KeyDown { keycode: Some(Keycode::V), .. } => {
-                    return Keyboard::KeyF
+                Event::KeyDown { keycode: Some(keycode), .. } => {
+                    if let Some(keyboard) = self.keymap.key_for(&Self::key_name(keycode)) {
+                        return keyboard
+                    }
                 },
                 _ => {}
             }
@@ -182,7 +225,7 @@ impl Chip8Window {
         Keyboard::None
     }
 
-    pub fn draw_canvas(&mut self, buffer : Vec<Vec<bool>>) {
+    fn draw_canvas(&mut self, buffer : &[Vec<bool>]) {
         self.canvas.set_draw_color(self.off_color);
         self.canvas.clear();
         
@@ -211,4 +254,12 @@ impl Chip8Window {
 
         self.canvas.present();
     }
+
+    fn set_sound_timer(&mut self, sound_timer : u8) {
+        self.sound.set_sound_timer(sound_timer);
+    }
+
+    fn set_muted(&mut self, muted : bool) {
+        self.sound.set_muted(muted);
+    }
 }
\ No newline at end of file
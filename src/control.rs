@@ -0,0 +1,315 @@
+use std::{
+    io::{
+        BufRead,
+        BufReader,
+        Write
+    },
+    net::{
+        TcpListener,
+        TcpStream
+    },
+    sync::mpsc::{
+        self,
+        Receiver,
+        Sender
+    },
+    thread,
+};
+
+use base64::Engine;
+use serde_json::{
+    json,
+    Value
+};
+
+use crate::{
+    Chip8,
+    Keyboard,
+    Palette,
+    ScreenshotView,
+    rom,
+    screenshot
+};
+
+/// these are the requests that change the emulation loop itself, so the loop does them instead of the control server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlAction {
+    Pause,
+    Continue,
+
+    // run this many frames and stay paused
+    StepFrames(u32),
+
+    Reset,
+
+    // start a different rom, these are its bytes
+    LoadRom(Vec<u8>),
+
+    Quit,
+}
+
+/// this is one request from a client along with where its reply goes
+struct ControlRequest {
+    request : Value,
+    reply : Sender<Value>,
+}
+
+/// what the emulation loop is doing, for the status request
+#[derive(Debug, Clone, Copy)]
+pub struct ControlStatus {
+    pub frame : usize,
+    pub paused : bool,
+    pub palette : Palette,
+}
+
+/// this is a json-rpc 2.0 server on localhost for driving the emulator from other programs like test tools or editors
+///
+/// every request is one line of json and gets one line back. clients are handled on their own threads and pass their
+/// requests to the emulation loop, which answers them between frames so the server never holds up drawing. EX :
+///
+/// ```text
+/// --> {"jsonrpc" : "2.0", "id" : 1, "method" : "read_memory", "params" : {"address" : 512, "length" : 4}}
+/// <-- {"jsonrpc" : "2.0", "id" : 1, "result" : {"bytes" : [18, 159, 252, 252]}}
+/// ```
+///
+/// the methods are :
+/// status, pause, continue, step {up to 600 frames or 100000 instructions}, reset, load_rom {path}, quit,
+/// set_key {key, null lets go}, read_memory {address, length}, write_memory {address, bytes},
+/// registers, screenshot {scale} which gives back a base64 png, save_state {path} and load_state {state or path}
+pub struct ControlServer {
+    receiver : Receiver<ControlRequest>,
+
+    // the key a client is holding down, none lets the frontend's keys through
+    pub keyboard : Option<Keyboard>,
+}
+
+impl ControlServer {
+    // json-rpc's error codes
+    const PARSE_ERROR : i64 = -32700;
+    const INVALID_REQUEST : i64 = -32600;
+    const METHOD_NOT_FOUND : i64 = -32601;
+    const INVALID_PARAMS : i64 = -32602;
+    const SERVER_ERROR : i64 = -32000;
+
+    // the most instructions one step request can run, they run between frames so too many would hold up drawing
+    pub const MAX_STEP_INSTRUCTIONS : u64 = 100_000;
+
+    // the most frames one step request can run, they all run before the next frame is drawn
+    pub const MAX_STEP_FRAMES : u64 = 600;
+
+    /// this fn starts listening on a localhost port and gives back right away, clients are accepted in the background
+    pub fn start(port : u16) -> Result<ControlServer, String> {
+        let listener = match TcpListener::bind(("127.0.0.1", port)) {
+            Ok(listener) => listener,
+            Err(error) => return Err(format!("error : could not start the control server on port {} : {}!", port, error))
+        };
+
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for stream in listener.incoming().map_while(Result::ok) {
+                let sender = sender.clone();
+                thread::spawn(move || Self::serve_client(stream, sender));
+            }
+        });
+
+        Ok(ControlServer { receiver, keyboard : None })
+    }
+
+    /// this fn reads requests from one client until it hangs up or the emulator stops
+    fn serve_client(stream : TcpStream, sender : Sender<ControlRequest>) {
+        let mut writer = match stream.try_clone() {
+            Ok(writer) => writer,
+            Err(_) => return
+        };
+
+        for line in BufReader::new(stream).lines().map_while(Result::ok) {
+            if line.trim().is_empty() {
+                continue
+            }
+
+            let response = match serde_json::from_str::<Value>(&line) {
+                Ok(request) => {
+                    let (reply, reply_receiver) = mpsc::channel();
+                    if sender.send(ControlRequest { request, reply }).is_err() {
+                        return
+                    }
+
+                    match reply_receiver.recv() {
+                        Ok(response) => response,
+                        Err(_) => return
+                    }
+                },
+                Err(error) => Self::error_response(Value::Null, Self::PARSE_ERROR, &error.to_string())
+            };
+
+            if writeln!(writer, "{}", response).is_err() {
+                return
+            }
+        }
+    }
+
+    /// this fn answers every request that came in since it was last called
+    ///
+    /// requests for the chip8 are answered right here and the ones for the emulation loop come back as actions,
+    /// which the loop should do before it calls this again so a client that waits for its reply sees them done
+    pub fn handle_requests(&mut self, chip8 : &mut Chip8, status : ControlStatus) -> Vec<ControlAction> {
+        let mut actions = Vec::new();
+
+        while let Ok(ControlRequest { request, reply }) = self.receiver.try_recv() {
+            let id = request.get("id").cloned().unwrap_or(Value::Null);
+
+            let response = match request.get("method").and_then(Value::as_str) {
+                Some(method) => {
+                    let params = request.get("params").cloned().unwrap_or(Value::Null);
+                    match self.handle_method(chip8, status, method, &params, &mut actions) {
+                        Ok(result) => json!({ "jsonrpc" : "2.0", "id" : id, "result" : result }),
+                        Err((code, message)) => Self::error_response(id, code, &message)
+                    }
+                },
+                None => Self::error_response(id, Self::INVALID_REQUEST, "the request has no method")
+            };
+
+            let _ = reply.send(response);
+        }
+
+        actions
+    }
+
+    fn handle_method(
+        &mut self,
+        chip8 : &mut Chip8,
+        status : ControlStatus,
+        method : &str,
+        params : &Value,
+        actions : &mut Vec<ControlAction>
+    ) -> Result<Value, (i64, String)> {
+        let invalid = |message : &str| (Self::INVALID_PARAMS, message.to_string());
+        let number = |name : &str| params.get(name).and_then(Value::as_u64);
+
+        let result = match method {
+            "status" => json!({ "frame" : status.frame, "paused" : status.paused }),
+            "pause" => {
+                actions.push(ControlAction::Pause);
+                json!(true)
+            },
+            "continue" => {
+                actions.push(ControlAction::Continue);
+                json!(true)
+            },
+            "step" => {
+                match (number("frames"), number("instructions")) {
+                    (_, Some(instructions)) if instructions > Self::MAX_STEP_INSTRUCTIONS => {
+                        return Err(invalid(&format!("step can run at most {} instructions at once", Self::MAX_STEP_INSTRUCTIONS)))
+                    },
+                    (_, Some(instructions)) => {
+                        // instructions run right away and leave the timers alone, frames go through the loop
+                        let keyboard = self.keyboard.unwrap_or(Keyboard::None);
+                        for _ in 0..instructions {
+                            if !chip8.processor_frame(keyboard) {
+                                break
+                            }
+                        }
+                        actions.push(ControlAction::Pause);
+                    },
+                    (Some(frames), None) if frames > Self::MAX_STEP_FRAMES => {
+                        return Err(invalid(&format!("step can run at most {} frames at once", Self::MAX_STEP_FRAMES)))
+                    },
+                    (frames, None) => actions.push(ControlAction::StepFrames(frames.unwrap_or(1) as u32))
+                }
+                json!(true)
+            },
+            "reset" => {
+                actions.push(ControlAction::Reset);
+                json!(true)
+            },
+            "load_rom" => {
+                let path = params.get("path").and_then(Value::as_str).ok_or_else(|| invalid("load_rom needs a path"))?;
+                let rom = rom::read_rom(path).map_err(|error| (Self::SERVER_ERROR, error))?;
+                actions.push(ControlAction::LoadRom(rom));
+                json!(true)
+            },
+            "quit" => {
+                actions.push(ControlAction::Quit);
+                json!(true)
+            },
+            "set_key" => {
+                self.keyboard = match params.get("key") {
+                    Some(Value::Null) | None => None,
+                    Some(key) => match key.as_u64() {
+                        Some(key) if key <= 0xF => Some(Keyboard::from_keycode(key as u8)),
+                        _ => return Err(invalid("key should be 0 to 15 or null"))
+                    }
+                };
+                json!(true)
+            },
+            "read_memory" => {
+                let address = number("address").ok_or_else(|| invalid("read_memory needs an address"))? as usize;
+                let length = number("length").unwrap_or(1) as usize;
+
+                match chip8.memory.get(address..address.saturating_add(length)) {
+                    Some(bytes) => json!({ "bytes" : bytes }),
+                    None => return Err(invalid("that is outside of memory"))
+                }
+            },
+            "write_memory" => {
+                let address = number("address").ok_or_else(|| invalid("write_memory needs an address"))? as usize;
+                let bytes = params.get("bytes")
+                    .and_then(Value::as_array)
+                    .and_then(|bytes| bytes.iter().map(|byte| byte.as_u64().filter(|byte| *byte <= 0xFF).map(|byte| byte as u8)).collect::<Option<Vec<u8>>>())
+                    .ok_or_else(|| invalid("write_memory needs bytes"))?;
+
+                chip8.poke(address, &bytes).map_err(|error| (Self::INVALID_PARAMS, error))?;
+                json!(true)
+            },
+            "registers" => json!({
+                "v" : chip8.v_regs,
+                "i" : chip8.index_reg,
+                "pc" : chip8.pc_reg,
+                "stack" : chip8.stack,
+                "delay_timer" : chip8.delay_timer_register,
+                "sound_timer" : chip8.sound_timer_register,
+            }),
+            "screenshot" => {
+                let view = match number("scale") {
+                    Some(scale) if scale > 0 => ScreenshotView::Scaled { palette : status.palette, pixel_size : scale as usize },
+                    Some(_) => return Err(invalid("scale should be at least 1")),
+                    None => ScreenshotView::Native,
+                };
+
+                let png = screenshot::encode_png(&chip8.display_buffer, view).map_err(|error| (Self::SERVER_ERROR, error))?;
+                json!({ "png" : base64::engine::general_purpose::STANDARD.encode(png) })
+            },
+            "save_state" => {
+                let state = chip8.save_state();
+
+                if let Some(path) = params.get("path").and_then(Value::as_str) {
+                    if std::fs::write(path, state.to_string()).is_err() {
+                        return Err((Self::SERVER_ERROR, format!("error : could not write save state '{}'!", path)))
+                    }
+                }
+
+                json!({ "state" : state })
+            },
+            "load_state" => {
+                let state = match (params.get("state"), params.get("path").and_then(Value::as_str)) {
+                    (Some(state), _) => state.clone(),
+                    (None, Some(path)) => std::fs::read_to_string(path).ok()
+                        .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+                        .ok_or_else(|| (Self::SERVER_ERROR, format!("error : could not read save state '{}'!", path)))?,
+                    (None, None) => return Err(invalid("load_state needs a state or a path"))
+                };
+
+                chip8.load_state(&state).map_err(|error| (Self::INVALID_PARAMS, error))?;
+                json!(true)
+            },
+            _ => return Err((Self::METHOD_NOT_FOUND, format!("there is no method '{}'", method)))
+        };
+
+        Ok(result)
+    }
+
+    fn error_response(id : Value, code : i64, message : &str) -> Value {
+        json!({ "jsonrpc" : "2.0", "id" : id, "error" : { "code" : code, "message" : message } })
+    }
+}
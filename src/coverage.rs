@@ -0,0 +1,245 @@
+use std::{
+    fs::File,
+    io::{
+        BufRead,
+        BufReader,
+        Write
+    },
+    path::Path,
+};
+
+use crate::{
+    Chip8,
+    MemoryAccess,
+    disassembler
+};
+
+/// this is what memory a rom used during a run, for finding the branches a test doesn't reach
+///
+/// it counts every instruction run at every address and every byte an instruction read or wrote. runs of the same
+/// rom can be merged so a coverage file can add up a whole set of replays. a coverage file looks like this, with the
+/// address in hex and then how many times it was run, read and written :
+///
+/// ```text
+/// rip8-coverage 1
+/// rom_hash 9f1c2b7a0e4d6c35
+/// 200 1 0 0
+/// 3f0 0 12 4
+/// ```
+#[derive(Debug, Clone)]
+pub struct Coverage {
+    pub rom_hash : u64,
+
+    // how many times an instruction started at every address
+    pub executed : Vec<u64>,
+
+    // how many times every address was read or written by an instruction, fetching instructions doesn't count
+    pub read : Vec<u64>,
+    pub written : Vec<u64>,
+}
+
+impl Coverage {
+    const HEADER : &'static str = "rip8-coverage 1";
+
+    pub fn new(rom_hash : u64, memory_size : usize) -> Coverage {
+        Coverage {
+            rom_hash,
+            executed : vec![0; memory_size],
+            read : vec![0; memory_size],
+            written : vec![0; memory_size],
+        }
+    }
+
+    /// this fn counts one access, the chip8 calls it from the same place it checks watchpoints
+    pub fn mark(&mut self, address : usize, access : MemoryAccess) {
+        let counts = match access {
+            MemoryAccess::Execute => &mut self.executed,
+            MemoryAccess::Read => &mut self.read,
+            MemoryAccess::Write => &mut self.written,
+        };
+
+        if let Some(count) = counts.get_mut(address) {
+            *count += 1;
+        }
+    }
+
+    /// this fn adds another run of the same rom into this one
+    pub fn merge(&mut self, other : &Coverage) -> Result<(), String> {
+        if other.rom_hash != self.rom_hash {
+            return Err(String::from("error : could not merge coverage of a different rom!"))
+        }
+
+        for (counts, other_counts) in [(&mut self.executed, &other.executed), (&mut self.read, &other.read), (&mut self.written, &other.written)] {
+            if counts.len() < other_counts.len() {
+                counts.resize(other_counts.len(), 0);
+            }
+            for (count, other_count) in counts.iter_mut().zip(other_counts.iter()) {
+                *count += other_count;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn load(file_path : &str) -> Result<Coverage, String> {
+        let file_handle = match File::open(file_path) {
+            Ok(file) => file,
+            _ => return Err(format!("error : could not open coverage file '{}'!", file_path))
+        };
+
+        let mut lines = BufReader::new(file_handle).lines().map_while(Result::ok);
+
+        if lines.next().as_deref() != Some(Self::HEADER) {
+            return Err(String::from("error : not a rip8 coverage file!"))
+        }
+
+        let mut coverage = Coverage::new(0, 0);
+
+        for line in lines {
+            let words = line.split_whitespace().collect::<Vec<&str>>();
+            match words.as_slice() {
+                [] => continue,
+                ["rom_hash", hash] => match u64::from_str_radix(hash, 16) {
+                    Ok(hash) => coverage.rom_hash = hash,
+                    Err(_) => return Err(format!("error : bad rom hash in coverage file '{}'!", line))
+                },
+                [address, executed, read, written] => {
+                    let parsed = (
+                        usize::from_str_radix(address, 16),
+                        executed.parse::<u64>(),
+                        read.parse::<u64>(),
+                        written.parse::<u64>()
+                    );
+
+                    match parsed {
+                        (Ok(address), Ok(executed), Ok(read), Ok(written)) if address < 0x10000 => {
+                            if coverage.executed.len() <= address {
+                                for counts in [&mut coverage.executed, &mut coverage.read, &mut coverage.written] {
+                                    counts.resize(address + 1, 0);
+                                }
+                            }
+
+                            coverage.executed[address] = executed;
+                            coverage.read[address] = read;
+                            coverage.written[address] = written;
+                        },
+                        _ => return Err(format!("error : bad line in coverage file '{}'!", line))
+                    }
+                },
+                _ => return Err(format!("error : bad line in coverage file '{}'!", line))
+            }
+        }
+
+        Ok(coverage)
+    }
+
+    /// this fn saves the coverage, if the file already has coverage of the same rom this run is added to it
+    pub fn save_merged(&self, file_path : &str) -> Result<(), String> {
+        let mut coverage = self.clone();
+        if Path::new(file_path).exists() {
+            coverage.merge(&Coverage::load(file_path)?)?;
+        }
+
+        coverage.save(file_path)
+    }
+
+    pub fn save(&self, file_path : &str) -> Result<(), String> {
+        let mut text = format!("{}\nrom_hash {:016x}\n", Self::HEADER, self.rom_hash);
+
+        for address in 0..self.executed.len() {
+            let counts = (self.executed[address], self.read[address], self.written[address]);
+            if counts != (0, 0, 0) {
+                text += &format!("{:03x} {} {} {}\n", address, counts.0, counts.1, counts.2);
+            }
+        }
+
+        match File::create(file_path).and_then(|mut file| file.write_all(text.as_bytes())) {
+            Ok(_) => Ok(()),
+            _ => Err(format!("error : could not write coverage file '{}'!", file_path))
+        }
+    }
+
+    /// this fn writes the rom's disassembly with how many times every instruction ran down the side
+    ///
+    /// code is found by following the rom from where it starts and by what actually ran, code that was never run is
+    /// marked with ##### like gcov does. everything else is data and shows how many times it was read and written
+    pub fn annotated_disassembly(&self, rom : &[u8]) -> String {
+        let (memory, code) = Self::trace_rom(rom);
+        let start = Chip8::PROGRAM_START as usize;
+        let end = start + rom.len();
+
+        let mut text = String::new();
+        let mut address = start;
+
+        while address < end {
+            let executed = self.count(&self.executed, address);
+
+            if (executed != 0 || code[address]) && address + 1 < end {
+                let count = if executed == 0 { String::from("#####") } else { executed.to_string() };
+                text += &format!(
+                    "{:>10} | {:03X}  {:02X}{:02X}  {}\n",
+                    count, address, memory[address], memory[address + 1], disassembler::disassemble_at(&memory, address)
+                );
+                address += 2;
+            } else {
+                text += &format!("{:>10} | {:03X}  {:02X}    DB {:02X}{}\n", "", address, memory[address], memory[address], self.data_counts(address));
+                address += 1;
+            }
+        }
+
+        // the rom can keep variables past its own end, those are listed too
+        let outside = (0..self.read.len())
+            .filter(|address| (*address < start || *address >= end) && self.count(&self.written, *address) + self.count(&self.read, *address) != 0)
+            .collect::<Vec<usize>>();
+
+        if !outside.is_empty() {
+            text += "\ndata outside the rom\n";
+            for address in outside {
+                text += &format!("{:>10} | {:03X}{}\n", "", address, self.data_counts(address));
+            }
+        }
+
+        text
+    }
+
+    /// this fn writes the coverage as an lcov tracefile where every instruction is a line named by its address,
+    /// so tools like genhtml can show it
+    pub fn lcov(&self, rom : &[u8], source_name : &str) -> String {
+        let (_, code) = Self::trace_rom(rom);
+        let start = Chip8::PROGRAM_START as usize;
+
+        let lines = (start..start + rom.len())
+            .filter(|address| code[*address] || self.count(&self.executed, *address) != 0)
+            .map(|address| (address, self.count(&self.executed, address)))
+            .collect::<Vec<(usize, u64)>>();
+
+        let mut text = format!("TN:\nSF:{}\n", source_name);
+        for (address, count) in lines.iter() {
+            text += &format!("DA:{},{}\n", address, count);
+        }
+        text += &format!("LF:{}\nLH:{}\nend_of_record\n", lines.len(), lines.iter().filter(|(_, count)| *count != 0).count());
+
+        text
+    }
+
+    /// this fn loads the rom into memory the way the chip8 would and finds the code in it
+    fn trace_rom(rom : &[u8]) -> (Vec<u8>, Vec<bool>) {
+        let start = Chip8::PROGRAM_START as usize;
+        let mut memory = vec![0; (start + rom.len()).max(Chip8::MAX_ROM_SIZE + start)];
+        memory[start..start + rom.len()].copy_from_slice(rom);
+
+        let code = disassembler::trace_code(&memory, start);
+        (memory, code)
+    }
+
+    fn count(&self, counts : &[u64], address : usize) -> u64 {
+        counts.get(address).copied().unwrap_or(0)
+    }
+
+    fn data_counts(&self, address : usize) -> String {
+        match (self.count(&self.read, address), self.count(&self.written, address)) {
+            (0, 0) => String::new(),
+            (read, written) => format!("  read {} written {}", read, written)
+        }
+    }
+}
@@ -0,0 +1,513 @@
+use std::io::{
+    BufRead,
+    Write,
+    stdin,
+    stdout
+};
+
+use crossterm::style::Stylize;
+
+use crate::{
+    Cheat,
+    CheatList,
+    Chip8,
+    EmulatorConfig,
+    Keyboard,
+    MemoryAccess,
+    MemoryRegion,
+    MemorySearch,
+    Quirks,
+    SearchFilter,
+    Watchpoint,
+    movie,
+    rom
+};
+
+/// this fn runs the interactive debugger in the terminal until it is quit
+///
+/// the chip8 is always paused here, it only runs when it is told to step or run frames
+pub fn run_debugger(config : &EmulatorConfig) -> Result<(), String> {
+    let mut debugger = Debugger::new(config)?;
+
+    println!("rip8 debugger, type help for the commands");
+    debugger.print_status();
+
+    let mut last_command = String::new();
+    let mut lines = stdin().lock().lines();
+
+    loop {
+        print!("(rip8) ");
+        let _ = stdout().flush();
+
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => break
+        };
+
+        // an empty line does the last command again, so stepping is just pressing enter
+        let command = if line.trim().is_empty() { last_command.clone() } else { line.trim().to_string() };
+        if command.is_empty() {
+            continue
+        }
+
+        match debugger.run_command(&command) {
+            Ok(true) => {},
+            Ok(false) => break,
+            Err(error) => println!("{}", error)
+        }
+
+        last_command = command;
+    }
+
+    Ok(())
+}
+
+struct Debugger {
+    chip8 : Chip8,
+    rom : Vec<u8>,
+
+    // the settings the chip8 is made again with on a reset
+    hertz : u32,
+    seed : Option<u64>,
+    quirks : Quirks,
+
+    // the key held down while the chip8 runs
+    keyboard : Keyboard,
+
+    // the cheats for the rom, they start as the ones that were saved for it
+    cheats : CheatList,
+
+    // the memory search going on, if there is one
+    search : Option<MemorySearch>,
+
+    // how many instructions have run in the current frame, the timers update when this reaches the per frame amount
+    frame_instructions : u32,
+    frame : usize,
+}
+
+impl Debugger {
+    const HELP : &'static str = "\
+commands, addresses and bytes are hex and counts are decimal :
+    regs                      show the registers, timers and stack
+    mem [address] [length]    show memory, the pc, the index register and the font are highlighted
+    poke <address> <bytes..>  write bytes into memory
+    sprite [rows]             show the sprite the index register points at, rows defaults to the next DXYN's
+    screen                    show the display
+    step [count]              run instructions
+    frame [count]             run to the end of frames
+    key <0-f | none>          hold a key down while the chip8 runs
+    watch <address[-end]> [rwx]  pause when memory is read, written or executed, rw if it isn't given
+    watches                   list the watchpoints
+    unwatch [number]          remove a watchpoint or all of them
+    search [filter]           start a memory search or narrow it with equal, changed, increased, decreased or a byte
+    candidates                list the addresses the memory search has left
+    freeze <address> <byte> [name]  write a byte every frame
+    patch <address> <byte> [name]   write a byte now and whenever the rom is loaded
+    cheats                    list the cheats
+    uncheat [number]          remove a cheat or all of them
+    savecheats                save the cheats so rip8 run --cheats uses them
+    reset                     load the rom again
+    quit
+an empty line does the last command again";
+
+    fn new(config : &EmulatorConfig) -> Result<Debugger, String> {
+        let rom = rom::read_rom(&config.rom_path)?;
+        let cheats = CheatList::load_for_rom(movie::rom_hash(&rom))?;
+
+        let mut debugger = Debugger {
+            chip8 : Chip8::new(),
+            rom,
+            hertz : config.hertz,
+            seed : config.seed,
+            quirks : config.quirks,
+            keyboard : Keyboard::None,
+            cheats,
+            search : None,
+            frame_instructions : 0,
+            frame : 0,
+        };
+        debugger.reset()?;
+
+        Ok(debugger)
+    }
+
+    fn reset(&mut self) -> Result<(), String> {
+        // the watchpoints are kept so a bug can be chased from the start again
+        let watchpoints = std::mem::take(&mut self.chip8.watchpoints);
+
+        self.chip8 = match self.seed {
+            Some(seed) => Chip8::with_seed(seed),
+            None => Chip8::new(),
+        };
+        self.chip8.quirks = self.quirks;
+        self.chip8.load_rom(&self.rom)?;
+        self.chip8.load_font();
+        self.chip8.watchpoints = watchpoints;
+        self.cheats.apply_patches(&mut self.chip8);
+
+        self.frame_instructions = 0;
+        self.frame = 0;
+
+        Ok(())
+    }
+
+    /// this fn runs one command and gives back false when the debugger should quit
+    fn run_command(&mut self, command : &str) -> Result<bool, String> {
+        let mut words = command.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let arguments = words.collect::<Vec<&str>>();
+
+        match name {
+            "help" | "h" => println!("{}", Self::HELP),
+            "quit" | "q" => return Ok(false),
+            "regs" | "r" => self.print_registers(),
+            "mem" | "m" => {
+                let start = match arguments.first() {
+                    Some(address) => parse_hex(address)?,
+                    None => (self.chip8.pc_reg as usize).saturating_sub(0x20),
+                };
+                let length = match arguments.get(1) {
+                    Some(length) => parse_count(length)?,
+                    None => 0x80,
+                };
+                self.print_memory(start, length);
+            },
+            "poke" => {
+                let address = match arguments.first() {
+                    Some(address) => parse_hex(address)?,
+                    None => return Err(String::from("error : poke needs an address and bytes"))
+                };
+
+                let bytes = arguments[1..].iter()
+                    .map(|byte| parse_hex(byte).and_then(|byte| u8::try_from(byte).map_err(|_| format!("error : '{:X}' is not a byte", byte))))
+                    .collect::<Result<Vec<u8>, String>>()?;
+
+                if bytes.is_empty() {
+                    return Err(String::from("error : poke needs bytes to write"))
+                }
+
+                self.chip8.poke(address, &bytes)?;
+                self.print_memory(address, bytes.len());
+            },
+            "sprite" | "s" => {
+                let rows = match arguments.first() {
+                    Some(rows) => parse_count(rows)?,
+                    None => self.chip8.pending_sprite_height().filter(|rows| *rows != 0).unwrap_or(8),
+                };
+                self.print_sprite(rows);
+            },
+            "screen" => self.print_screen(),
+            "step" | "n" => {
+                let count = match arguments.first() {
+                    Some(count) => parse_count(count)?,
+                    None => 1,
+                };
+
+                for _ in 0..count {
+                    if !self.step_instruction() {
+                        break
+                    }
+                }
+                self.print_status();
+            },
+            "frame" | "f" => {
+                let count = match arguments.first() {
+                    Some(count) => parse_count(count)?,
+                    None => 1,
+                };
+
+                let end_frame = self.frame + count;
+                while self.frame < end_frame {
+                    if !self.step_instruction() {
+                        break
+                    }
+                }
+                self.print_status();
+            },
+            "key" | "k" => {
+                self.keyboard = match arguments.first() {
+                    Some(&"none") | None => Keyboard::None,
+                    Some(key) => match u8::from_str_radix(key, 16) {
+                        Ok(key_code) if key_code <= 0xF => Keyboard::from_keycode(key_code),
+                        _ => return Err(format!("error : '{}' is not a chip8 key", key))
+                    }
+                };
+            },
+            "watch" | "w" => {
+                let (start, end) = match arguments.first() {
+                    Some(range) => match range.split_once('-') {
+                        Some((start, end)) => (parse_hex(start)?, parse_hex(end)?),
+                        None => (parse_hex(range)?, parse_hex(range)?)
+                    },
+                    None => return Err(String::from("error : watch needs an address"))
+                };
+
+                if start > end || end >= self.chip8.memory.len() {
+                    return Err(format!("error : {:03X}-{:03X} is not a range of memory", start, end))
+                }
+
+                let access = arguments.get(1).copied().unwrap_or("rw");
+                if access.is_empty() || !access.chars().all(|access| "rwx".contains(access)) {
+                    return Err(format!("error : '{}' should be some of r w and x", access))
+                }
+
+                let watchpoint = Watchpoint::new(start, end, access.contains('r'), access.contains('w'), access.contains('x'));
+                self.chip8.watchpoints.push(watchpoint);
+                self.print_watchpoints();
+            },
+            "watches" => self.print_watchpoints(),
+            "unwatch" => {
+                match arguments.first() {
+                    Some(number) => {
+                        let number = parse_count(number)?;
+                        if number >= self.chip8.watchpoints.len() {
+                            return Err(format!("error : there is no watchpoint {}", number))
+                        }
+                        self.chip8.watchpoints.remove(number);
+                    },
+                    None => self.chip8.watchpoints.clear()
+                }
+                self.print_watchpoints();
+            },
+            "search" => {
+                match arguments.first() {
+                    Some(filter) => {
+                        let filter = SearchFilter::parse(filter)?;
+                        match &mut self.search {
+                            Some(search) => search.filter(&self.chip8, filter),
+                            None => return Err(String::from("error : start a search with search first"))
+                        };
+                    },
+                    None => self.search = Some(MemorySearch::new(&self.chip8))
+                }
+                self.print_candidates();
+            },
+            "candidates" => self.print_candidates(),
+            "freeze" | "patch" => {
+                let (address, value) = match (arguments.first(), arguments.get(1)) {
+                    (Some(address), Some(value)) => (parse_hex(address)?, parse_hex(value)?),
+                    _ => return Err(format!("error : {} needs an address and a byte", name))
+                };
+
+                if address >= self.chip8.memory.len() {
+                    return Err(format!("error : {:03X} is outside of memory", address))
+                }
+
+                let value = match u8::try_from(value) {
+                    Ok(value) => value,
+                    Err(_) => return Err(format!("error : '{:X}' is not a byte", value))
+                };
+
+                self.chip8.memory[address] = value;
+                self.cheats.cheats.push(Cheat {
+                    address,
+                    value,
+                    frozen : name == "freeze",
+                    name : arguments[2..].join(" "),
+                });
+                self.print_cheats();
+            },
+            "cheats" => self.print_cheats(),
+            "uncheat" => {
+                match arguments.first() {
+                    Some(number) => {
+                        let number = parse_count(number)?;
+                        if number >= self.cheats.cheats.len() {
+                            return Err(format!("error : there is no cheat {}", number))
+                        }
+                        self.cheats.cheats.remove(number);
+                    },
+                    None => self.cheats.cheats.clear()
+                }
+                self.print_cheats();
+            },
+            "savecheats" => println!("saved cheats to {}", self.cheats.save()?),
+            "reset" => {
+                self.reset()?;
+                self.print_status();
+            },
+            _ => return Err(format!("error : unknown command '{}', type help for the commands", name))
+        }
+
+        Ok(true)
+    }
+
+    /// this fn runs one instruction and updates the timers when a frame's worth of instructions have run
+    ///
+    /// it gives back false if the chip8 ran out of memory or hit a watchpoint
+    fn step_instruction(&mut self) -> bool {
+        // the watchpoints that paused the chip8 were already reported so carry on past them
+        self.chip8.resume_from_watchpoints();
+
+        if self.frame_instructions == 0 {
+            self.cheats.apply_frozen(&mut self.chip8);
+        }
+
+        if !self.chip8.processor_frame(self.keyboard) {
+            println!("chip8 ran out of memory");
+            return false
+        }
+
+        let instructions_per_frame = (self.hertz as f64 / 60_f64).round().max(1.0) as u32;
+
+        self.frame_instructions += 1;
+        if self.frame_instructions >= instructions_per_frame {
+            self.chip8.update_timers();
+            self.frame_instructions = 0;
+            self.frame += 1;
+        }
+
+        if self.chip8.hit_watchpoint() {
+            self.print_watch_hits();
+            return false
+        }
+
+        true
+    }
+
+    fn print_watch_hits(&self) {
+        for hit in self.chip8.watch_hits.iter() {
+            match hit.access {
+                MemoryAccess::Read => println!("pc {:03X} read {:03X} : {:02X}", hit.pc, hit.address, hit.old_value),
+                MemoryAccess::Write => println!("pc {:03X} wrote {:03X} : {:02X} -> {:02X}", hit.pc, hit.address, hit.old_value, hit.new_value),
+                MemoryAccess::Execute => println!("pc {:03X} executed {:03X} : {:02X}", hit.pc, hit.address, hit.old_value),
+            }
+        }
+    }
+
+    fn print_candidates(&self) {
+        // there can be thousands of candidates at the start so only the first ones are shown
+        const SHOWN_CANDIDATES : usize = 32;
+
+        let search = match &self.search {
+            Some(search) => search,
+            None => {
+                println!("no memory search, start one with search");
+                return
+            }
+        };
+
+        println!("{} candidates", search.candidates.len());
+
+        if search.candidates.len() <= SHOWN_CANDIDATES {
+            for address in search.candidates.iter() {
+                println!("{:03X}  {:02X}", address, self.chip8.memory[*address]);
+            }
+        }
+    }
+
+    fn print_cheats(&self) {
+        if self.cheats.cheats.is_empty() {
+            println!("no cheats");
+        }
+
+        for (number, cheat) in self.cheats.cheats.iter().enumerate() {
+            let kind = if cheat.frozen { "freeze" } else { "patch" };
+            println!("{}  {} {:03X} {:02X} {}", number, kind, cheat.address, cheat.value, cheat.name);
+        }
+    }
+
+    fn print_watchpoints(&self) {
+        if self.chip8.watchpoints.is_empty() {
+            println!("no watchpoints");
+        }
+
+        for (number, watchpoint) in self.chip8.watchpoints.iter().enumerate() {
+            let access = [(watchpoint.read, 'r'), (watchpoint.write, 'w'), (watchpoint.execute, 'x')].iter()
+                .filter(|(watched, _)| *watched)
+                .map(|(_, access)| *access)
+                .collect::<String>();
+
+            println!("{}  {:03X}-{:03X} {}", number, watchpoint.start, watchpoint.end, access);
+        }
+    }
+
+    fn print_status(&self) {
+        let instruction = if (self.chip8.pc_reg as usize) + 1 < self.chip8.memory.len() {
+            format!("{:04X}", self.chip8.fetch())
+        } else {
+            String::from("----")
+        };
+
+        println!(
+            "frame {} pc {:03X} : {} i {:03X}",
+            self.frame, self.chip8.pc_reg, instruction, self.chip8.index_reg
+        );
+    }
+
+    fn print_registers(&self) {
+        let registers = self.chip8.v_regs.iter()
+            .enumerate()
+            .map(|(register, value)| format!("v{:X} {:02X}", register, value))
+            .collect::<Vec<String>>();
+
+        println!("{}", registers[..8].join("  "));
+        println!("{}", registers[8..].join("  "));
+        println!(
+            "pc {:03X}  i {:03X}  delay {}  sound {}  key {}",
+            self.chip8.pc_reg, self.chip8.index_reg, self.chip8.delay_timer_register, self.chip8.sound_timer_register,
+            match self.keyboard {
+                Keyboard::None => String::from("none"),
+                key => format!("{:X}", key.get_keycode())
+            }
+        );
+
+        let stack = self.chip8.stack.iter().map(|address| format!("{:03X}", address)).collect::<Vec<String>>();
+        println!("stack [{}]", stack.join(" "));
+    }
+
+    fn print_memory(&self, start : usize, length : usize) {
+        let index_length = self.chip8.pending_sprite_height().unwrap_or(Chip8::DEFAULT_INDEX_LENGTH);
+
+        for row in self.chip8.memory_rows(start, length, index_length) {
+            let bytes = row.bytes.iter()
+                .map(|(byte, region)| {
+                    let text = format!("{:02X}", byte);
+                    match region {
+                        MemoryRegion::ProgramCounter => text.black().on_yellow().to_string(),
+                        MemoryRegion::Index => text.black().on_cyan().to_string(),
+                        MemoryRegion::Font => text.dark_green().to_string(),
+                        MemoryRegion::Other => text,
+                    }
+                })
+                .collect::<Vec<String>>();
+
+            println!("{:03X}  {}", row.address, bytes.join(" "));
+        }
+
+        println!("{}  {}  {}", "pc".black().on_yellow(), "i".black().on_cyan(), "font".dark_green());
+    }
+
+    fn print_sprite(&self, rows : usize) {
+        for (row, pixels) in self.chip8.sprite_preview(rows).iter().enumerate() {
+            let address = self.chip8.index_reg as usize + row;
+            let byte = self.chip8.memory.get(address).copied().unwrap_or(0);
+            let pixels = pixels.iter().map(|pixel| if *pixel { "\u{2588}\u{2588}" } else { "\u{00B7}\u{00B7}" }).collect::<String>();
+
+            println!("{:03X}  {:02X}  {}", address, byte, pixels);
+        }
+    }
+
+    fn print_screen(&self) {
+        for row in self.chip8.display_buffer.iter() {
+            println!("{}", row.iter().map(|pixel| if *pixel { '\u{2588}' } else { '\u{00B7}' }).collect::<String>());
+        }
+    }
+}
+
+/// this fn reads a hex number with or without 0x in front of it
+fn parse_hex(text : &str) -> Result<usize, String> {
+    let digits = text.trim_start_matches("0x").trim_start_matches("0X");
+
+    match usize::from_str_radix(digits, 16) {
+        Ok(number) => Ok(number),
+        Err(_) => Err(format!("error : '{}' is not a hex number", text))
+    }
+}
+
+fn parse_count(text : &str) -> Result<usize, String> {
+    match text.parse::<usize>() {
+        Ok(count) => Ok(count),
+        Err(_) => Err(format!("error : '{}' is not a number", text))
+    }
+}
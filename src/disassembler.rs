@@ -0,0 +1,137 @@
+/// this fn gives back the pattern chip8 docs name an instruction by, EX : 0xD015 is DXYN
+///
+/// it goes by the same rules the chip8 decodes with, so 5XY1 is still 5XY0 and anything the chip8 treats as a nop
+/// is 0NNN or ????
+pub fn opcode_class(instruction : u16) -> &'static str {
+    match (instruction >> 12, instruction & 0x000F, instruction & 0x00FF) {
+        (0x0, _, _) if instruction == 0x00E0 => "00E0",
+        (0x0, _, _) if instruction == 0x00EE => "00EE",
+        (0x0, _, _) => "0NNN",
+        (0x1, _, _) => "1NNN",
+        (0x2, _, _) => "2NNN",
+        (0x3, _, _) => "3XNN",
+        (0x4, _, _) => "4XNN",
+        (0x5, _, _) => "5XY0",
+        (0x6, _, _) => "6XNN",
+        (0x7, _, _) => "7XNN",
+        (0x8, 0x0, _) => "8XY0",
+        (0x8, 0x1, _) => "8XY1",
+        (0x8, 0x2, _) => "8XY2",
+        (0x8, 0x3, _) => "8XY3",
+        (0x8, 0x4, _) => "8XY4",
+        (0x8, 0x5, _) => "8XY5",
+        (0x8, 0x6, _) => "8XY6",
+        (0x8, 0x7, _) => "8XY7",
+        (0x8, 0xE, _) => "8XYE",
+        (0x9, _, _) => "9XY0",
+        (0xA, _, _) => "ANNN",
+        (0xB, _, _) => "BNNN",
+        (0xC, _, _) => "CXNN",
+        (0xD, _, _) => "DXYN",
+        (0xE, _, 0x9E) => "EX9E",
+        (0xE, _, 0xA1) => "EXA1",
+        (0xF, _, 0x07) => "FX07",
+        (0xF, _, 0x0A) => "FX0A",
+        (0xF, _, 0x15) => "FX15",
+        (0xF, _, 0x18) => "FX18",
+        (0xF, _, 0x1E) => "FX1E",
+        (0xF, _, 0x29) => "FX29",
+        (0xF, _, 0x33) => "FX33",
+        (0xF, _, 0x55) => "FX55",
+        (0xF, _, 0x65) => "FX65",
+        _ => "????"
+    }
+}
+
+/// this fn turns an instruction into text with the usual chip8 mnemonics, EX : 0xD015 is "DRW V0, V1, 5"
+///
+/// instructions the chip8 doesn't know come out as "DW" and the instruction so they read as data
+pub fn disassemble(instruction : u16) -> String {
+    let nnn = instruction & 0x0FFF;
+    let nn = instruction & 0x00FF;
+    let n = instruction & 0x000F;
+    let x = (instruction >> 8) & 0xF;
+    let y = (instruction >> 4) & 0xF;
+
+    match opcode_class(instruction) {
+        "00E0" => String::from("CLS"),
+        "00EE" => String::from("RET"),
+        "0NNN" => format!("SYS {:03X}", nnn),
+        "1NNN" => format!("JP {:03X}", nnn),
+        "2NNN" => format!("CALL {:03X}", nnn),
+        "3XNN" => format!("SE V{:X}, {:02X}", x, nn),
+        "4XNN" => format!("SNE V{:X}, {:02X}", x, nn),
+        "5XY0" => format!("SE V{:X}, V{:X}", x, y),
+        "6XNN" => format!("LD V{:X}, {:02X}", x, nn),
+        "7XNN" => format!("ADD V{:X}, {:02X}", x, nn),
+        "8XY0" => format!("LD V{:X}, V{:X}", x, y),
+        "8XY1" => format!("OR V{:X}, V{:X}", x, y),
+        "8XY2" => format!("AND V{:X}, V{:X}", x, y),
+        "8XY3" => format!("XOR V{:X}, V{:X}", x, y),
+        "8XY4" => format!("ADD V{:X}, V{:X}", x, y),
+        "8XY5" => format!("SUB V{:X}, V{:X}", x, y),
+        "8XY6" => format!("SHR V{:X}, V{:X}", x, y),
+        "8XY7" => format!("SUBN V{:X}, V{:X}", x, y),
+        "8XYE" => format!("SHL V{:X}, V{:X}", x, y),
+        "9XY0" => format!("SNE V{:X}, V{:X}", x, y),
+        "ANNN" => format!("LD I, {:03X}", nnn),
+        "BNNN" => format!("JP V0, {:03X}", nnn),
+        "CXNN" => format!("RND V{:X}, {:02X}", x, nn),
+        "DXYN" => format!("DRW V{:X}, V{:X}, {:X}", x, y, n),
+        "EX9E" => format!("SKP V{:X}", x),
+        "EXA1" => format!("SKNP V{:X}", x),
+        "FX07" => format!("LD V{:X}, DT", x),
+        "FX0A" => format!("LD V{:X}, K", x),
+        "FX15" => format!("LD DT, V{:X}", x),
+        "FX18" => format!("LD ST, V{:X}", x),
+        "FX1E" => format!("ADD I, V{:X}", x),
+        "FX29" => format!("LD F, V{:X}", x),
+        "FX33" => format!("LD B, V{:X}", x),
+        "FX55" => format!("LD [I], V{:X}", x),
+        "FX65" => format!("LD V{:X}, [I]", x),
+        _ => format!("DW {:04X}", instruction)
+    }
+}
+
+/// this fn disassembles the instruction at an address of memory, an address without a whole instruction is "DB" and the byte
+pub fn disassemble_at(memory : &[u8], address : usize) -> String {
+    match (memory.get(address), memory.get(address + 1)) {
+        (Some(high), Some(low)) => disassemble(((*high as u16) << 8) | *low as u16),
+        (Some(byte), None) => format!("DB {:02X}", byte),
+        _ => String::new()
+    }
+}
+
+/// this fn finds the instructions that can be reached from an address by following jumps, calls and skips
+///
+/// it gives back true for every address an instruction starts at. a path stops at a return, at BNNN since where
+/// that goes depends on v0, and at anything the chip8 doesn't know so data after the code isn't read as code
+pub fn trace_code(memory : &[u8], start : usize) -> Vec<bool> {
+    let mut instruction_starts = vec![false; memory.len()];
+    let mut addresses = vec![start];
+
+    while let Some(address) = addresses.pop() {
+        if address + 1 >= memory.len() || instruction_starts[address] {
+            continue
+        }
+
+        let instruction = ((memory[address] as u16) << 8) | memory[address + 1] as u16;
+        let target = (instruction & 0x0FFF) as usize;
+        let class = opcode_class(instruction);
+
+        if class == "0NNN" || class == "????" {
+            continue
+        }
+        instruction_starts[address] = true;
+
+        match class {
+            "00EE" | "BNNN" => {},
+            "1NNN" => addresses.push(target),
+            "2NNN" => addresses.extend([target, address + 2]),
+            "3XNN" | "4XNN" | "5XY0" | "9XY0" | "EX9E" | "EXA1" => addresses.extend([address + 2, address + 4]),
+            _ => addresses.push(address + 2)
+        }
+    }
+
+    instruction_starts
+}
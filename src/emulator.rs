@@ -1,44 +1,742 @@
-use crate::{ Chip8, Chip8Window };
+use crate::{
+    AudioConfig,
+    CartridgeOptions,
+    CheatList,
+    Chip8,
+    Coverage,
+    ControlAction,
+    ControlServer,
+    ControlStatus,
+    Frontend,
+    GdbStub,
+    HeadlessFrontend,
+    Hotkey,
+    Keymap,
+    Movie,
+    MovieRecorder,
+    Palette,
+    Profiler,
+    Quirks,
+    RomWatcher,
+    ScreenshotView,
+    VideoCapture,
+    WavWriter,
+    video::{ GifRecorder, Y4mWriter },
+    movie,
+    rom,
+    screenshot
+};
 use std::{time, thread};
 
-pub fn run_emulator(hertz : u32, rom_path : &String) {
+#[cfg(feature = "sdl")]
+use crate::Chip8Window;
+#[cfg(feature = "terminal")]
+use crate::{ TerminalConfig, TerminalWindow };
+#[cfg(feature = "scripting")]
+use crate::{ Script, ScriptEvents };
+
+/// these are the settings for a run of the emulator
+pub struct EmulatorConfig {
+    // the clockspeed of the chip8 in hz
+    pub hertz : u32,
+
+    pub rom_path : String,
+
+    // the seed for the chip8's rng, if this is none a random seed is used
+    pub seed : Option<u64>,
+
+    // path to record an input movie to
+    pub record_movie_path : Option<String>,
+
+    // path to play an input movie back from
+    pub play_movie_path : Option<String>,
+
+    // run without a window as fast as possible, this stops when the movie ends or after headless_frames
+    pub headless : bool,
+
+    // how many frames to run for in headless mode, if this is none it runs until the movie ends
+    pub headless_frames : Option<usize>,
+
+    // the tone, waveform and volume of the buzzer
+    pub audio : AudioConfig,
+
+    // path to render the buzzer to as a wav file
+    pub wav_path : Option<String>,
+
+    // the sample rate of the wav file
+    pub wav_sample_rate : u32,
+
+    // frames to take a screenshot after, this is mostly for headless runs
+    pub screenshot_frames : Vec<usize>,
+
+    // if the scheduled screenshots should be native resolution instead of what the window shows
+    pub native_screenshots : bool,
+
+    // path to record the display to as an animated gif
+    pub gif_path : Option<String>,
+
+    // path to write the display to as a raw y4m video stream
+    pub y4m_path : Option<String>,
+
+    // draw in the terminal instead of opening an sdl window
+    #[cfg(feature = "terminal")]
+    pub terminal : Option<TerminalConfig>,
+
+    // which behavior the chip8 has for the instructions interpreters disagree on
+    pub quirks : Quirks,
+
+    // the colors to draw with, if this is none each frontend uses its own
+    pub palette : Option<Palette>,
+
+    // which keys are the chip8 keypad
+    pub keymap : Keymap,
+
+    // use the cheats saved for the rom
+    pub cheats : bool,
+
+    // the localhost port to wait for gdb to connect on before running
+    pub gdb_port : Option<u16>,
+
+    // path to a rhai script to run along with the rom
+    pub script_path : Option<String>,
+
+    // the localhost port for the json-rpc control server
+    pub control_port : Option<u16>,
+
+    // path to write a profile of the rom to when the emulator stops, the folded stacks go next to it with .folded added
+    pub profile_path : Option<String>,
+
+    // path to add this run's coverage to, runs of the same rom add up in the one file
+    pub coverage_path : Option<String>,
+
+    // paths to write the coverage to as an lcov tracefile and as an annotated disassembly
+    pub lcov_path : Option<String>,
+    pub annotate_path : Option<String>,
+
+    // reload the rom when its file changes, for .8o and .chip8 source it is assembled again
+    pub watch : bool,
+
+    // when the rom is reloaded keep the registers, stack, timers and display instead of starting over
+    pub keep_state : bool,
+}
+
+impl EmulatorConfig {
+    pub fn new(hertz : u32, rom_path : &str) -> EmulatorConfig {
+        EmulatorConfig {
+            hertz,
+            rom_path : rom_path.to_string(),
+            seed : None,
+            record_movie_path : None,
+            play_movie_path : None,
+            headless : false,
+            headless_frames : None,
+            audio : AudioConfig::new(),
+            wav_path : None,
+            wav_sample_rate : 44100,
+            screenshot_frames : Vec::new(),
+            native_screenshots : false,
+            gif_path : None,
+            y4m_path : None,
+            #[cfg(feature = "terminal")]
+            terminal : None,
+            quirks : Quirks::new(),
+            palette : None,
+            keymap : Keymap::new(),
+            cheats : false,
+            gdb_port : None,
+            script_path : None,
+            control_port : None,
+            profile_path : None,
+            coverage_path : None,
+            lcov_path : None,
+            annotate_path : None,
+            watch : false,
+            keep_state : false,
+        }
+    }
+
+    /// this fn uses the settings an octo cartridge came with
+    pub fn apply_cartridge(&mut self, options : &CartridgeOptions) {
+        if let Some(tickrate) = options.tickrate {
+            self.hertz = tickrate * 60;
+        }
+
+        self.quirks = options.quirks;
+
+        if options.palette.is_some() {
+            self.palette = options.palette;
+        }
+
+        if let Some(keymap) = &options.keymap {
+            self.keymap = keymap.clone();
+        }
+    }
+}
+
+/// this fn runs the emulator with the frontend picked in the config
+pub fn run_emulator(config : &EmulatorConfig) -> Result<(), String> {
+    if config.headless {
+        if config.play_movie_path.is_none() && config.headless_frames.is_none() && config.control_port.is_none() {
+            return Err(String::from("error : headless mode needs a movie to play back, a number of frames to run or a control server!"))
+        }
+
+        let mut headless_frontend = HeadlessFrontend::new(config.headless_frames);
+        if let Some(palette) = config.palette {
+            headless_frontend.palette = palette;
+        }
+
+        return run_frontend(&mut headless_frontend, config)
+    }
+
+    #[cfg(feature = "terminal")]
+    if let Some(terminal_config) = config.terminal {
+        let mut terminal_window = TerminalWindow::new(terminal_config)?;
+        terminal_window.keymap = config.keymap.clone();
+        if let Some(palette) = config.palette {
+            terminal_window.palette = palette;
+        }
+
+        return run_frontend(&mut terminal_window, config)
+    }
+
+    run_window(config)
+}
+
+/// this fn runs the emulator in an sdl window
+#[cfg(feature = "sdl")]
+fn run_window(config : &EmulatorConfig) -> Result<(), String> {
+    let mut chip8_window = Chip8Window::new(config.audio);
+    chip8_window.keymap = config.keymap.clone();
+    match config.palette {
+        Some(palette) => chip8_window.set_palette(palette),
+        None => chip8_window.invert_colors(),
+    }
+
+    run_frontend(&mut chip8_window, config)
+}
+
+#[cfg(not(feature = "sdl"))]
+fn run_window(_config : &EmulatorConfig) -> Result<(), String> {
+    Err(String::from("error : rip8 was built without the sdl window, run it headless or in the terminal instead!"))
+}
+
+/// this is the emulation loop, it runs the chip8 and sends everything it does to the frontend
+pub fn run_frontend<F : Frontend>(frontend : &mut F, config : &EmulatorConfig) -> Result<(), String> {
     const FRAME_TIME : f64 = 1_f64 / 60_f64;
-    let hertz = (hertz as f64 / 60_f64).round() as u32;
-    
-    let mut chip8 = Chip8::new();
-    chip8.load_rom_from_bin(rom_path);
-    chip8.load_font();
 
-    let mut chip8_window = Chip8Window::new();
+    // the speeds that the speed up and slow down hotkeys step through
+    const SPEEDS : [f64; 7] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 16.0];
+    const NORMAL_SPEED : usize = 2;
+
+    let hertz = (config.hertz as f64 / 60_f64).round() as u32;
+
+    // the rom is only read once so it can come from stdin and still be reset
+    let mut rom = rom::read_rom(&config.rom_path)?;
+    let rom_hash = movie::rom_hash(&rom);
+
+    let movie = match &config.play_movie_path {
+        Some(path) => {
+            let movie = Movie::load(path)?;
+            movie.check_sync(rom_hash, config.hertz, config.quirks)?;
+            Some(movie)
+        }
+        None => None
+    };
+
+    // a movie being played back always uses the seed it was recorded with
+    let seed = match (&movie, config.seed) {
+        (Some(movie), _) => Some(movie.seed),
+        (None, seed) => seed,
+    };
+
+    let mut cheats = match config.cheats {
+        true => Some(CheatList::load_for_rom(rom_hash)?),
+        false => None
+    };
+
+    let mut chip8 = match seed {
+        Some(seed) => Chip8::with_seed(seed),
+        None => Chip8::new(),
+    };
+    load_chip8(&mut chip8, &rom, config, cheats.as_ref())?;
+
+    let mut recorder = match &config.record_movie_path {
+        Some(path) => Some(MovieRecorder::new(path, rom_hash, chip8.seed, config.hertz, config.quirks)?),
+        None => None
+    };
+
+    let mut wav_writer = match &config.wav_path {
+        Some(path) => Some(WavWriter::new(path, config.audio, config.wav_sample_rate)?),
+        None => None
+    };
+
+    let mut video_capture = start_video_capture(config, &chip8, frontend.palette())?;
+
+    #[cfg(feature = "scripting")]
+    let mut script = match &config.script_path {
+        Some(path) => Some(Script::load(path, &mut chip8)?),
+        None => None
+    };
+    #[cfg(not(feature = "scripting"))]
+    if config.script_path.is_some() {
+        return Err(String::from("error : rip8 was built without scripting, run it without --script!"))
+    }
+
+    let mut watcher = match config.watch {
+        true => Some(RomWatcher::new(&config.rom_path)?),
+        false => None
+    };
+
+    let mut control = match config.control_port {
+        Some(port) => Some(ControlServer::start(port)?),
+        None => None
+    };
+
+    // the frontend isn't updated while gdb has the chip8 halted
+    let mut gdb = match config.gdb_port {
+        Some(port) => {
+            frontend.show_message(&format!("waiting for gdb on localhost:{}", port));
+            frontend.draw_canvas(&chip8.display_buffer);
+            Some(GdbStub::listen(port)?)
+        },
+        None => None
+    };
+
+    if config.coverage_path.is_some() || config.lcov_path.is_some() || config.annotate_path.is_some() {
+        chip8.coverage = Some(Coverage::new(rom_hash, chip8.memory.len()));
+    }
+
+    let mut profiler = config.profile_path.as_ref().map(|_| Profiler::new());
 
-    chip8_window.invert_colors();
+    let mut frame : usize = 0;
 
-    loop {
+    let mut paused = false;
+    let mut fast_forward = false;
+    let mut muted = false;
+    let mut speed = NORMAL_SPEED;
+
+    frontend.set_title(&window_title(paused, fast_forward, SPEEDS[speed]));
+
+    'emulation : loop {
         let start_frame_time = time::Instant::now();
 
-        let keyboard = chip8_window.handle_input();
-        
-        for _ in 0..hertz {
-            if !chip8.processor_frame(keyboard.clone()) {
-                return
+        // while a movie is playing the frontend is still polled so it can be closed but the keys come from the movie
+        let frontend_keyboard = frontend.handle_input();
+
+        let mut frames_to_run : u32 = 0;
+
+        for hotkey in frontend.take_hotkeys() {
+            match hotkey {
+                Hotkey::Quit => break 'emulation,
+                Hotkey::Pause => paused = !paused,
+                Hotkey::FrameAdvance => if paused { frames_to_run += 1 },
+                Hotkey::Reset => {
+                    reset_chip8(&mut chip8, &rom, config, cheats.as_ref())?;
+
+                    if let Some(recorder) = &mut recorder {
+                        recorder.record_reset()?;
+                    }
+                },
+                Hotkey::SpeedUp => speed = (speed + 1).min(SPEEDS.len() - 1),
+                Hotkey::SlowDown => speed = speed.saturating_sub(1),
+                Hotkey::FastForward(held) => fast_forward = held,
+                Hotkey::Screenshot { native } => {
+                    let view = if native {
+                        ScreenshotView::Native
+                    } else {
+                        ScreenshotView::Scaled { palette : frontend.palette(), pixel_size : screenshot::WINDOW_PIXEL_SIZE }
+                    };
+                    let file_name = take_screenshot(&chip8, &config.rom_path, frame, view)?;
+                    frontend.show_message(&format!("saved screenshot {}", file_name));
+                },
+                Hotkey::ToggleCapture => {
+                    // recordings asked for on the command line keep going until the emulator stops
+                    if video_capture.hotkey_recorder.is_some() {
+                        video_capture.finish_hotkey_recording()?;
+                        frontend.show_message("stopped recording");
+                    } else {
+                        let file_name = format!("{}_{:06}.gif", screenshot::rom_name(&config.rom_path), frame);
+                        video_capture.hotkey_recorder = Some(GifRecorder::new(
+                            &file_name,
+                            frontend.palette(),
+                            chip8.display_buffer[0].len(),
+                            chip8.display_buffer.len(),
+                            VideoCapture::PIXEL_SIZE
+                        )?);
+                        frontend.show_message(&format!("recording to {}", file_name));
+                    }
+                },
+                Hotkey::ToggleMute => {
+                    muted = !muted;
+                    set_muted(frontend, muted);
+                },
+            }
+
+            frontend.set_title(&window_title(paused, fast_forward, SPEEDS[speed]));
+        }
+
+        if let Some(control) = &mut control {
+            let status = ControlStatus { frame, paused, palette : frontend.palette() };
+
+            for action in control.handle_requests(&mut chip8, status) {
+                match action {
+                    ControlAction::Pause => paused = true,
+                    ControlAction::Continue => paused = false,
+                    ControlAction::StepFrames(frames) => {
+                        paused = true;
+                        // a few clients can each ask for steps before the loop gets to run them
+                        frames_to_run = frames_to_run.saturating_add(frames);
+                    },
+                    ControlAction::Reset => {
+                        reset_chip8(&mut chip8, &rom, config, cheats.as_ref())?;
+
+                        if let Some(recorder) = &mut recorder {
+                            recorder.record_reset()?;
+                        }
+                    },
+                    ControlAction::LoadRom(new_rom) => {
+                        if let Err(error) = switch_rom(&mut chip8, &mut rom, new_rom, &mut cheats, config, false) {
+                            frontend.show_message(&error);
+                        }
+                    },
+                    ControlAction::Quit => break 'emulation,
+                }
+            }
+
+            frontend.set_title(&window_title(paused, fast_forward, SPEEDS[speed]));
+        }
+
+        if let Some(watcher) = &mut watcher {
+            if watcher.changed() {
+                // a rom that doesn't assemble or load is only shown so the game keeps running until it is fixed
+                let reloaded = rom::read_rom(&config.rom_path)
+                    .and_then(|new_rom| switch_rom(&mut chip8, &mut rom, new_rom, &mut cheats, config, config.keep_state));
+
+                match reloaded {
+                    Ok(_) => frontend.show_message(&format!("reloaded {}", config.rom_path)),
+                    Err(error) => frontend.show_message(&error)
+                }
             }
         }
 
-        chip8_window.draw_canvas(chip8.display_buffer.clone());
-        
-        chip8.update_timers();
+        // a key held by a control client wins over the frontend's
+        let frontend_keyboard = control.as_ref().and_then(|control| control.keyboard).unwrap_or(frontend_keyboard);
 
-        if chip8.sound_timer_register != 0 {
-            chip8_window.audio_device.resume();
-        } else {
-            chip8_window.audio_device.pause();
+        // speeds above normal run more than one chip8 frame for every frame that is drawn,
+        // speeds below normal run one chip8 frame and then wait longer
+        if !paused {
+            frames_to_run = SPEEDS[speed].max(1.0) as u32;
         }
 
-        let remaining_frame_time = FRAME_TIME - start_frame_time.elapsed().as_secs_f64();
+        for _ in 0..frames_to_run {
+            let keyboard = match &movie {
+                Some(movie) => match movie.frames.get(frame) {
+                    Some(movie_frame) => {
+                        if movie_frame.reset {
+                            reset_chip8(&mut chip8, &rom, config, cheats.as_ref())?;
+                        }
+                        movie_frame.keyboard
+                    },
+                    None => {
+                        frontend.show_message(&format!("movie finished after {} frames with no desyncs", frame));
+                        break 'emulation
+                    }
+                },
+                None => frontend_keyboard
+            };
+
+            // a script can hold keys down unless a movie is giving the keys
+            #[cfg(feature = "scripting")]
+            let keyboard = match &mut script {
+                Some(script) => {
+                    let events = script.run_frame(&mut chip8, frame)?;
+                    if handle_script_events(frontend, &chip8, events, &mut muted, &config.rom_path, frame)? {
+                        break 'emulation
+                    }
+
+                    match &movie {
+                        Some(_) => keyboard,
+                        None => script.keyboard().unwrap_or(keyboard)
+                    }
+                },
+                None => keyboard
+            };
+
+            if let Some(cheats) = &cheats {
+                cheats.apply_frozen(&mut chip8);
+            }
+
+            for _ in 0..hertz {
+                if let Some(gdb) = &mut gdb {
+                    if !gdb.before_instruction(&mut chip8)? {
+                        break 'emulation
+                    }
+                }
+
+                #[cfg(feature = "scripting")]
+                if let Some(script) = &mut script {
+                    if script.has_pc_hook(chip8.pc_reg) {
+                        let events = script.run_pc_hooks(&mut chip8, frame)?;
+                        if handle_script_events(frontend, &chip8, events, &mut muted, &config.rom_path, frame)? {
+                            break 'emulation
+                        }
+                    }
+                }
+
+                if let Some(profiler) = &mut profiler {
+                    profiler.record(&chip8, keyboard);
+                }
+
+                if !chip8.processor_frame(keyboard) {
+                    frontend.show_message(&format!("chip8 ran out of memory on frame {}", frame));
+                    break 'emulation
+                }
+            }
+
+            // the sdl audio callback times the tone itself from the sound timer so the tone isn't cut to whole frames
+            frontend.set_sound_timer(chip8.sound_timer_register);
+
+            if let Some(wav_writer) = &mut wav_writer {
+                wav_writer.push_frame(chip8.sound_timer_register)?;
+            }
+
+            chip8.update_timers();
+
+            if let Some(recorder) = &mut recorder {
+                recorder.record_frame(keyboard, &chip8)?;
+            }
+
+            if let Some(movie) = &movie {
+                movie.check_frame(frame, &chip8)?;
+            }
+
+            frame += 1;
+
+            if config.screenshot_frames.contains(&frame) {
+                let view = if config.native_screenshots {
+                    ScreenshotView::Native
+                } else {
+                    ScreenshotView::Scaled { palette : frontend.palette(), pixel_size : screenshot::WINDOW_PIXEL_SIZE }
+                };
+                let file_name = take_screenshot(&chip8, &config.rom_path, frame, view)?;
+                frontend.show_message(&format!("saved screenshot {}", file_name));
+            }
+        }
+
+        frontend.draw_canvas(&chip8.display_buffer);
+
+        // only frames where the chip8 ran are captured so pausing doesn't fill the video with the same frame
+        if frames_to_run != 0 {
+            video_capture.push_frame(&chip8.display_buffer)?;
+        }
+
+        if paused {
+            frontend.set_sound_timer(0);
+        }
+
+        // a paused headless run with a control server would spin while it waits for requests otherwise
+        let waiting_for_control = paused && control.is_some();
+
+        if (fast_forward || !frontend.is_realtime()) && !waiting_for_control {
+            continue
+        }
+
+        let remaining_frame_time = FRAME_TIME / SPEEDS[speed].min(1.0) - start_frame_time.elapsed().as_secs_f64();
 
         // if you move the window for some reason the time duration might be negative so this prevents a crashs
         if remaining_frame_time.is_sign_positive() {
             thread::sleep(time::Duration::from_secs_f64(remaining_frame_time))
-        } 
+        }
+    }
+
+    video_capture.finish()?;
+
+    if let (Some(profiler), Some(path)) = (&profiler, &config.profile_path) {
+        let folded_path = format!("{}.folded", path);
+
+        if std::fs::write(path, profiler.report(&chip8.memory)).is_err() {
+            return Err(format!("error : could not write profile '{}'!", path))
+        }
+        if std::fs::write(&folded_path, profiler.folded_stacks()).is_err() {
+            return Err(format!("error : could not write profile '{}'!", folded_path))
+        }
+
+        frontend.show_message(&format!("saved profile to {} and {}", path, folded_path));
+    }
+
+    if let Some(coverage) = &chip8.coverage {
+        save_coverage(coverage, &rom, config)?;
     }
-}
\ No newline at end of file
+
+    match wav_writer {
+        Some(wav_writer) => wav_writer.finish(),
+        None => Ok(())
+    }
+}
+
+/// this fn starts the gif and y4m recordings that were asked for in the config
+fn start_video_capture(config : &EmulatorConfig, chip8 : &Chip8, palette : Palette) -> Result<VideoCapture, String> {
+    let width = chip8.display_buffer[0].len();
+    let height = chip8.display_buffer.len();
+
+    let mut video_capture = VideoCapture::new();
+
+    if let Some(path) = &config.gif_path {
+        video_capture.gif_recorder = Some(GifRecorder::new(path, palette, width, height, VideoCapture::PIXEL_SIZE)?);
+    }
+
+    if let Some(path) = &config.y4m_path {
+        video_capture.y4m_writer = Some(Y4mWriter::new(path, palette, width, height, VideoCapture::PIXEL_SIZE)?);
+    }
+
+    Ok(video_capture)
+}
+
+/// this fn starts the rom over with the same seed, the coverage carries on since it is for the whole run
+fn reset_chip8(chip8 : &mut Chip8, rom : &[u8], config : &EmulatorConfig, cheats : Option<&CheatList>) -> Result<(), String> {
+    let coverage = chip8.coverage.take();
+
+    *chip8 = Chip8::with_seed(chip8.seed);
+    chip8.coverage = coverage;
+
+    load_chip8(chip8, rom, config, cheats)
+}
+
+/// this fn changes to a different rom, cheats and coverage are for one rom so they start over for the new one
+///
+/// keeping the state only swaps the rom in memory and leaves the registers, stack, timers and display alone.
+/// the new rom is set up on a copy of the chip8 so if anything fails the old rom keeps running like nothing happened
+fn switch_rom(
+    chip8 : &mut Chip8,
+    rom : &mut Vec<u8>,
+    new_rom : Vec<u8>,
+    cheats : &mut Option<CheatList>,
+    config : &EmulatorConfig,
+    keep_state : bool
+) -> Result<(), String> {
+    let rom_hash = movie::rom_hash(&new_rom);
+
+    let new_cheats = match config.cheats {
+        true => Some(CheatList::load_for_rom(rom_hash)?),
+        false => None
+    };
+
+    let mut new_chip8 = chip8.clone();
+    if let Some(coverage) = &mut new_chip8.coverage {
+        *coverage = Coverage::new(rom_hash, new_chip8.memory.len());
+    }
+
+    if keep_state {
+        // whatever is left of the old rom past the end of the new one is cleared so it can't run by mistake
+        let start = Chip8::PROGRAM_START as usize;
+        new_chip8.memory[start..start + rom.len()].fill(0);
+        new_chip8.load_rom(&new_rom)?;
+
+        if let Some(cheats) = &new_cheats {
+            cheats.apply_patches(&mut new_chip8);
+        }
+    } else {
+        reset_chip8(&mut new_chip8, &new_rom, config, new_cheats.as_ref())?;
+    }
+
+    // nothing went wrong so the new rom can take over
+    *chip8 = new_chip8;
+    *rom = new_rom;
+    *cheats = new_cheats;
+
+    Ok(())
+}
+
+/// this fn writes the coverage everywhere the config asked for it
+fn save_coverage(coverage : &Coverage, rom : &[u8], config : &EmulatorConfig) -> Result<(), String> {
+    if let Some(path) = &config.coverage_path {
+        coverage.save_merged(path)?;
+    }
+
+    // the reports show everything in the coverage file so a set of runs can be looked at together
+    let coverage = match &config.coverage_path {
+        Some(path) => Coverage::load(path)?,
+        None => coverage.clone()
+    };
+
+    if let Some(path) = &config.lcov_path {
+        if std::fs::write(path, coverage.lcov(rom, &config.rom_path)).is_err() {
+            return Err(format!("error : could not write lcov file '{}'!", path))
+        }
+    }
+
+    if let Some(path) = &config.annotate_path {
+        if std::fs::write(path, coverage.annotated_disassembly(rom)).is_err() {
+            return Err(format!("error : could not write annotated disassembly '{}'!", path))
+        }
+    }
+
+    Ok(())
+}
+
+/// this fn loads the rom and font into a freshly made chip8 and patches it with the cheats
+fn load_chip8(chip8 : &mut Chip8, rom : &[u8], config : &EmulatorConfig, cheats : Option<&CheatList>) -> Result<(), String> {
+    chip8.quirks = config.quirks;
+    chip8.load_rom(rom)?;
+    chip8.load_font();
+
+    if let Some(cheats) = cheats {
+        cheats.apply_patches(chip8);
+    }
+
+    Ok(())
+}
+
+/// this fn does what a script asked for and gives back true if the script wants the emulator to stop
+#[cfg(feature = "scripting")]
+fn handle_script_events<F : Frontend>(
+    frontend : &mut F,
+    chip8 : &Chip8,
+    events : ScriptEvents,
+    muted : &mut bool,
+    rom_path : &str,
+    frame : usize
+) -> Result<bool, String> {
+    for message in events.messages.iter() {
+        frontend.show_message(message);
+    }
+
+    if let Some(mute) = events.mute {
+        *muted = mute;
+        set_muted(frontend, mute);
+    }
+
+    let view = ScreenshotView::Scaled { palette : frontend.palette(), pixel_size : screenshot::WINDOW_PIXEL_SIZE };
+    for path in events.screenshots {
+        let file_name = path.unwrap_or_else(|| screenshot::screenshot_file_name(rom_path, frame, view));
+        screenshot::save_png(&chip8.display_buffer, &file_name, view)?;
+        frontend.show_message(&format!("saved screenshot {}", file_name));
+    }
+
+    Ok(events.quit)
+}
+
+/// this fn mutes or unmutes the frontend's buzzer and tells the user
+fn set_muted<F : Frontend>(frontend : &mut F, muted : bool) {
+    frontend.set_muted(muted);
+    frontend.show_message(if muted { "muted" } else { "unmuted" });
+}
+
+/// this fn saves the chip8's display as a png named after the rom and frame and gives back the file name
+fn take_screenshot(chip8 : &Chip8, rom_path : &str, frame : usize, view : ScreenshotView) -> Result<String, String> {
+    let file_name = screenshot::screenshot_file_name(rom_path, frame, view);
+    screenshot::save_png(&chip8.display_buffer, &file_name, view)?;
+    Ok(file_name)
+}
+
+/// this fn makes the window title that shows how fast the emulator is running
+fn window_title(paused : bool, fast_forward : bool, speed : f64) -> String {
+    if paused {
+        String::from("rip8 - paused")
+    } else if fast_forward {
+        String::from("rip8 - fast forward")
+    } else {
+        format!("rip8 - {}x", speed)
+    }
+}
+
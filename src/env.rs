@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use crate::{
+    Chip8,
+    Keyboard,
+    Quirks
+};
+
+/// a reward fn gets memory from before and after a step and gives back how good the step was
+pub type RewardFn = Arc<dyn Fn(&[u8], &[u8]) -> f64 + Send + Sync>;
+
+/// a done fn gets memory after a step and gives back if the game is over
+pub type DoneFn = Arc<dyn Fn(&[u8]) -> bool + Send + Sync>;
+
+/// this fn makes a reward that is how much the byte at an address went up, for games that keep their score in a byte
+pub fn reward_from_byte(address : usize) -> RewardFn {
+    Arc::new(move |before, after| {
+        match (before.get(address), after.get(address)) {
+            (Some(before), Some(after)) => *after as f64 - *before as f64,
+            _ => 0.0
+        }
+    })
+}
+
+/// this fn makes a done fn that is true once the byte at an address is a value, EX : lives hitting 0
+pub fn done_when_byte(address : usize, value : u8) -> DoneFn {
+    Arc::new(move |memory| memory.get(address) == Some(&value))
+}
+
+/// this is what a step of an env gives back
+#[derive(Debug, Clone)]
+pub struct Step {
+    // the display after the step, one byte for every pixel, 1 for on and 0 for off, row by row
+    pub observation : Vec<u8>,
+
+    pub reward : f64,
+
+    // the game is over and the env should be reset
+    pub done : bool,
+
+    // how many frames have run since the last reset
+    pub frame : usize,
+}
+
+/// this is a gym style environment around a headless chip8, for training agents to play games
+///
+/// the env runs a rom without a frontend, reset starts it over with a seed and step holds a key down for some frames
+/// and gives back the display. what counts as reward and when the game is over comes from the rom's memory, so
+/// it is up to the fns given with with_reward and with_done, by default the reward is always 0 and the game only ends
+/// if the chip8 runs out of memory or max frames is hit. EX for a game that keeps its score at 0x3f0 and lives at 0x3f1 :
+///
+/// ```text
+/// let mut env = Env::new(&rom, 700)?
+///     .with_reward(reward_from_byte(0x3f0))
+///     .with_done(done_when_byte(0x3f1, 0));
+///
+/// let mut observation = env.reset(1);
+/// loop {
+///     let step = env.step(Keyboard::from_keycode(agent.act(&observation)), 4);
+///     observation = step.observation;
+///     if step.done { break }
+/// }
+/// ```
+///
+/// an env can be cloned, the clone carries on exactly the same from where it was which is handy for search
+/// and for running a lot of envs on different threads
+#[derive(Clone)]
+pub struct Env {
+    chip8 : Chip8,
+    rom : Vec<u8>,
+    quirks : Quirks,
+
+    // how many instructions are run every frame
+    instructions_per_frame : u32,
+
+    reward : RewardFn,
+    done : DoneFn,
+
+    // the game is cut off after this many frames, none lets it run forever
+    max_frames : Option<usize>,
+
+    frame : usize,
+
+    // memory from before the step for the reward fn, kept here so a step doesn't need a new vec every time
+    previous_memory : Vec<u8>,
+}
+
+impl Env {
+    /// this fn makes an env for a rom running at hertz instructions a second, it has to be reset before stepping
+    pub fn new(rom : &[u8], hertz : u32) -> Result<Env, String> {
+        if rom.len() > Chip8::MAX_ROM_SIZE {
+            return Err(format!("error : the rom is {} bytes but there is only room for {} bytes!", rom.len(), Chip8::MAX_ROM_SIZE))
+        }
+
+        let mut env = Env {
+            chip8 : Chip8::with_seed(0),
+            rom : rom.to_vec(),
+            quirks : Quirks::new(),
+            instructions_per_frame : (hertz as f64 / 60_f64).round() as u32,
+            reward : Arc::new(|_, _| 0.0),
+            done : Arc::new(|_| false),
+            max_frames : None,
+            frame : 0,
+            previous_memory : Vec::new(),
+        };
+        env.reset(0);
+
+        Ok(env)
+    }
+
+    pub fn with_reward(mut self, reward : RewardFn) -> Env {
+        self.reward = reward;
+        self
+    }
+
+    pub fn with_done(mut self, done : DoneFn) -> Env {
+        self.done = done;
+        self
+    }
+
+    pub fn with_quirks(mut self, quirks : Quirks) -> Env {
+        self.quirks = quirks;
+        self.chip8.quirks = quirks;
+        self
+    }
+
+    pub fn with_max_frames(mut self, max_frames : usize) -> Env {
+        self.max_frames = Some(max_frames);
+        self
+    }
+
+    /// this fn starts the rom over with the rng seeded with seed and gives back the first observation
+    pub fn reset(&mut self, seed : u64) -> Vec<u8> {
+        let mut chip8 = Chip8::with_seed(seed);
+        chip8.quirks = self.quirks;
+        chip8.load_font();
+
+        // the size was checked in new so this can't fail
+        let _ = chip8.load_rom(&self.rom);
+
+        self.chip8 = chip8;
+        self.frame = 0;
+
+        self.observation()
+    }
+
+    /// this fn holds a key down for some frames and gives back what happened, Keyboard::None is no key
+    ///
+    /// Keyboard::from_keycode turns anything above 0xF into no key so it can turn a discrete action of 0 to 16 into a key
+    pub fn step(&mut self, keyboard : Keyboard, frames : u32) -> Step {
+        self.previous_memory.clone_from(&self.chip8.memory);
+
+        let mut out_of_memory = false;
+        for _ in 0..frames {
+            out_of_memory = !self.run_frame(keyboard);
+            if out_of_memory {
+                break
+            }
+        }
+
+        let reward = (self.reward)(&self.previous_memory, &self.chip8.memory);
+        let past_max_frames = self.max_frames.is_some_and(|max_frames| self.frame >= max_frames);
+        let done = out_of_memory || past_max_frames || (self.done)(&self.chip8.memory);
+
+        Step {
+            observation : self.observation(),
+            reward,
+            done,
+            frame : self.frame,
+        }
+    }
+
+    /// this fn gives back the display, one byte for every pixel, 1 for on and 0 for off, row by row
+    pub fn observation(&self) -> Vec<u8> {
+        self.chip8.display_buffer.iter().flatten().map(|pixel| *pixel as u8).collect()
+    }
+
+    /// this fn gives back the width and height of the observation
+    pub fn observation_size(&self) -> (usize, usize) {
+        (self.chip8.display_buffer.first().map_or(0, |row| row.len()), self.chip8.display_buffer.len())
+    }
+
+    /// this fn gives back the chip8 so memory and registers can be read for features other than the display
+    pub fn chip8(&self) -> &Chip8 {
+        &self.chip8
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// this fn runs one 60th of a second of the chip8, it gives back false once the chip8 has run out of memory
+    fn run_frame(&mut self, keyboard : Keyboard) -> bool {
+        for _ in 0..self.instructions_per_frame {
+            if !self.chip8.processor_frame(keyboard) {
+                return false
+            }
+        }
+
+        self.chip8.update_timers();
+        self.frame += 1;
+
+        true
+    }
+}
@@ -1,3 +1,4 @@
+#[derive(Debug, Clone)]
 pub struct Font {
     pub font_data : Vec<u8>,
 
@@ -0,0 +1,93 @@
+use crate::{ Keyboard, Hotkey, Palette };
+
+/// this is everything the emulator loop needs from whatever is showing the chip8 to the user
+///
+/// the loop in run_frontend only talks to the chip8 through this, so a new way of running games
+/// (a window, a terminal, a test that records frames) only has to implement these fns
+pub trait Frontend {
+    /// this fn reads the input since the last frame and gives back the chip8 key being held
+    ///
+    /// emulator hotkeys are not returned here, they are given back by take_hotkeys
+    fn handle_input(&mut self) -> Keyboard;
+
+    /// this fn gives back every hotkey that was pressed since the last time it was called
+    fn take_hotkeys(&mut self) -> Vec<Hotkey>;
+
+    /// this fn shows the display buffer
+    fn draw_canvas(&mut self, buffer : &[Vec<bool>]);
+
+    /// this fn is given the sound timer once per frame so the frontend can play the buzzer
+    fn set_sound_timer(&mut self, sound_timer : u8);
+
+    /// this fn turns the buzzer off or back on, the sound timer still gets set while it is muted
+    fn set_muted(&mut self, _muted : bool) {}
+
+    /// this fn shows the emulator's state, like how fast it is running
+    fn set_title(&mut self, _title : &str) {}
+
+    /// this fn gives back the colors the display is shown with
+    fn palette(&self) -> Palette {
+        Palette::new()
+    }
+
+    /// this fn tells the user something happened
+    fn show_message(&mut self, message : &str) {
+        println!("{}", message);
+    }
+
+    /// this fn gives back if the loop should wait between frames to run at the real speed of the chip8
+    fn is_realtime(&self) -> bool {
+        true
+    }
+}
+
+/// this is a frontend that shows nothing and runs as fast as it can
+///
+/// the input comes from a movie if one is playing, otherwise no key is ever pressed.
+/// it can be given a number of frames to run before it quits by itself
+pub struct HeadlessFrontend {
+    pub frames_left : Option<usize>,
+
+    // the colors screenshots and recordings are made with
+    pub palette : Palette,
+
+    hotkeys : Vec<Hotkey>,
+}
+
+impl HeadlessFrontend {
+    pub fn new(frame_limit : Option<usize>) -> HeadlessFrontend {
+        HeadlessFrontend {
+            frames_left : frame_limit,
+            palette : Palette::new(),
+            hotkeys : Vec::new(),
+        }
+    }
+}
+
+impl Frontend for HeadlessFrontend {
+    fn handle_input(&mut self) -> Keyboard {
+        match &mut self.frames_left {
+            Some(0) => self.hotkeys.push(Hotkey::Quit),
+            Some(frames_left) => *frames_left -= 1,
+            None => {}
+        }
+
+        Keyboard::None
+    }
+
+    fn take_hotkeys(&mut self) -> Vec<Hotkey> {
+        std::mem::take(&mut self.hotkeys)
+    }
+
+    fn palette(&self) -> Palette {
+        self.palette
+    }
+
+    fn draw_canvas(&mut self, _buffer : &[Vec<bool>]) {}
+
+    fn set_sound_timer(&mut self, _sound_timer : u8) {}
+
+    fn is_realtime(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,514 @@
+use std::{
+    io::{
+        ErrorKind,
+        Read,
+        Write
+    },
+    net::{
+        TcpListener,
+        TcpStream
+    },
+};
+
+use crate::{
+    Chip8,
+    MemoryAccess,
+    Watchpoint,
+    state::{ decode_hex, encode_hex }
+};
+
+/// what the chip8 is doing as far as gdb is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GdbState {
+    // stopped and waiting for gdb to say what to do next
+    Halted,
+
+    // running until a breakpoint, a watchpoint or gdb interrupting it
+    Running,
+
+    // running one instruction and then stopping again
+    Stepping,
+
+    // gdb left, the chip8 runs like gdb was never there
+    Detached,
+}
+
+/// this is a stub for gdb's remote serial protocol so a debugger can be attached to the chip8 over tcp
+///
+/// gdb has no chip8 architecture so the registers are described to it with a target description, in this order :
+/// v0 to vf (8 bits each), i (16 bits), pc (16 bits), sp (8 bits, how deep the stack is), dt and st (8 bits each).
+/// 16 bit registers are little endian in the g and p packets like gdb expects.
+///
+/// the stub is driven from the emulation loop by calling before_instruction before every instruction,
+/// while the chip8 is halted that call blocks and answers gdb until it continues or steps
+pub struct GdbStub {
+    stream : TcpStream,
+
+    // addresses to stop at before the instruction there runs
+    breakpoints : Vec<u16>,
+
+    state : GdbState,
+
+    // the instruction right after resuming runs even if there is a breakpoint on it so continuing doesn't stop in place
+    resuming : bool,
+
+    // gdb can ask to stop sending + and - for every packet
+    no_ack : bool,
+
+    // if the stream is set to not block, it doesn't while running so the chip8 can check for an interrupt
+    nonblocking : bool,
+}
+
+impl GdbStub {
+    // gdb sends this byte on its own when ctrl+c is pressed
+    const INTERRUPT : u8 = 0x03;
+
+    const REGISTER_COUNT : usize = 21;
+    const I_REGISTER : usize = 16;
+    const PC_REGISTER : usize = 17;
+    const SP_REGISTER : usize = 18;
+    const DT_REGISTER : usize = 19;
+    const ST_REGISTER : usize = 20;
+
+    // the biggest packet gdb is told it can send, in hex
+    const PACKET_SIZE : usize = 0x4000;
+
+    /// this fn waits for gdb to connect on a localhost port, EX : target remote localhost:1234
+    ///
+    /// the chip8 starts out halted so gdb can set breakpoints before anything runs
+    pub fn listen(port : u16) -> Result<GdbStub, String> {
+        let listener = match TcpListener::bind(("127.0.0.1", port)) {
+            Ok(listener) => listener,
+            Err(error) => return Err(format!("error : could not listen for gdb on port {} : {}!", port, error))
+        };
+
+        let stream = match listener.accept() {
+            Ok((stream, _address)) => stream,
+            Err(error) => return Err(format!("error : could not accept the gdb connection : {}!", error))
+        };
+        let _ = stream.set_nodelay(true);
+
+        Ok(GdbStub {
+            stream,
+            breakpoints : Vec::new(),
+            state : GdbState::Halted,
+            resuming : false,
+            no_ack : false,
+            nonblocking : false,
+        })
+    }
+
+    /// this fn is called before every instruction, it stops the chip8 when it should and then answers gdb until it resumes
+    ///
+    /// it gives back false when gdb kills the chip8
+    pub fn before_instruction(&mut self, chip8 : &mut Chip8) -> Result<bool, String> {
+        match self.state {
+            GdbState::Detached => return Ok(true),
+            GdbState::Halted => {},
+            GdbState::Stepping => {
+                self.state = GdbState::Halted;
+                let reason = self.stop_reason(chip8);
+                self.send_packet(&reason)?;
+            },
+            GdbState::Running => {
+                if chip8.hit_watchpoint() {
+                    self.state = GdbState::Halted;
+                    let reason = self.stop_reason(chip8);
+                    self.send_packet(&reason)?;
+                } else if !self.resuming && self.breakpoints.contains(&chip8.pc_reg) {
+                    self.state = GdbState::Halted;
+                    self.send_packet("S05")?;
+                } else if self.interrupted()? {
+                    self.state = GdbState::Halted;
+                    self.send_packet("S02")?;
+                }
+            },
+        }
+
+        self.resuming = false;
+
+        if self.state == GdbState::Halted {
+            return self.serve(chip8)
+        }
+
+        Ok(true)
+    }
+
+    /// this fn answers packets until gdb continues, steps, detaches or kills the chip8
+    fn serve(&mut self, chip8 : &mut Chip8) -> Result<bool, String> {
+        self.set_nonblocking(false)?;
+
+        while self.state == GdbState::Halted {
+            let packet = match self.read_packet()? {
+                Some(packet) => packet,
+                None => {
+                    // gdb hung up so the chip8 carries on without it
+                    self.state = GdbState::Detached;
+                    break
+                }
+            };
+
+            if packet == "k" {
+                return Ok(false)
+            }
+
+            let reply = self.handle_packet(chip8, &packet);
+            if let Some(reply) = reply {
+                self.send_packet(&reply)?;
+            }
+
+            // the OK for this still gets acknowledged, every packet after it doesn't
+            if packet == "QStartNoAckMode" {
+                self.no_ack = true;
+            }
+        }
+
+        // the same goes for an execute watchpoint on the instruction the chip8 stopped at
+        self.resuming = true;
+        chip8.resuming_execute = true;
+        Ok(true)
+    }
+
+    /// this fn runs one packet and gives back the reply, continuing and stepping don't reply until the chip8 stops
+    fn handle_packet(&mut self, chip8 : &mut Chip8, packet : &str) -> Option<String> {
+        // an empty reply tells gdb a packet isn't supported
+        if !packet.is_char_boundary(1) {
+            return Some(String::new())
+        }
+        let (command, arguments) = packet.split_at(1);
+
+        let reply = match command {
+            "?" => self.stop_reason(chip8),
+            "g" => (0..Self::REGISTER_COUNT).map(|register| Self::read_register(chip8, register)).collect(),
+            "G" => {
+                let mut bytes = match decode_hex(arguments) {
+                    Some(bytes) => bytes.into_iter(),
+                    None => return Some(String::from("E01"))
+                };
+
+                for register in 0..Self::REGISTER_COUNT {
+                    let value = match Self::register_size(register) {
+                        1 => bytes.next().map(|byte| byte as u16),
+                        _ => bytes.next().zip(bytes.next()).map(|(low, high)| u16::from_le_bytes([low, high])),
+                    };
+
+                    match value {
+                        Some(value) if Self::write_register(chip8, register, value) => {},
+                        _ => return Some(String::from("E01"))
+                    }
+                }
+                String::from("OK")
+            },
+            "p" => match usize::from_str_radix(arguments, 16) {
+                Ok(register) if register < Self::REGISTER_COUNT => Self::read_register(chip8, register),
+                _ => String::from("E01")
+            },
+            "P" => {
+                let register_and_value = arguments.split_once('=')
+                    .and_then(|(register, value)| Some((usize::from_str_radix(register, 16).ok()?, decode_hex(value)?)));
+
+                match register_and_value {
+                    Some((register, bytes)) if register < Self::REGISTER_COUNT && bytes.len() == Self::register_size(register) => {
+                        let value = match bytes[..] {
+                            [low] => low as u16,
+                            [low, high] => u16::from_le_bytes([low, high]),
+                            _ => 0
+                        };
+                        if Self::write_register(chip8, register, value) {
+                            String::from("OK")
+                        } else {
+                            String::from("E01")
+                        }
+                    },
+                    _ => String::from("E01")
+                }
+            },
+            "m" => match Self::parse_address_length(arguments) {
+                Some((address, length)) => match address.checked_add(length).and_then(|end| chip8.memory.get(address..end)) {
+                    Some(bytes) => encode_hex(bytes),
+                    None => String::from("E01")
+                },
+                None => String::from("E01")
+            },
+            "M" => {
+                let write = arguments.split_once(':')
+                    .and_then(|(range, data)| Some((Self::parse_address_length(range)?, decode_hex(data)?)));
+
+                match write {
+                    Some(((address, length), bytes)) if bytes.len() == length && chip8.poke(address, &bytes).is_ok() => String::from("OK"),
+                    _ => String::from("E01")
+                }
+            },
+            "c" | "s" => {
+                // these can give an address to resume from
+                if let Ok(address) = u16::from_str_radix(arguments, 16) {
+                    chip8.pc_reg = address;
+                }
+
+                self.state = if command == "c" { GdbState::Running } else { GdbState::Stepping };
+                return None
+            },
+            "D" => {
+                self.state = GdbState::Detached;
+                String::from("OK")
+            },
+            "Z" | "z" => self.handle_breakpoint(chip8, command == "Z", arguments),
+            "H" => String::from("OK"),
+            "T" => String::from("OK"),
+            "q" | "Q" => self.handle_query(packet),
+            _ => String::new()
+        };
+
+        Some(reply)
+    }
+
+    /// this fn adds or removes a breakpoint or watchpoint, EX : Z0,2a4,2 or z2,3f0,1
+    fn handle_breakpoint(&mut self, chip8 : &mut Chip8, insert : bool, arguments : &str) -> String {
+        let mut parts = arguments.split(',');
+        let (kind, address, length) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(kind), Some(address), Some(length)) => (kind, address, length),
+            _ => return String::from("E01")
+        };
+
+        let (address, end) = match (usize::from_str_radix(address, 16), usize::from_str_radix(length, 16)) {
+            (Ok(address), Ok(length)) if address < chip8.memory.len() => match address.checked_add(length.max(1) - 1) {
+                Some(end) => (address, end.min(chip8.memory.len() - 1)),
+                None => return String::from("E01")
+            },
+            _ => return String::from("E01")
+        };
+
+        // gdb's watchpoint kinds are 2 for writes, 3 for reads and 4 for both
+        let (read, write) = match kind {
+            "0" | "1" => {
+                let address = address as u16;
+                if insert {
+                    if !self.breakpoints.contains(&address) {
+                        self.breakpoints.push(address);
+                    }
+                } else {
+                    self.breakpoints.retain(|breakpoint| *breakpoint != address);
+                }
+                return String::from("OK")
+            },
+            "2" => (false, true),
+            "3" => (true, false),
+            "4" => (true, true),
+            _ => return String::new()
+        };
+
+        let watchpoint = Watchpoint::new(address, end, read, write, false);
+
+        if insert {
+            chip8.watchpoints.push(watchpoint);
+        } else if let Some(index) = chip8.watchpoints.iter().position(|existing| *existing == watchpoint) {
+            chip8.watchpoints.remove(index);
+        }
+
+        String::from("OK")
+    }
+
+    fn handle_query(&mut self, packet : &str) -> String {
+        match packet {
+            packet if packet.starts_with("qSupported") => {
+                format!("PacketSize={:x};qXfer:features:read+;QStartNoAckMode+;swbreak+;hwbreak+", Self::PACKET_SIZE)
+            },
+            "QStartNoAckMode" => String::from("OK"),
+            "qAttached" => String::from("1"),
+            "qC" => String::from("QC1"),
+            "qfThreadInfo" => String::from("m1"),
+            "qsThreadInfo" => String::from("l"),
+            packet if packet.starts_with("qXfer:features:read:target.xml:") => {
+                let range = &packet["qXfer:features:read:target.xml:".len()..];
+                let (offset, length) = match Self::parse_address_length(range) {
+                    Some(range) => range,
+                    None => return String::from("E01")
+                };
+
+                let description = Self::target_description();
+                let start = offset.min(description.len());
+                let end = offset.saturating_add(length).min(description.len());
+
+                // m means there is more to read and l means this is the last of it
+                let more = if end < description.len() { "m" } else { "l" };
+                format!("{}{}", more, &description[start..end])
+            },
+            _ => String::new()
+        }
+    }
+
+    /// this fn gives back why the chip8 stopped, a watchpoint says which address it was
+    fn stop_reason(&mut self, chip8 : &mut Chip8) -> String {
+        match chip8.resume_from_watchpoints().first() {
+            Some(hit) => {
+                let kind = match hit.access {
+                    MemoryAccess::Read => "rwatch",
+                    MemoryAccess::Write => "watch",
+                    // gdb stops at these the same as at a breakpoint, an access watchpoint would be awatch
+                    MemoryAccess::Execute => return String::from("T05hwbreak:;"),
+                };
+                format!("T05{}:{:x};", kind, hit.address)
+            },
+            None => String::from("S05")
+        }
+    }
+
+    /// this fn describes the chip8's registers to gdb
+    fn target_description() -> String {
+        let mut registers = (0..16)
+            .map(|register| format!("<reg name=\"v{:x}\" bitsize=\"8\" regnum=\"{}\"/>", register, register))
+            .collect::<Vec<String>>();
+
+        registers.push(format!("<reg name=\"i\" bitsize=\"16\" type=\"data_ptr\" regnum=\"{}\"/>", Self::I_REGISTER));
+        registers.push(format!("<reg name=\"pc\" bitsize=\"16\" type=\"code_ptr\" regnum=\"{}\"/>", Self::PC_REGISTER));
+        registers.push(format!("<reg name=\"sp\" bitsize=\"8\" regnum=\"{}\"/>", Self::SP_REGISTER));
+        registers.push(format!("<reg name=\"dt\" bitsize=\"8\" regnum=\"{}\"/>", Self::DT_REGISTER));
+        registers.push(format!("<reg name=\"st\" bitsize=\"8\" regnum=\"{}\"/>", Self::ST_REGISTER));
+
+        format!(
+            "<?xml version=\"1.0\"?><!DOCTYPE target SYSTEM \"gdb-target.dtd\"><target version=\"1.0\"><feature name=\"org.rip8.chip8\">{}</feature></target>",
+            registers.concat()
+        )
+    }
+
+    fn register_size(register : usize) -> usize {
+        match register {
+            Self::I_REGISTER | Self::PC_REGISTER => 2,
+            _ => 1
+        }
+    }
+
+    fn read_register(chip8 : &Chip8, register : usize) -> String {
+        match register {
+            0..=15 => format!("{:02x}", chip8.v_regs[register]),
+            Self::I_REGISTER => encode_hex(&chip8.index_reg.to_le_bytes()),
+            Self::PC_REGISTER => encode_hex(&chip8.pc_reg.to_le_bytes()),
+            Self::SP_REGISTER => format!("{:02x}", chip8.stack.len()),
+            Self::DT_REGISTER => format!("{:02x}", chip8.delay_timer_register),
+            _ => format!("{:02x}", chip8.sound_timer_register),
+        }
+    }
+
+    /// this fn gives back false when the value can't go in the register
+    fn write_register(chip8 : &mut Chip8, register : usize, value : u16) -> bool {
+        match register {
+            0..=15 => chip8.v_regs[register] = value as u8,
+            Self::I_REGISTER => chip8.index_reg = value,
+            Self::PC_REGISTER => chip8.pc_reg = value,
+            // the stack isn't in memory so changing sp can only drop entries or add empty ones
+            Self::SP_REGISTER if value as usize > Chip8::STACK_SIZE => return false,
+            Self::SP_REGISTER => chip8.stack.resize(value as usize, 0),
+            Self::DT_REGISTER => chip8.delay_timer_register = value as u8,
+            _ => chip8.sound_timer_register = value as u8,
+        }
+
+        true
+    }
+
+    /// this fn reads a range like 2a0,10
+    fn parse_address_length(text : &str) -> Option<(usize, usize)> {
+        let (address, length) = text.split_once(',')?;
+        Some((usize::from_str_radix(address, 16).ok()?, usize::from_str_radix(length, 16).ok()?))
+    }
+
+    /// this fn checks if gdb sent an interrupt without waiting for one
+    fn interrupted(&mut self) -> Result<bool, String> {
+        self.set_nonblocking(true)?;
+
+        let mut byte = [0];
+        match self.stream.read(&mut byte) {
+            Ok(0) => {
+                self.state = GdbState::Detached;
+                Ok(false)
+            },
+            Ok(_) => Ok(byte[0] == Self::INTERRUPT),
+            Err(error) if error.kind() == ErrorKind::WouldBlock => Ok(false),
+            Err(error) => Err(format!("error : lost the gdb connection : {}!", error))
+        }
+    }
+
+    fn set_nonblocking(&mut self, nonblocking : bool) -> Result<(), String> {
+        if self.nonblocking != nonblocking {
+            if let Err(error) = self.stream.set_nonblocking(nonblocking) {
+                return Err(format!("error : lost the gdb connection : {}!", error))
+            }
+            self.nonblocking = nonblocking;
+        }
+
+        Ok(())
+    }
+
+    fn read_byte(&mut self) -> Result<Option<u8>, String> {
+        let mut byte = [0];
+        match self.stream.read(&mut byte) {
+            Ok(0) => Ok(None),
+            Ok(_) => Ok(Some(byte[0])),
+            Err(error) => Err(format!("error : lost the gdb connection : {}!", error))
+        }
+    }
+
+    /// this fn reads the next packet, a packet looks like $data#checksum
+    ///
+    /// acks and interrupts between packets are skipped, none means gdb hung up
+    fn read_packet(&mut self) -> Result<Option<String>, String> {
+        loop {
+            match self.read_byte()? {
+                Some(b'$') => {},
+                Some(_) => continue,
+                None => return Ok(None)
+            }
+
+            let mut data = Vec::new();
+            loop {
+                match self.read_byte()? {
+                    Some(b'#') => break,
+                    Some(byte) => data.push(byte),
+                    None => return Ok(None)
+                }
+            }
+
+            let checksum = match (self.read_byte()?, self.read_byte()?) {
+                (Some(high), Some(low)) => std::str::from_utf8(&[high, low]).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+                _ => return Ok(None)
+            };
+
+            let valid = checksum == Some(data.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)));
+
+            if !self.no_ack {
+                self.write_all(if valid { b"+" } else { b"-" })?;
+            }
+
+            if valid {
+                return Ok(Some(String::from_utf8_lossy(&data).to_string()))
+            }
+        }
+    }
+
+    fn send_packet(&mut self, data : &str) -> Result<(), String> {
+        let checksum = data.bytes().fold(0u8, |sum, byte| sum.wrapping_add(byte));
+        self.write_all(format!("${}#{:02x}", data, checksum).as_bytes())
+    }
+
+    fn write_all(&mut self, bytes : &[u8]) -> Result<(), String> {
+        // writes always block, the stream is only left nonblocking for checking for interrupts
+        let nonblocking = self.nonblocking;
+        self.set_nonblocking(false)?;
+
+        let result = self.stream.write_all(bytes).and_then(|_| self.stream.flush());
+        self.set_nonblocking(nonblocking)?;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(format!("error : lost the gdb connection : {}!", error))
+        }
+    }
+}
+
+impl Drop for GdbStub {
+    /// this tells gdb the chip8 is gone when the emulator stops while gdb is still attached
+    fn drop(&mut self) {
+        if self.state != GdbState::Detached {
+            let _ = self.send_packet("W00");
+        }
+    }
+}
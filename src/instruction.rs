@@ -0,0 +1,154 @@
+use crate::{
+    Chip8,
+    chip_8::BinaryOp
+};
+
+impl Chip8 {
+    /// these run an opcode, picked by its first nybble so finding the right one is a single lookup
+    ///
+    /// each one only picks apart the parts of the opcode it uses, opcodes that don't match anything leave the chip8
+    /// where it is
+    pub(crate) const OPCODE_HANDLERS : [fn(&mut Chip8, u16); 16] = [
+        Self::opcode_0nnn,
+        Self::opcode_1nnn,
+        Self::opcode_2nnn,
+        Self::opcode_3xnn,
+        Self::opcode_4xnn,
+        Self::opcode_5xy0,
+        Self::opcode_6xnn,
+        Self::opcode_7xnn,
+        Self::opcode_8xyn,
+        Self::opcode_9xy0,
+        Self::opcode_annn,
+        Self::opcode_bnnn,
+        Self::opcode_cxnn,
+        Self::opcode_dxyn,
+        Self::opcode_exnn,
+        Self::opcode_fxnn,
+    ];
+
+    /// this fn gives back the register in the second nybble of an opcode
+    fn opcode_x(opcode : u16) -> usize {
+        ((opcode >> 8) & 0xF) as usize
+    }
+
+    /// this fn gives back the register in the third nybble of an opcode
+    fn opcode_y(opcode : u16) -> usize {
+        ((opcode >> 4) & 0xF) as usize
+    }
+
+    // 00E0 00EE
+    fn opcode_0nnn(&mut self, opcode : u16) {
+        match opcode {
+            0x00E0 => self.clear_display_instruction(),
+            0x00EE => self.return_instruction(),
+            _ => {}
+        }
+    }
+
+    fn opcode_1nnn(&mut self, opcode : u16) {
+        self.jump_instruction(opcode & 0xFFF)
+    }
+
+    fn opcode_2nnn(&mut self, opcode : u16) {
+        self.call_instruction(opcode & 0xFFF)
+    }
+
+    // the skips are almost all the same and have one function for them, the last argument is weather the skip
+    // should happen if the two values are the same or not
+    fn opcode_3xnn(&mut self, opcode : u16) {
+        self.skipif_vx_reg_nn_instruction(self.v_regs[Self::opcode_x(opcode)], opcode as u8, true)
+    }
+
+    fn opcode_4xnn(&mut self, opcode : u16) {
+        self.skipif_vx_reg_nn_instruction(self.v_regs[Self::opcode_x(opcode)], opcode as u8, false)
+    }
+
+    // the last nybble isn't checked
+    fn opcode_5xy0(&mut self, opcode : u16) {
+        self.skipif_vx_reg_nn_instruction(self.v_regs[Self::opcode_x(opcode)], self.v_regs[Self::opcode_y(opcode)], true)
+    }
+
+    fn opcode_6xnn(&mut self, opcode : u16) {
+        self.set_vx_reg_instruction(Self::opcode_x(opcode), opcode as u8)
+    }
+
+    fn opcode_7xnn(&mut self, opcode : u16) {
+        self.add_reg_vx_instruction(Self::opcode_x(opcode), opcode as u8, false)
+    }
+
+    // 8XY0 8XY1 8XY2 8XY3 8XY4 8XY5 8XY6 8XY7 8XYE
+    fn opcode_8xyn(&mut self, opcode : u16) {
+        let reg = Self::opcode_x(opcode);
+        let num = self.v_regs[Self::opcode_y(opcode)];
+
+        match opcode & 0xF {
+            0x0 => self.set_vx_reg_instruction(reg, num),
+            // binary operation instructions
+            0x1 => self.bin_op_vx_reg_instruction(reg, num, BinaryOp::Or),
+            0x2 => self.bin_op_vx_reg_instruction(reg, num, BinaryOp::And),
+            0x3 => self.bin_op_vx_reg_instruction(reg, num, BinaryOp::Xor),
+            // add and subtract with carry
+            0x4 => self.add_reg_vx_instruction(reg, num, true),
+            0x5 => self.subtract_vx_reg_instruction(reg, num, false),
+            0x7 => self.subtract_vx_reg_instruction(reg, num, true),
+            0x6 => self.shift_vx_register(reg, num, true),
+            0xE => self.shift_vx_register(reg, num, false),
+            _ => {}
+        }
+    }
+
+    // the last nybble isn't checked
+    fn opcode_9xy0(&mut self, opcode : u16) {
+        self.skipif_vx_reg_nn_instruction(self.v_regs[Self::opcode_x(opcode)], self.v_regs[Self::opcode_y(opcode)], false)
+    }
+
+    fn opcode_annn(&mut self, opcode : u16) {
+        self.set_index_reg_instruction(opcode & 0xFFF)
+    }
+
+    fn opcode_bnnn(&mut self, opcode : u16) {
+        self.jump_with_offset_instruction(Self::opcode_x(opcode), opcode & 0xFFF)
+    }
+
+    fn opcode_cxnn(&mut self, opcode : u16) {
+        self.random_instruction(Self::opcode_x(opcode), opcode as u8)
+    }
+
+    fn opcode_dxyn(&mut self, opcode : u16) {
+        let x_coordinate = self.v_regs[Self::opcode_x(opcode)];
+        let y_coordinate = self.v_regs[Self::opcode_y(opcode)];
+        self.draw_sprite_instruction(x_coordinate, y_coordinate, (opcode & 0xF) as u8)
+    }
+
+    // EX9E EXA1
+    fn opcode_exnn(&mut self, opcode : u16) {
+        // there are only 16 keys so these only look at the low nybble of vx, no key never matches
+        let key = self.v_regs[Self::opcode_x(opcode)] & 0xF;
+
+        match opcode & 0xFF {
+            0x9E => self.skipif_vx_reg_nn_instruction(key, self.keyboard.get_keycode(), true),
+            0xA1 => self.skipif_vx_reg_nn_instruction(key, self.keyboard.get_keycode(), false),
+            _ => {}
+        }
+    }
+
+    // FX07 FX0A FX15 FX18 FX1E FX29 FX33 FX55 FX65
+    fn opcode_fxnn(&mut self, opcode : u16) {
+        let reg = Self::opcode_x(opcode);
+
+        match opcode & 0xFF {
+            0x07 => self.set_vx_reg_instruction(reg, self.delay_timer_register),
+            0x0A => self.get_key_instruction(reg),
+            0x15 => self.set_delay_timer_reg_instruction(self.v_regs[reg]),
+            0x18 => self.set_sound_timer_reg_instruction(self.v_regs[reg]),
+            0x1E => self.add_to_index_reg_instruction(self.v_regs[reg] as u16),
+            // there are only 16 font chars so only the low nybble of vx picks one
+            0x29 => self.set_index_to_font_char_instruction((self.v_regs[reg] & 0xF) as usize),
+            0x33 => self.bcd_instruction(reg),
+            0x55 => self.store_to_memory_instruction(reg),
+            0x65 => self.load_from_memory_instruction(reg),
+            _ => {}
+        }
+    }
+}
@@ -41,4 +41,87 @@ impl Keyboard {
             _ => 0xff 
         }
     }
-}
\ No newline at end of file
+
+    /// this fn gets the key for a given keycode
+    /// 
+    /// any keycode that is not 0x0 - 0xf will give Keyboard::None
+    pub fn from_keycode(key_code : u8) -> Keyboard {
+        match key_code {
+            0x0 => Keyboard::Key0,
+            0x1 => Keyboard::Key1,
+            0x2 => Keyboard::Key2,
+            0x3 => Keyboard::Key3,
+            0x4 => Keyboard::Key4,
+            0x5 => Keyboard::Key5,
+            0x6 => Keyboard::Key6,
+            0x7 => Keyboard::Key7,
+            0x8 => Keyboard::Key8,
+            0x9 => Keyboard::Key9,
+            0xA => Keyboard::KeyA,
+            0xB => Keyboard::KeyB,
+            0xC => Keyboard::KeyC,
+            0xD => Keyboard::KeyD,
+            0xE => Keyboard::KeyE,
+            0xF => Keyboard::KeyF,
+            _ => Keyboard::None
+        }
+    }
+}
+
+/// these are the controls for the emulator itself that are not part of the chip8 keypad
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hotkey {
+    Quit,
+    Pause,
+    FrameAdvance,
+    Reset,
+    SpeedUp,
+    SlowDown,
+    // fast forward runs unthrottled for as long as the key is held
+    FastForward(bool),
+    // native screenshots are the display at its real resolution instead of what the window shows
+    Screenshot { native : bool },
+    // starts or stops recording the display to a gif
+    ToggleCapture,
+    // turns the buzzer off or back on
+    ToggleMute,
+}
+
+/// this maps keys on the keyboard to the chip8 keypad
+///
+/// keys are named the way browsers name them since that is what octo cartridges use, letters are lowercase,
+/// EX : "x", "1", "ArrowUp", "Enter" or " " for space
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    // the names of the keys for every chip8 key from 0x0 to 0xF
+    pub keys : Vec<Vec<String>>,
+}
+
+impl Keymap {
+    /// this is the left side of the keyboard laid out like the keypad
+    ///
+    /// 1 2 3 4        1 2 3 C
+    /// Q W E R   ->   4 5 6 D
+    /// A S D F        7 8 9 E
+    /// Z X C V        A 0 B F
+    pub fn new() -> Keymap {
+        let layout = ["x", "1", "2", "3", "q", "w", "e", "a", "s", "d", "z", "c", "4", "r", "f", "v"];
+
+        Keymap {
+            keys : layout.iter().map(|key| vec![key.to_string()]).collect(),
+        }
+    }
+
+    /// this fn gives back the chip8 key a keyboard key is mapped to
+    pub fn key_for(&self, name : &str) -> Option<Keyboard> {
+        self.keys.iter()
+            .position(|names| names.iter().any(|key| key == name))
+            .map(|key_code| Keyboard::from_keycode(key_code as u8))
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Keymap {
+        Keymap::new()
+    }
+}
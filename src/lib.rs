@@ -1,14 +1,102 @@
+pub mod frontend;
+pub use frontend::{ Frontend, HeadlessFrontend };
+
+#[cfg(feature = "sdl")]
 pub mod chip_8_window;
+#[cfg(feature = "sdl")]
 pub use chip_8_window::Chip8Window;
 
 pub mod keyboard;
-pub use keyboard::Keyboard;
+pub use keyboard::{ Keyboard, Hotkey, Keymap };
 
 pub mod font;
 pub use font::Font;
 
+pub mod audio;
+pub use audio::{ AudioConfig, SharedSound, ToneGenerator, Waveform };
+
+pub mod quirks;
+pub use quirks::Quirks;
+
+pub mod watchpoint;
+pub use watchpoint::{ MemoryAccess, Watchpoint, WatchHit };
+
+pub mod instruction;
+
 pub mod chip_8;
 pub use chip_8::Chip8;
 
+pub mod memory_view;
+pub use memory_view::{ MemoryRegion, MemoryRow };
+
+pub mod rom;
+
+pub mod reload;
+pub use reload::RomWatcher;
+
+pub mod disassembler;
+
+pub mod lint;
+pub use lint::{ LintIssue, LintReport, Severity };
+
+pub mod profiler;
+pub use profiler::Profiler;
+
+pub mod coverage;
+pub use coverage::Coverage;
+
+pub mod octo;
+
+pub mod cartridge;
+pub use cartridge::{ Cartridge, CartridgeOptions };
+
+pub mod cheat;
+pub use cheat::{ Cheat, CheatList, MemorySearch, SearchFilter };
+
+pub mod state;
+
+pub mod env;
+pub use env::{ Env, Step };
+
+pub mod control;
+pub use control::{ ControlAction, ControlServer, ControlStatus };
+
+pub mod gdb;
+pub use gdb::GdbStub;
+
+pub mod movie;
+pub use movie::{ Movie, MovieFrame, MovieRecorder };
+
+pub mod wav;
+pub use wav::WavWriter;
+
+pub mod screenshot;
+pub use screenshot::{ Palette, ScreenshotView };
+
+pub mod video;
+pub use video::VideoCapture;
+
+#[cfg(feature = "terminal")]
+pub mod terminal;
+#[cfg(feature = "terminal")]
+pub use terminal::{ TerminalConfig, TerminalStyle, TerminalWindow };
+
+#[cfg(feature = "scripting")]
+pub mod script;
+#[cfg(feature = "scripting")]
+pub use script::{ Script, ScriptEvents };
+
+// the emulator loop only uses the frontends and scripts it was built with
 pub mod emulator;
-pub use emulator::run_emulator;
\ No newline at end of file
+pub use emulator::{ run_emulator, run_frontend, EmulatorConfig };
+
+// the debugger prints with crossterm's colors
+#[cfg(feature = "terminal")]
+pub mod debugger;
+#[cfg(feature = "terminal")]
+pub use debugger::run_debugger;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "wasm")]
+pub use wasm::WasmChip8;
@@ -0,0 +1,454 @@
+use std::collections::{
+    HashMap,
+    HashSet
+};
+
+use crate::{
+    Chip8,
+    Quirks,
+    disassembler
+};
+
+/// how bad a lint problem is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    // the rom will misbehave or crash if this runs
+    Error,
+
+    // the rom might work but only with the right quirks or by luck
+    Warning,
+}
+
+/// this is one problem lint found, at the address of the instruction it is about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    pub address : usize,
+    pub severity : Severity,
+    pub message : String,
+}
+
+/// this is everything lint found in a rom
+#[derive(Debug, Clone)]
+pub struct LintReport {
+    // sorted by address
+    pub issues : Vec<LintIssue>,
+
+    // how many instructions can be reached from the start of the rom
+    pub reachable_instructions : usize,
+
+    // the name of the quirks the rom most likely needs, none if nothing in it points at any
+    pub suggested_quirks : Option<&'static str>,
+
+    // what the suggestion is based on
+    pub reasons : Vec<String>,
+}
+
+impl LintReport {
+    pub fn has_errors(&self) -> bool {
+        self.issues.iter().any(|issue| issue.severity == Severity::Error)
+    }
+
+    /// this fn writes the report out the way rip8 lint prints it
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+
+        for issue in self.issues.iter() {
+            let severity = match issue.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            text += &format!("{:03X}  {:<8} {}\n", issue.address, severity, issue.message);
+        }
+
+        let errors = self.issues.iter().filter(|issue| issue.severity == Severity::Error).count();
+        text += &format!(
+            "\n{} reachable instructions, {} errors, {} warnings\n",
+            self.reachable_instructions, errors, self.issues.len() - errors
+        );
+
+        match self.suggested_quirks {
+            Some(name) => {
+                text += &format!("the rom probably needs the {} quirks (--quirks {}) because :\n", name, name);
+                for reason in self.reasons.iter() {
+                    text += &format!("    {}\n", reason);
+                }
+            },
+            None => text += "nothing in the rom depends on which quirks it runs with\n"
+        }
+
+        text
+    }
+}
+
+/// this fn walks every instruction that can be reached from the start of a rom and reports what looks wrong with it
+///
+/// code is found by following jumps, calls and skips from 0x200 so data is never read as code, but code that is only
+/// reached through BNNN can't be found. the quirks are the ones the rom is meant to run with, instructions that act
+/// differently with other quirks are pointed out along with what the quirks given make them do
+pub fn lint(rom : &[u8], quirks : Quirks) -> LintReport {
+    let mut linter = Linter::new(rom, quirks);
+    let code = linter.trace();
+    linter.check_index_register(&code);
+
+    let mut issues = linter.issues;
+    issues.sort_by(|a, b| a.address.cmp(&b.address).then(a.severity.cmp(&b.severity)));
+    issues.dedup();
+
+    let (suggested_quirks, reasons) = suggest_quirks(&linter.evidence);
+
+    LintReport {
+        issues,
+        reachable_instructions : code.iter().filter(|is_code| **is_code).count(),
+        suggested_quirks,
+        reasons,
+    }
+}
+
+/// this fn gives back the extension and a description of an instruction chip8 doesn't have but superchip or xo-chip do
+fn extension_instruction(instruction : u16) -> Option<(&'static str, &'static str)> {
+    let x = (instruction >> 8) & 0xF;
+
+    match (instruction >> 12, instruction & 0x00FF) {
+        (0x0, 0xC1..=0xCF) if x == 0 => Some(("superchip", "00CN scrolls down")),
+        (0x0, 0xFB) if x == 0 => Some(("superchip", "00FB scrolls right")),
+        (0x0, 0xFC) if x == 0 => Some(("superchip", "00FC scrolls left")),
+        (0x0, 0xFD) if x == 0 => Some(("superchip", "00FD exits")),
+        (0x0, 0xFE) if x == 0 => Some(("superchip", "00FE switches to low resolution")),
+        (0x0, 0xFF) if x == 0 => Some(("superchip", "00FF switches to high resolution")),
+        (0xD, _) if instruction & 0x000F == 0 => Some(("superchip", "DXY0 draws a 16x16 sprite")),
+        (0xF, 0x30) => Some(("superchip", "FX30 points I at the big font")),
+        (0xF, 0x75) => Some(("superchip", "FX75 saves registers to the flags")),
+        (0xF, 0x85) => Some(("superchip", "FX85 loads registers from the flags")),
+        (0x0, 0xD1..=0xDF) if x == 0 => Some(("xo-chip", "00DN scrolls up")),
+        (0x5, _) if instruction & 0x000F == 2 => Some(("xo-chip", "5XY2 saves a range of registers")),
+        (0x5, _) if instruction & 0x000F == 3 => Some(("xo-chip", "5XY3 loads a range of registers")),
+        (0xF, 0x00) if x == 0 => Some(("xo-chip", "F000 NNNN loads a 16 bit address into I")),
+        (0xF, 0x01) => Some(("xo-chip", "FN01 picks drawing planes")),
+        (0xF, 0x02) if x == 0 => Some(("xo-chip", "F002 loads an audio pattern")),
+        (0xF, 0x3A) => Some(("xo-chip", "FX3A sets the pitch")),
+        _ => None
+    }
+}
+
+/// this is what the instructions of a rom say about the quirks it was written for
+#[derive(Debug, Clone, Default)]
+struct QuirkEvidence {
+    uses_superchip : bool,
+    uses_xo_chip : bool,
+
+    // 8XY6 or 8XYE with two different registers, which only makes sense when vy is shifted into vx
+    shifts_other_register : bool,
+
+    // I is used right after FX55 or FX65 without being set again, so the rom expects them to move I
+    uses_moved_index : bool,
+}
+
+/// this fn picks the quirks that fit what a rom's instructions say about it
+fn suggest_quirks(evidence : &QuirkEvidence) -> (Option<&'static str>, Vec<String>) {
+    let mut reasons = Vec::new();
+
+    if evidence.uses_superchip {
+        reasons.push(String::from("it uses superchip instructions"));
+    }
+    if evidence.uses_xo_chip {
+        reasons.push(String::from("it uses xo-chip instructions"));
+    }
+    if evidence.shifts_other_register {
+        reasons.push(String::from("it shifts one register into another, which only works without the shift quirk"));
+    }
+    if evidence.uses_moved_index {
+        reasons.push(String::from("it uses I after FX55 or FX65 moved it, which only works without the load_store quirk"));
+    }
+
+    let suggestion = if evidence.uses_xo_chip {
+        Some("octo")
+    } else if evidence.uses_superchip && !evidence.shifts_other_register && !evidence.uses_moved_index {
+        Some("schip")
+    } else if evidence.uses_superchip {
+        // superchip instructions with the original shift and load_store is how octo runs superchip games
+        Some("octo")
+    } else if evidence.shifts_other_register || evidence.uses_moved_index {
+        Some("chip8")
+    } else {
+        None
+    };
+
+    (suggestion, reasons)
+}
+
+/// what is known about the index register at an instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IndexValue {
+    Known(usize),
+    Unknown,
+
+    // FX55 or FX65 just ran, so where I is depends on the load_store quirk
+    Moved,
+}
+
+impl IndexValue {
+    /// this fn combines what is known about I from two paths to the same instruction
+    fn merge(self, other : IndexValue) -> IndexValue {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (IndexValue::Moved, _) | (_, IndexValue::Moved) => IndexValue::Moved,
+            _ => IndexValue::Unknown
+        }
+    }
+}
+
+struct Linter {
+    memory : Vec<u8>,
+
+    // the first address after the rom
+    rom_end : usize,
+
+    quirks : Quirks,
+    issues : Vec<LintIssue>,
+    evidence : QuirkEvidence,
+}
+
+impl Linter {
+    fn new(rom : &[u8], quirks : Quirks) -> Linter {
+        let start = Chip8::PROGRAM_START as usize;
+        let rom = &rom[..rom.len().min(Chip8::MAX_ROM_SIZE)];
+
+        let mut memory = vec![0; start + Chip8::MAX_ROM_SIZE];
+        memory[start..start + rom.len()].copy_from_slice(rom);
+
+        Linter {
+            memory,
+            rom_end : start + rom.len(),
+            quirks,
+            issues : Vec::new(),
+            evidence : QuirkEvidence::default(),
+        }
+    }
+
+    fn issue(&mut self, address : usize, severity : Severity, message : String) {
+        self.issues.push(LintIssue { address, severity, message });
+    }
+
+    fn instruction_at(&self, address : usize) -> u16 {
+        ((self.memory[address] as u16) << 8) | self.memory[address + 1] as u16
+    }
+
+    /// this fn follows every path from the start of the rom, checking each instruction and each call and return
+    ///
+    /// it gives back true for every address an instruction starts at. every path is followed once for the main program
+    /// and once for each subroutine it is in, so a return can be checked against a call
+    fn trace(&mut self) -> Vec<bool> {
+        let start = Chip8::PROGRAM_START as usize;
+
+        let mut code = vec![false; self.memory.len()];
+        let mut visited : HashSet<(usize, Option<usize>)> = HashSet::new();
+
+        // every subroutine that is called and if a return was found in it
+        let mut subroutines : HashMap<usize, bool> = HashMap::new();
+
+        let mut paths : Vec<(usize, Option<usize>)> = vec![(start, None)];
+
+        while let Some((address, subroutine)) = paths.pop() {
+            if !visited.insert((address, subroutine)) {
+                continue
+            }
+
+            if address + 1 >= self.memory.len() {
+                self.issue(address, Severity::Error, String::from("runs off the end of memory"));
+                continue
+            }
+            if address >= self.rom_end {
+                self.issue(address, Severity::Warning, String::from("runs past the end of the rom into empty memory"));
+                continue
+            }
+
+            let instruction = self.instruction_at(address);
+            let class = disassembler::opcode_class(instruction);
+            let target = (instruction & 0x0FFF) as usize;
+            let next = address + 2;
+
+            if !code[address] {
+                code[address] = true;
+
+                // an instruction that doesn't do anything is most likely data, so the path stops there
+                if !self.check_instruction(address, instruction) {
+                    continue
+                }
+            }
+
+            match class {
+                "00EE" => match subroutine {
+                    Some(entry) => { subroutines.insert(entry, true); },
+                    None => self.issue(address, Severity::Error, String::from("returns when nothing called it, the stack is empty")),
+                },
+                "1NNN" => if self.check_target(address, target, "jumps") {
+                    paths.push((target, subroutine));
+                },
+                "2NNN" => {
+                    if self.check_target(address, target, "calls") {
+                        subroutines.entry(target).or_insert(false);
+                        paths.push((target, Some(target)));
+                    }
+                    paths.push((next, subroutine));
+                },
+                "BNNN" => { self.check_target(address, target, "jumps"); },
+                "3XNN" | "4XNN" | "5XY0" | "9XY0" | "EX9E" | "EXA1" => paths.extend([(next, subroutine), (next + 2, subroutine)]),
+                _ => paths.push((next, subroutine))
+            }
+        }
+
+        for (entry, returns) in subroutines {
+            if !returns {
+                self.issue(entry, Severity::Warning, String::from("this subroutine never returns, every call to it leaves an address on the stack"));
+            }
+        }
+
+        code
+    }
+
+    /// this fn checks that a jump or call lands somewhere in the rom
+    fn check_target(&mut self, address : usize, target : usize, verb : &str) -> bool {
+        if target < Chip8::PROGRAM_START as usize {
+            self.issue(address, Severity::Error, format!("{} to {:03X} which is the interpreter's memory, not the rom", verb, target));
+            return false
+        }
+
+        if target >= self.rom_end {
+            self.issue(address, Severity::Warning, format!("{} to {:03X} which is past the end of the rom", verb, target));
+            return false
+        }
+
+        true
+    }
+
+    /// this fn checks one instruction on its own and gives back false if it doesn't do anything on a chip8
+    fn check_instruction(&mut self, address : usize, instruction : u16) -> bool {
+        let class = disassembler::opcode_class(instruction);
+        let x = (instruction >> 8) & 0xF;
+        let y = (instruction >> 4) & 0xF;
+
+        if let Some((extension, description)) = extension_instruction(instruction) {
+            match extension {
+                "superchip" => self.evidence.uses_superchip = true,
+                _ => self.evidence.uses_xo_chip = true,
+            }
+
+            self.issue(address, Severity::Error, format!("{:04X} is a {} instruction that chip8 doesn't have, {}", instruction, extension, description));
+
+            // 00FD stops the program, everything else carries on
+            return instruction != 0x00FD
+        }
+
+        match class {
+            "????" => {
+                self.issue(address, Severity::Error, format!("{:04X} isn't an instruction, it does nothing", instruction));
+                return false
+            },
+            "0NNN" => {
+                self.issue(address, Severity::Warning, format!("{:04X} calls machine code on the cosmac vip, interpreters skip it", instruction));
+                return false
+            },
+            "5XY0" | "9XY0" if instruction & 0x000F != 0 => {
+                self.issue(address, Severity::Warning, format!("{:04X} runs as {}, its last digit should be 0", instruction, disassembler::disassemble(instruction & 0xFFF0)));
+            },
+            "8XY6" | "8XYE" if x != y => {
+                self.evidence.shifts_other_register = true;
+
+                let what_it_does = match self.quirks.shift {
+                    true => format!("the quirks given shift v{:X} in place and ignore v{:X}", x, y),
+                    false => format!("the quirks given shift v{:X} into v{:X}", y, x),
+                };
+                self.issue(address, Severity::Warning, format!("{} depends on the shift quirk, {}", disassembler::disassemble(instruction), what_it_does));
+            },
+            "BNNN" if x != 0 => {
+                let what_it_does = match self.quirks.jump {
+                    true => format!("the quirks given add v{:X}", x),
+                    false => String::from("the quirks given add v0"),
+                };
+                self.issue(address, Severity::Warning, format!("{} depends on the jump quirk, {}", disassembler::disassemble(instruction), what_it_does));
+            },
+            _ => {}
+        }
+
+        true
+    }
+
+    /// this fn follows what is known about I through the code to find writes into the rom's own code and
+    /// instructions that use I after FX55 or FX65 moved it
+    fn check_index_register(&mut self, code : &[bool]) {
+        let mut values : HashMap<usize, IndexValue> = HashMap::new();
+        let mut paths = vec![(Chip8::PROGRAM_START as usize, IndexValue::Unknown)];
+
+        while let Some((address, value)) = paths.pop() {
+            if !code.get(address).copied().unwrap_or(false) {
+                continue
+            }
+
+            let value = match values.get(&address) {
+                Some(old_value) if old_value.merge(value) == *old_value => continue,
+                Some(old_value) => old_value.merge(value),
+                None => value
+            };
+            values.insert(address, value);
+
+            let instruction = self.instruction_at(address);
+            let target = (instruction & 0x0FFF) as usize;
+            let next = address + 2;
+
+            let value_after = match disassembler::opcode_class(instruction) {
+                "ANNN" => IndexValue::Known(target),
+                "FX1E" | "FX29" => IndexValue::Unknown,
+                "FX55" | "FX65" => IndexValue::Moved,
+                // superchip's FX30 and xo-chip's F000 NNNN set I too
+                "????" if extension_instruction(instruction).is_some() => IndexValue::Unknown,
+                _ => value
+            };
+
+            match disassembler::opcode_class(instruction) {
+                "00EE" | "BNNN" => {},
+                "1NNN" => paths.push((target, value_after)),
+                // the subroutine can change I so nothing is known about it after the call
+                "2NNN" => paths.extend([(target, value_after), (next, IndexValue::Unknown)]),
+                "3XNN" | "4XNN" | "5XY0" | "9XY0" | "EX9E" | "EXA1" => paths.extend([(next, value_after), (next + 2, value_after)]),
+                _ => paths.push((next, value_after))
+            }
+        }
+
+        let mut addresses = values.keys().copied().collect::<Vec<usize>>();
+        addresses.sort();
+
+        for address in addresses {
+            let instruction = self.instruction_at(address);
+            let class = disassembler::opcode_class(instruction);
+            let x = ((instruction >> 8) & 0xF) as usize;
+
+            let uses_index = matches!(class, "DXYN" | "FX1E" | "FX33" | "FX55" | "FX65");
+            if uses_index && values[&address] == IndexValue::Moved {
+                self.evidence.uses_moved_index = true;
+
+                let what_it_does = match self.quirks.load_store {
+                    true => "the quirks given leave I where it was",
+                    false => "the quirks given move I past the last register",
+                };
+                self.issue(address, Severity::Warning, format!(
+                    "{} uses I right after FX55 or FX65, that depends on the load_store quirk, {}",
+                    disassembler::disassemble(instruction), what_it_does
+                ));
+            }
+
+            let written = match class {
+                "FX33" => 3,
+                "FX55" => x + 1,
+                _ => continue
+            };
+
+            if let IndexValue::Known(index) = values[&address] {
+                if index + written > self.memory.len() {
+                    self.issue(address, Severity::Error, format!("{} writes past the end of memory from {:03X}", disassembler::disassemble(instruction), index));
+                } else if let Some(code_address) = (index..index + written).find(|byte| code[*byte] || (*byte > 0 && code[*byte - 1])) {
+                    self.issue(address, Severity::Warning, format!("{} writes into the code at {:03X}", disassembler::disassemble(instruction), code_address));
+                }
+            }
+        }
+    }
+}
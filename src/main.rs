@@ -1,20 +1,155 @@
-use rip8::{ run_emulator };
+use rip8::{ lint, rom, run_debugger, run_emulator, Cartridge, EmulatorConfig, Quirks, TerminalConfig, TerminalStyle, Waveform };
+
+// the clockspeed for rip8 run when neither --hertz or a cartridge gives one
+const DEFAULT_HERTZ : u32 = 700;
 
 fn main() {
 
     let args : Vec<String> = std::env::args().collect();
 
+    // --watch can come before the gamepath too, like rip8 run --watch game.8o
+    let watch = args.iter().skip(1).any(|arg| arg == "--watch");
+    let args = args.into_iter().filter(|arg| arg != "--watch").collect::<Vec<String>>();
+
     if args.len() < 3 {
-        panic!("error : incorrect arguments required clockspeed (hz) and gamepath");
+        panic!("error : incorrect arguments required 'run gamepath', 'debug gamepath' or clockspeed (hz) and gamepath");
     }
 
-    let parse_result = args[1].parse::<u32>();
-    
-    let hertz = match parse_result {
-        Ok(parsed_number) => parsed_number,
-        Err(_error) => panic!("error invalid argument given for clockspeed")
+    // rip8 run game.ch8 picks the clockspeed itself, rip8 700 game.ch8 is the older way that gives it first
+    // rip8 debug game.ch8 opens the game paused in the terminal debugger
+    // rip8 lint game.ch8 checks the game for problems without running it
+    let debug = args[1] == "debug";
+    let lint = args[1] == "lint";
+    let mut hertz = match args[1].as_str() {
+        "run" | "debug" | "lint" => None,
+        clockspeed => match clockspeed.parse::<u32>() {
+            Ok(parsed_number) => Some(parsed_number),
+            Err(_error) => panic!("error invalid argument given for clockspeed")
+        }
     };
 
-    run_emulator(hertz, &args[2]);
+    let mut config = EmulatorConfig::new(DEFAULT_HERTZ, &args[2]);
+    let mut quirks = None;
+    config.watch = watch;
+
+    // optional flags come after the clockspeed and gamepath
+    let mut flags = args[3..].iter();
+    while let Some(flag) = flags.next() {
+        match flag.as_str() {
+            "--hertz" => match flag_value(flag, flags.next()).parse::<u32>() {
+                Ok(parsed_number) => hertz = Some(parsed_number),
+                Err(_error) => panic!("error invalid argument given for --hertz")
+            },
+            "--record" => config.record_movie_path = Some(flag_value(flag, flags.next())),
+            "--play" => config.play_movie_path = Some(flag_value(flag, flags.next())),
+            "--seed" => match flag_value(flag, flags.next()).parse::<u64>() {
+                Ok(seed) => config.seed = Some(seed),
+                Err(_error) => panic!("error invalid argument given for --seed")
+            },
+            "--headless" => config.headless = true,
+            "--frames" => match flag_value(flag, flags.next()).parse::<usize>() {
+                Ok(frames) => config.headless_frames = Some(frames),
+                Err(_error) => panic!("error invalid argument given for --frames")
+            },
+            "--tone" => match flag_value(flag, flags.next()).parse::<f32>() {
+                Ok(frequency) if frequency > 0.0 => config.audio.frequency = frequency,
+                _ => panic!("error invalid argument given for --tone")
+            },
+            "--wave" => match Waveform::from_name(&flag_value(flag, flags.next())) {
+                Ok(waveform) => config.audio.waveform = waveform,
+                Err(error) => panic!("{}", error)
+            },
+            "--wav" => config.wav_path = Some(flag_value(flag, flags.next())),
+            "--sample-rate" => match flag_value(flag, flags.next()).parse::<u32>() {
+                Ok(sample_rate) if sample_rate >= 60 => config.wav_sample_rate = sample_rate,
+                _ => panic!("error invalid argument given for --sample-rate")
+            },
+            "--screenshot" => match flag_value(flag, flags.next()).parse::<usize>() {
+                Ok(frame) => config.screenshot_frames.push(frame),
+                Err(_error) => panic!("error invalid argument given for --screenshot")
+            },
+            "--native" => config.native_screenshots = true,
+            "--cheats" => config.cheats = true,
+            "--script" => config.script_path = Some(flag_value(flag, flags.next())),
+            "--quirks" => match Quirks::from_name(&flag_value(flag, flags.next())) {
+                Some(parsed_quirks) => quirks = Some(parsed_quirks),
+                None => panic!("error invalid argument given for --quirks, it should be rip8, chip8, schip or octo")
+            },
+            "--keep-state" => config.keep_state = true,
+            "--profile" => config.profile_path = Some(flag_value(flag, flags.next())),
+            "--coverage" => config.coverage_path = Some(flag_value(flag, flags.next())),
+            "--lcov" => config.lcov_path = Some(flag_value(flag, flags.next())),
+            "--annotate" => config.annotate_path = Some(flag_value(flag, flags.next())),
+            "--control" => match flag_value(flag, flags.next()).parse::<u16>() {
+                Ok(parsed_number) => config.control_port = Some(parsed_number),
+                Err(_error) => panic!("error invalid argument given for --control")
+            },
+            "--gdb" => match flag_value(flag, flags.next()).parse::<u16>() {
+                Ok(parsed_number) => config.gdb_port = Some(parsed_number),
+                Err(_error) => panic!("error invalid argument given for --gdb")
+            },
+            "--tui" => config.terminal = Some(config.terminal.unwrap_or_default()),
+            "--braille" => config.terminal = Some(TerminalConfig {
+                style : TerminalStyle::Braille,
+                ..config.terminal.unwrap_or_default()
+            }),
+            "--bell" => config.terminal = Some(TerminalConfig {
+                bell : true,
+                ..config.terminal.unwrap_or_default()
+            }),
+            "--gif" => config.gif_path = Some(flag_value(flag, flags.next())),
+            "--y4m" => config.y4m_path = Some(flag_value(flag, flags.next())),
+            "--volume" => match flag_value(flag, flags.next()).parse::<f32>() {
+                Ok(volume) if (0.0..=1.0).contains(&volume) => config.audio.volume = volume,
+                _ => panic!("error invalid argument given for --volume")
+            },
+            _ => panic!("error : unknown argument '{}'", flag)
+        }
+    }
+
+    // octo cartridges come with their own settings, a clockspeed that was given still wins over the cartridge's
+    if config.rom_path.to_lowercase().ends_with(".gif") {
+        match Cartridge::load(&config.rom_path) {
+            Ok(cartridge) => config.apply_cartridge(&cartridge.options),
+            Err(error) => {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Some(hertz) = hertz {
+        config.hertz = hertz;
+    }
+
+    if let Some(quirks) = quirks {
+        config.quirks = quirks;
+    }
+
+    if lint {
+        match rom::read_rom(&config.rom_path) {
+            Ok(rom) => {
+                let report = lint::lint(&rom, config.quirks);
+                print!("{}", report.text());
+                std::process::exit(if report.has_errors() { 1 } else { 0 });
+            },
+            Err(error) => {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let result = if debug { run_debugger(&config) } else { run_emulator(&config) };
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
 }
 
+fn flag_value(flag : &str, value : Option<&String>) -> String {
+    match value {
+        Some(value) => value.clone(),
+        None => panic!("error : {} needs a value", flag)
+    }
+}
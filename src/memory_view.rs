@@ -0,0 +1,104 @@
+use crate::Chip8;
+
+/// these are what a byte of memory can be used for right now, the debugger colors bytes with them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryRegion {
+    // the two bytes of the instruction the program counter is on
+    ProgramCounter,
+
+    // the bytes the index register points at
+    Index,
+
+    // the font sprites
+    Font,
+
+    Other,
+}
+
+/// this is one row of a hex dump of memory
+#[derive(Debug, Clone)]
+pub struct MemoryRow {
+    pub address : usize,
+    pub bytes : Vec<(u8, MemoryRegion)>,
+}
+
+impl Chip8 {
+    // how many bytes from the index register count as its region when the next instruction doesn't say
+    pub const DEFAULT_INDEX_LENGTH : usize = 16;
+
+    /// this fn gives back what a byte of memory is being used for
+    ///
+    /// index_length is how many bytes from the index register count as the index region,
+    /// the program counter wins over the index register which wins over the font
+    pub fn memory_region(&self, address : usize, index_length : usize) -> MemoryRegion {
+        let pc = self.pc_reg as usize;
+        let index = self.index_reg as usize;
+        let font_start = self.font.font_location_in_memory;
+
+        if (pc..pc + 2).contains(&address) {
+            MemoryRegion::ProgramCounter
+        } else if (index..index.saturating_add(index_length)).contains(&address) {
+            MemoryRegion::Index
+        } else if (font_start..font_start + self.font.font_data.len()).contains(&address) {
+            MemoryRegion::Font
+        } else {
+            MemoryRegion::Other
+        }
+    }
+
+    /// this fn gives back a hex dump of memory in rows of 16 bytes with what every byte is being used for
+    ///
+    /// the rows line up on multiples of 16 so start is rounded down and the dump stops at the end of memory
+    pub fn memory_rows(&self, start : usize, length : usize, index_length : usize) -> Vec<MemoryRow> {
+        const ROW_LENGTH : usize = 16;
+
+        let start = (start / ROW_LENGTH) * ROW_LENGTH;
+        // the length is typed into the debugger so it can be far past the end of memory
+        let end = start.saturating_add(length).min(self.memory.len());
+
+        (start..end).step_by(ROW_LENGTH)
+            .map(|address| MemoryRow {
+                address,
+                bytes : (address..(address + ROW_LENGTH).min(end))
+                    .map(|address| (self.memory[address], self.memory_region(address, index_length)))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// this fn writes bytes into memory, this is for editing memory from the debugger while the chip8 is paused
+    pub fn poke(&mut self, address : usize, bytes : &[u8]) -> Result<(), String> {
+        // the address can come from a debugger over the network so it may not even fit with the length added
+        match address.checked_add(bytes.len()).and_then(|end| self.memory.get_mut(address..end)) {
+            Some(memory) => {
+                memory.copy_from_slice(bytes);
+                Ok(())
+            },
+            None => Err(format!("error : {:#05X} to {:#05X} is outside of memory!", address, address.saturating_add(bytes.len())))
+        }
+    }
+
+    /// this fn draws rows of the sprite the index register points at, 8 pixels wide the same way DXYN reads it
+    ///
+    /// rows past the end of memory wrap around to the start of it like DXYN does
+    pub fn sprite_preview(&self, rows : usize) -> Vec<Vec<bool>> {
+        (0..rows)
+            .map(|row| {
+                let byte = self.memory[self.index_address(row)];
+                (0..8).rev().map(|bit| (byte >> bit) & 1 == 1).collect()
+            })
+            .collect()
+    }
+
+    /// this fn gives back how many rows the next instruction will draw if it is a DXYN
+    pub fn pending_sprite_height(&self) -> Option<usize> {
+        if self.pc_reg as usize + 1 >= self.memory.len() {
+            return None
+        }
+
+        match self.fetch() {
+            instruction if instruction & 0xF000 == 0xD000 => Some((instruction & 0x000F) as usize),
+            _ => None
+        }
+    }
+}
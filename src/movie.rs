@@ -0,0 +1,255 @@
+use std::{
+    fs::File,
+    io::{
+        BufRead,
+        BufReader,
+        BufWriter,
+        Write
+    },
+};
+
+use crate::{
+    Chip8,
+    Keyboard,
+    Quirks
+};
+
+/// this is a recording of every keyboard state that was fed to the chip8 once per frame
+///
+/// along with the input the movie keeps the rom hash, rng seed, clockspeed and quirks so that playing it back
+/// reproduces the exact same session. every frame also stores a hash of the chip8 state after that frame
+/// so playback can tell the exact frame where it stopped matching the recording
+///
+/// a soft reset is stored as a 'reset' line before the frame it happened on
+///
+/// a movie file looks like this :
+///
+/// ```text
+/// rip8-movie 1
+/// rom_hash 9f1c2b7a0e4d6c35
+/// seed 1234
+/// hertz 700
+/// quirks shift,load_store,clip
+/// frames
+/// - 3a5e0c91d2b7f418
+/// 5 77c2e9a0b1d43f6e
+/// reset
+/// - 3a5e0c91d2b7f418
+/// ```
+pub struct Movie {
+    pub rom_hash : u64,
+    pub seed : u64,
+    pub hertz : u32,
+
+    // movies from before quirks were recorded ran with rip8's own
+    pub quirks : Quirks,
+
+    pub frames : Vec<MovieFrame>,
+}
+
+/// this is one frame of input in a movie
+#[derive(Debug, Clone, Copy)]
+pub struct MovieFrame {
+    // the key given to the chip8 on this frame
+    pub keyboard : Keyboard,
+
+    // if the chip8 was reset right before this frame
+    pub reset : bool,
+
+    // the hash of the chip8 state after this frame
+    pub state_hash : u64,
+}
+
+impl Movie {
+    const HEADER : &'static str = "rip8-movie 1";
+
+    pub fn new(rom_hash : u64, seed : u64, hertz : u32, quirks : Quirks) -> Movie {
+        Movie {
+            rom_hash,
+            seed,
+            hertz,
+            quirks,
+            frames : Vec::new(),
+        }
+    }
+
+    /// this fn will load a movie from a movie file
+    pub fn load(file_path : &String) -> Result<Movie, String> {
+        let file_handle = match File::open(file_path) {
+            Ok(file) => file,
+            _ => return Err(format!("error : could not open movie file '{}'!", file_path))
+        };
+
+        let mut lines = BufReader::new(file_handle).lines().map_while(Result::ok);
+
+        if lines.next().as_deref() != Some(Self::HEADER) {
+            return Err(String::from("error : not a rip8 movie file!"))
+        }
+
+        let mut movie = Movie::new(0, 0, 0, Quirks::new());
+
+        for line in lines.by_ref() {
+            let mut words = line.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some("frames"), None) => break,
+                (Some("rom_hash"), Some(value)) => movie.rom_hash = Self::parse_hex(value)?,
+                (Some("seed"), Some(value)) => movie.seed = Self::parse_number(value)?,
+                (Some("hertz"), Some(value)) => movie.hertz = Self::parse_number(value)? as u32,
+                (Some("quirks"), Some(value)) => match Quirks::from_names(value) {
+                    Some(quirks) => movie.quirks = quirks,
+                    None => return Err(format!("error : bad quirks in movie '{}'!", value))
+                },
+                _ => return Err(format!("error : bad line in movie header '{}'!", line))
+            }
+        }
+
+        let mut reset = false;
+
+        for line in lines {
+            if line == "reset" {
+                reset = true;
+                continue
+            }
+
+            let mut words = line.split_whitespace();
+            let (key, state_hash) = match (words.next(), words.next()) {
+                (Some(key), Some(state_hash)) => (key, state_hash),
+                _ => return Err(format!("error : bad frame in movie '{}'!", line))
+            };
+
+            let keyboard = match key {
+                "-" => Keyboard::None,
+                key => match u8::from_str_radix(key, 16) {
+                    Ok(key_code) if key_code <= 0xf => Keyboard::from_keycode(key_code),
+                    _ => return Err(format!("error : bad key in movie frame '{}'!", line))
+                }
+            };
+
+            movie.frames.push(MovieFrame {
+                keyboard,
+                reset,
+                state_hash : Self::parse_hex(state_hash)?,
+            });
+            reset = false;
+        }
+
+        Ok(movie)
+    }
+
+    /// this fn will make sure that the movie was recorded with the same rom and config that it is being played back with
+    pub fn check_sync(&self, rom_hash : u64, hertz : u32, quirks : Quirks) -> Result<(), String> {
+        if self.rom_hash != rom_hash {
+            return Err(format!(
+                "desync : movie was recorded with rom hash {:016x} but the loaded rom hash is {:016x}",
+                self.rom_hash, rom_hash
+            ))
+        }
+
+        if self.hertz != hertz {
+            return Err(format!(
+                "desync : movie was recorded at {} hz but is being played back at {} hz",
+                self.hertz, hertz
+            ))
+        }
+
+        if self.quirks != quirks {
+            return Err(format!(
+                "desync : movie was recorded with the quirks {} but is being played back with {}",
+                self.quirks.names(), quirks.names()
+            ))
+        }
+
+        Ok(())
+    }
+
+    /// this fn will check the chip8 state after a frame of playback against the state that was recorded
+    pub fn check_frame(&self, frame : usize, chip8 : &Chip8) -> Result<(), String> {
+        match self.frames.get(frame) {
+            Some(movie_frame) if movie_frame.state_hash != state_hash(chip8) => Err(format!(
+                "desync : frame {} does not match the recording (pc : {:#05x})",
+                frame, chip8.pc_reg
+            )),
+            _ => Ok(())
+        }
+    }
+
+    fn parse_hex(value : &str) -> Result<u64, String> {
+        u64::from_str_radix(value, 16).map_err(|_| format!("error : bad hex number in movie '{}'!", value))
+    }
+
+    fn parse_number(value : &str) -> Result<u64, String> {
+        value.parse::<u64>().map_err(|_| format!("error : bad number in movie '{}'!", value))
+    }
+}
+
+/// this writes a movie to a file as it is being recorded
+///
+/// every frame gets flushed right away so that the movie survives the emulator crashing,
+/// which is usually the reason somebody was recording in the first place
+pub struct MovieRecorder {
+    writer : BufWriter<File>,
+}
+
+impl MovieRecorder {
+    pub fn new(file_path : &String, rom_hash : u64, seed : u64, hertz : u32, quirks : Quirks) -> Result<MovieRecorder, String> {
+        let file_handle = match File::create(file_path) {
+            Ok(file) => file,
+            _ => return Err(format!("error : could not create movie file '{}'!", file_path))
+        };
+
+        let mut recorder = MovieRecorder { writer : BufWriter::new(file_handle) };
+
+        recorder.write(&format!(
+            "{}\nrom_hash {:016x}\nseed {}\nhertz {}\nquirks {}\nframes\n",
+            Movie::HEADER, rom_hash, seed, hertz, quirks.names()
+        ))?;
+
+        Ok(recorder)
+    }
+
+    /// this fn records the key that was given to the chip8 for a frame and the state the chip8 ended the frame with
+    pub fn record_frame(&mut self, keyboard : Keyboard, chip8 : &Chip8) -> Result<(), String> {
+        let key = match keyboard {
+            Keyboard::None => String::from("-"),
+            key => format!("{:x}", key.get_keycode())
+        };
+
+        self.write(&format!("{} {:016x}\n", key, state_hash(chip8)))
+    }
+
+    /// this fn records that the chip8 was reset before the next frame
+    pub fn record_reset(&mut self) -> Result<(), String> {
+        self.write("reset\n")
+    }
+
+    fn write(&mut self, text : &str) -> Result<(), String> {
+        match self.writer.write_all(text.as_bytes()).and_then(|_| self.writer.flush()) {
+            Ok(_) => Ok(()),
+            _ => Err(String::from("error : could not write to movie file!"))
+        }
+    }
+}
+
+/// this fn hashes a rom so that a movie can tell if it is being played back with the same rom
+///
+/// this is 64 bit FNV-1a
+pub fn rom_hash(bytes : &[u8]) -> u64 {
+    const FNV_OFFSET_BASIS : u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME : u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ *byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+/// this fn hashes everything about the chip8 that the rom can see
+pub fn state_hash(chip8 : &Chip8) -> u64 {
+    let mut bytes = chip8.memory.clone();
+    bytes.extend(chip8.v_regs.iter());
+    bytes.extend(chip8.stack.iter().flat_map(|address| address.to_be_bytes()));
+    bytes.extend(chip8.pc_reg.to_be_bytes());
+    bytes.extend(chip8.index_reg.to_be_bytes());
+    bytes.push(chip8.delay_timer_register);
+    bytes.push(chip8.sound_timer_register);
+    bytes.extend(chip8.display_buffer.iter().flatten().map(|pixel| *pixel as u8));
+
+    rom_hash(&bytes)
+}
@@ -0,0 +1,1160 @@
+use std::collections::{
+    HashMap,
+    VecDeque
+};
+
+/// this fn assembles octo source code into the bytes of a rom that start at location 512
+///
+/// octo is the assembly language most chip8 games are written in now (https://github.com/JohnEarnest/Octo),
+/// octo cartridges carry their program as octo source so it has to be assembled before it can run.
+/// this covers the whole language including macros, :calc and :stringmode, along with the schip and xo-chip
+/// instructions even though rip8 can't run those yet
+pub fn assemble(source : &str) -> Result<Vec<u8>, String> {
+    // octo starts every program with a jump to main, unless main is the first thing in the program
+    let rom = Assembler::new(source, true).assemble()?;
+
+    if rom.main == Assembler::PROGRAM_START + 2 {
+        return Ok(Assembler::new(source, false).assemble()?.bytes)
+    }
+
+    Ok(rom.bytes)
+}
+
+/// this is one word of octo source and the line it came from so errors can point at it
+#[derive(Debug, Clone)]
+struct Token {
+    text : String,
+    line : usize,
+}
+
+/// this is a number or a label that hasn't been defined yet
+enum Value {
+    Known(f64),
+    Forward(String),
+}
+
+/// these are the ways a label that is used before it is defined gets written in once it is known
+#[derive(Debug, Clone, Copy)]
+enum FixupKind {
+    // the low 12 bits of an instruction like 1NNN
+    Address,
+
+    // two bytes, for i := long and :pointer
+    Long,
+
+    // one byte that has to fit
+    Byte,
+
+    // the bottom or top byte of an address, for :unpack
+    LowByte,
+    HighByte,
+
+    // the top 4 bits of a 12 bit address go in the bottom of a byte, for :unpack
+    HighNybble,
+}
+
+struct Fixup {
+    address : usize,
+    name : String,
+    kind : FixupKind,
+    line : usize,
+}
+
+/// these are the ways to compare a register in an if or while
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    Greater,
+    LessEqual,
+    GreaterEqual,
+    Key,
+    NotKey,
+}
+
+impl Comparison {
+    fn from_name(name : &str) -> Option<Comparison> {
+        match name {
+            "==" => Some(Comparison::Equal),
+            "!=" => Some(Comparison::NotEqual),
+            "<" => Some(Comparison::Less),
+            ">" => Some(Comparison::Greater),
+            "<=" => Some(Comparison::LessEqual),
+            ">=" => Some(Comparison::GreaterEqual),
+            "key" => Some(Comparison::Key),
+            "-key" => Some(Comparison::NotKey),
+            _ => None
+        }
+    }
+
+    fn negate(self) -> Comparison {
+        match self {
+            Comparison::Equal => Comparison::NotEqual,
+            Comparison::NotEqual => Comparison::Equal,
+            Comparison::Less => Comparison::GreaterEqual,
+            Comparison::Greater => Comparison::LessEqual,
+            Comparison::LessEqual => Comparison::Greater,
+            Comparison::GreaterEqual => Comparison::Less,
+            Comparison::Key => Comparison::NotKey,
+            Comparison::NotKey => Comparison::Key,
+        }
+    }
+}
+
+/// this is the right side of a comparison
+enum Operand {
+    Register(u8),
+    Number(u8),
+}
+
+struct Condition {
+    register : u8,
+    comparison : Comparison,
+    operand : Option<Operand>,
+}
+
+struct Macro {
+    arguments : Vec<String>,
+    body : Vec<Token>,
+    calls : usize,
+}
+
+/// this is one alphabet of a :stringmode, every character in it expands to the body
+struct StringMode {
+    alphabet : Vec<char>,
+    body : Vec<Token>,
+}
+
+/// this is what assembling gives back
+struct AssembledRom {
+    bytes : Vec<u8>,
+
+    // where main ended up
+    main : usize,
+}
+
+struct Assembler {
+    tokens : VecDeque<Token>,
+
+    // the whole address space xo-chip can reach, octo lets :org go anywhere in it
+    memory : Vec<u8>,
+
+    // the address the next byte goes to
+    here : usize,
+
+    // one past the highest address anything was written to
+    end : usize,
+
+    labels : HashMap<String, usize>,
+    constants : HashMap<String, f64>,
+    aliases : HashMap<String, u8>,
+    macros : HashMap<String, Macro>,
+    string_modes : HashMap<String, Vec<StringMode>>,
+    fixups : Vec<Fixup>,
+
+    // the jumps from if ... begin that are waiting for their else or end
+    branches : Vec<(usize, usize)>,
+
+    // the start of every loop that is open and the jumps from its whiles that are waiting for its again
+    loops : Vec<(usize, Vec<usize>, usize)>,
+
+    // the line of the token that was last taken, for errors
+    line : usize,
+}
+
+impl Assembler {
+    const PROGRAM_START : usize = 0x200;
+    const MEMORY_SIZE : usize = 0x10000;
+
+    fn new(source : &str, jump_to_main : bool) -> Assembler {
+        let mut assembler = Assembler {
+            tokens : Self::tokenize(source),
+            memory : vec![0; Self::MEMORY_SIZE],
+            here : Self::PROGRAM_START,
+            end : Self::PROGRAM_START,
+            labels : HashMap::new(),
+            constants : HashMap::new(),
+            aliases : HashMap::new(),
+            macros : HashMap::new(),
+            string_modes : HashMap::new(),
+            fixups : Vec::new(),
+            branches : Vec::new(),
+            loops : Vec::new(),
+            line : 1,
+        };
+
+        if jump_to_main {
+            assembler.fixups.push(Fixup { address : Self::PROGRAM_START, name : String::from("main"), kind : FixupKind::Address, line : 1 });
+            assembler.emit_instruction(0x1000);
+        }
+
+        assembler
+    }
+
+    /// this fn splits the source into words, comments start with # and strings are kept whole with their quotes
+    fn tokenize(source : &str) -> VecDeque<Token> {
+        let mut tokens : VecDeque<Token> = VecDeque::new();
+        let mut characters = source.chars().peekable();
+        let mut line = 1;
+
+        while let Some(character) = characters.next() {
+            match character {
+                '\n' => line += 1,
+                '#' => {
+                    while characters.peek().is_some_and(|character| *character != '\n') {
+                        characters.next();
+                    }
+                },
+                '"' => {
+                    let mut text = String::from("\"");
+                    let start_line = line;
+
+                    while let Some(character) = characters.next() {
+                        match character {
+                            '"' => break,
+                            '\\' => match characters.next() {
+                                Some('n') => text.push('\n'),
+                                Some('t') => text.push('\t'),
+                                Some('0') => text.push('\0'),
+                                Some(escaped) => text.push(escaped),
+                                None => break
+                            },
+                            '\n' => {
+                                line += 1;
+                                text.push('\n');
+                            },
+                            _ => text.push(character)
+                        }
+                    }
+
+                    text.push('"');
+                    tokens.push_back(Token { text, line : start_line });
+                },
+                character if character.is_whitespace() => {},
+                _ => {
+                    let mut text = String::from(character);
+
+                    while characters.peek().is_some_and(|character| !character.is_whitespace() && *character != '#') {
+                        text.push(characters.next().unwrap());
+                    }
+
+                    tokens.push_back(Token { text, line });
+                }
+            }
+        }
+
+        tokens
+    }
+
+    fn assemble(mut self) -> Result<AssembledRom, String> {
+        while let Some(token) = self.next_token() {
+            self.statement(token)?;
+        }
+
+        if let Some((_, line)) = self.branches.last() {
+            return Err(format!("error : octo line {} : this if ... begin never has an end!", line))
+        }
+
+        if let Some((_, _, line)) = self.loops.last() {
+            return Err(format!("error : octo line {} : this loop never has an again!", line))
+        }
+
+        for fixup in std::mem::take(&mut self.fixups) {
+            let value = match self.labels.get(&fixup.name) {
+                Some(address) => *address as i64,
+                None if fixup.name == "main" => return Err(String::from("error : the octo program has no main label!")),
+                None => return Err(format!("error : octo line {} : '{}' is never defined!", fixup.line, fixup.name))
+            };
+
+            self.line = fixup.line;
+            self.apply(fixup.kind, fixup.address, value)?;
+        }
+
+        let main = self.labels.get("main").copied().unwrap_or(0);
+
+        Ok(AssembledRom {
+            bytes : self.memory[Self::PROGRAM_START..self.end].to_vec(),
+            main,
+        })
+    }
+
+    fn statement(&mut self, token : Token) -> Result<(), String> {
+        // a register at the start of a statement is always some kind of assignment
+        if let Some(register) = self.register(&token.text) {
+            return self.register_statement(register)
+        }
+
+        match token.text.as_str() {
+            ":" => {
+                let name = self.name()?;
+                self.define_label(name, self.here)?;
+            },
+            ":const" => {
+                let name = self.name()?;
+                let value = self.known_value()?;
+                self.define_constant(name, value)?;
+            },
+            ":alias" => {
+                let name = self.name()?;
+                let register_token = self.expect_token()?;
+                let register = match self.register(&register_token.text) {
+                    Some(register) => register,
+                    None => return Err(self.error(&format!("'{}' is not a register", register_token.text)))
+                };
+                self.aliases.insert(name, register);
+            },
+            ":calc" => {
+                let name = self.name()?;
+                self.expect(&["{"])?;
+                let value = self.calc()?;
+                self.define_constant(name, value)?;
+            },
+            ":unpack" => {
+                // v0 := the top of the address and v1 := the bottom, the top can have a nybble in front of it
+                let high_nybble = match self.peek_text() {
+                    Some("long") => {
+                        self.next_token();
+                        None
+                    },
+                    _ => Some(self.nybble_value()?)
+                };
+
+                let value = self.value()?;
+                match high_nybble {
+                    Some(high_nybble) => {
+                        self.emit_instruction(0x6000 | (high_nybble as u16) << 4);
+                        self.emit_fixup(value.clone_value(), self.here - 1, FixupKind::HighNybble)?;
+                    },
+                    None => {
+                        self.emit_instruction(0x6000);
+                        self.emit_fixup(value.clone_value(), self.here - 1, FixupKind::HighByte)?;
+                    }
+                }
+
+                self.emit_instruction(0x6100);
+                self.emit_fixup(value, self.here - 1, FixupKind::LowByte)?;
+            },
+            ":next" => {
+                // the label points at the second byte of the next instruction, for code that changes itself
+                let name = self.name()?;
+                self.define_label(name, self.here + 1)?;
+            },
+            ":org" => {
+                let address = self.known_value()?;
+                if !(0.0..Self::MEMORY_SIZE as f64).contains(&address) {
+                    return Err(self.error(&format!(":org {} is outside of memory", address)))
+                }
+                self.here = address as usize;
+            },
+            ":byte" => {
+                let value = self.value()?;
+                self.emit_byte(0);
+                self.emit_fixup(value, self.here - 1, FixupKind::Byte)?;
+            },
+            ":pointer" => {
+                let value = self.value()?;
+                self.emit_instruction(0);
+                self.emit_fixup(value, self.here - 2, FixupKind::Long)?;
+            },
+            ":call" => {
+                let value = self.value()?;
+                self.emit_address(0x2000, value)?;
+            },
+            ":macro" => self.define_macro()?,
+            ":stringmode" => self.define_string_mode()?,
+            ":assert" => {
+                let message = match self.peek_text() {
+                    Some(text) if text.starts_with('"') => Some(self.string()?),
+                    _ => None
+                };
+
+                self.expect(&["{"])?;
+                if self.calc()? == 0.0 {
+                    let message = message.unwrap_or(String::from("assertion failed"));
+                    return Err(self.error(&message))
+                }
+            },
+            // these are for octo's debugger, rip8 has its own
+            ":breakpoint" | ":proto" => {
+                self.expect_token()?;
+            },
+            ":monitor" => {
+                self.expect_token()?;
+                self.expect_token()?;
+            },
+            "return" | ";" => self.emit_instruction(0x00EE),
+            "clear" => self.emit_instruction(0x00E0),
+            "hires" => self.emit_instruction(0x00FF),
+            "lores" => self.emit_instruction(0x00FE),
+            "exit" => self.emit_instruction(0x00FD),
+            "scroll-left" => self.emit_instruction(0x00FC),
+            "scroll-right" => self.emit_instruction(0x00FB),
+            "scroll-down" => {
+                let rows = self.nybble_value()?;
+                self.emit_instruction(0x00C0 | rows as u16);
+            },
+            "scroll-up" => {
+                let rows = self.nybble_value()?;
+                self.emit_instruction(0x00D0 | rows as u16);
+            },
+            "audio" => self.emit_instruction(0xF002),
+            "plane" => {
+                let plane = self.nybble_value()?;
+                self.emit_instruction(0xF001 | (plane as u16) << 8);
+            },
+            "bcd" => {
+                let register = self.expect_register()?;
+                self.emit_instruction(0xF033 | (register as u16) << 8);
+            },
+            "save" | "load" => {
+                let first = self.expect_register()?;
+
+                if self.peek_text() == Some("-") {
+                    self.next_token();
+                    let last = self.expect_register()?;
+                    let low = if token.text == "save" { 0x2 } else { 0x3 };
+                    self.emit_instruction(0x5000 | (first as u16) << 8 | (last as u16) << 4 | low);
+                } else {
+                    let low = if token.text == "save" { 0x55 } else { 0x65 };
+                    self.emit_instruction(0xF000 | (first as u16) << 8 | low);
+                }
+            },
+            "saveflags" | "loadflags" => {
+                let register = self.expect_register()?;
+                let low = if token.text == "saveflags" { 0x75 } else { 0x85 };
+                self.emit_instruction(0xF000 | (register as u16) << 8 | low);
+            },
+            "sprite" => {
+                let x_register = self.expect_register()?;
+                let y_register = self.expect_register()?;
+                let height = self.nybble_value()?;
+                self.emit_instruction(0xD000 | (x_register as u16) << 8 | (y_register as u16) << 4 | height as u16);
+            },
+            "jump" => {
+                let value = self.value()?;
+                self.emit_address(0x1000, value)?;
+            },
+            "jump0" => {
+                let value = self.value()?;
+                self.emit_address(0xB000, value)?;
+            },
+            "native" => {
+                let value = self.value()?;
+                self.emit_address(0x0000, value)?;
+            },
+            "delay" | "buzzer" | "pitch" => {
+                self.expect(&[":="])?;
+                let register = self.expect_register()?;
+                let low = match token.text.as_str() {
+                    "delay" => 0x15,
+                    "buzzer" => 0x18,
+                    _ => 0x3A
+                };
+                self.emit_instruction(0xF000 | (register as u16) << 8 | low);
+            },
+            "i" => self.index_statement()?,
+            "if" => self.if_statement()?,
+            "else" => {
+                let (branch, line) = match self.branches.pop() {
+                    Some(branch) => branch,
+                    None => return Err(self.error("else without an if ... begin"))
+                };
+
+                let jump = self.here;
+                self.emit_instruction(0x1000);
+                self.patch_address(branch, self.here);
+                self.branches.push((jump, line));
+            },
+            "end" => {
+                let (branch, _) = match self.branches.pop() {
+                    Some(branch) => branch,
+                    None => return Err(self.error("end without an if ... begin"))
+                };
+                self.patch_address(branch, self.here);
+            },
+            "loop" => self.loops.push((self.here, Vec::new(), self.line)),
+            "while" => {
+                let condition = self.condition()?;
+                self.emit_skip_if(&condition);
+
+                let jump = self.here;
+                self.emit_instruction(0x1000);
+
+                match self.loops.last_mut() {
+                    Some((_, whiles, _)) => whiles.push(jump),
+                    None => return Err(self.error("while outside of a loop"))
+                }
+            },
+            "again" => {
+                let (start, whiles, _) = match self.loops.pop() {
+                    Some(open_loop) => open_loop,
+                    None => return Err(self.error("again without a loop"))
+                };
+
+                self.emit_instruction(0x1000 | start as u16);
+                for jump in whiles {
+                    self.patch_address(jump, self.here);
+                }
+            },
+            text if text.starts_with('{') => {
+                let value = self.calc()?;
+                self.emit_byte(value as i64 as u8);
+            },
+            text if self.macros.contains_key(text) => self.expand_macro(text)?,
+            text if self.string_modes.contains_key(text) => self.expand_string_mode(text)?,
+            text if Self::parse_number(text).is_some() || self.constants.contains_key(text) => {
+                let value = self.value_of(&token)?;
+                self.emit_byte(self.to_byte(value)?);
+            },
+            // anything else is the name of a subroutine to call
+            _ => {
+                let value = self.value_of(&token)?;
+                self.emit_address(0x2000, value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// this fn assembles everything that starts with a register, EX : v0 += 1
+    fn register_statement(&mut self, register : u8) -> Result<(), String> {
+        let operator = self.expect_token()?;
+        let x = (register as u16) << 8;
+
+        // the right side can be a register for every operator
+        let source = self.peek_text().and_then(|text| self.register(text));
+        if let Some(source) = source {
+            self.next_token();
+            let y = (source as u16) << 4;
+
+            let low = match operator.text.as_str() {
+                ":=" => 0x0,
+                "|=" => 0x1,
+                "&=" => 0x2,
+                "^=" => 0x3,
+                "+=" => 0x4,
+                "-=" => 0x5,
+                ">>=" => 0x6,
+                "=-" => 0x7,
+                "<<=" => 0xE,
+                _ => return Err(self.error(&format!("unknown operator '{}'", operator.text)))
+            };
+
+            self.emit_instruction(0x8000 | x | y | low);
+            return Ok(())
+        }
+
+        match operator.text.as_str() {
+            ":=" => match self.peek_text() {
+                Some("key") => {
+                    self.next_token();
+                    self.emit_instruction(0xF00A | x);
+                },
+                Some("delay") => {
+                    self.next_token();
+                    self.emit_instruction(0xF007 | x);
+                },
+                Some("random") => {
+                    self.next_token();
+                    let mask = self.byte_value()?;
+                    self.emit_instruction(0xC000 | x | mask as u16);
+                },
+                _ => {
+                    let value = self.value()?;
+                    self.emit_instruction(0x6000 | x);
+                    self.emit_fixup(value, self.here - 1, FixupKind::Byte)?;
+                }
+            },
+            "+=" => {
+                let value = self.byte_value()?;
+                self.emit_instruction(0x7000 | x | value as u16);
+            },
+            "-=" => {
+                let value = self.byte_value()?;
+                self.emit_instruction(0x7000 | x | value.wrapping_neg() as u16);
+            },
+            _ => return Err(self.error(&format!("'{}' needs a register on the right side", operator.text)))
+        }
+
+        Ok(())
+    }
+
+    /// this fn assembles everything that sets the index register
+    fn index_statement(&mut self) -> Result<(), String> {
+        let operator = self.expect_token()?;
+
+        match operator.text.as_str() {
+            "+=" => {
+                let register = self.expect_register()?;
+                self.emit_instruction(0xF01E | (register as u16) << 8);
+            },
+            ":=" => match self.peek_text() {
+                Some("hex") => {
+                    self.next_token();
+                    let register = self.expect_register()?;
+                    self.emit_instruction(0xF029 | (register as u16) << 8);
+                },
+                Some("bighex") => {
+                    self.next_token();
+                    let register = self.expect_register()?;
+                    self.emit_instruction(0xF030 | (register as u16) << 8);
+                },
+                Some("long") => {
+                    self.next_token();
+                    let value = self.value()?;
+                    self.emit_instruction(0xF000);
+                    self.emit_instruction(0);
+                    self.emit_fixup(value, self.here - 2, FixupKind::Long)?;
+                },
+                _ => {
+                    let value = self.value()?;
+                    self.emit_address(0xA000, value)?;
+                }
+            },
+            _ => return Err(self.error(&format!("unknown operator for i '{}'", operator.text)))
+        }
+
+        Ok(())
+    }
+
+    /// this fn assembles if ... then and if ... begin
+    fn if_statement(&mut self) -> Result<(), String> {
+        let condition = self.condition()?;
+        let keyword = self.expect(&["then", "begin"])?;
+
+        if keyword == "then" {
+            // the next statement gets skipped when the condition is false
+            self.emit_skip_if(&Condition { comparison : condition.comparison.negate(), ..condition });
+        } else {
+            // the jump to the else or end gets skipped when the condition is true
+            self.emit_skip_if(&condition);
+            self.branches.push((self.here, self.line));
+            self.emit_instruction(0x1000);
+        }
+
+        Ok(())
+    }
+
+    fn condition(&mut self) -> Result<Condition, String> {
+        let register = self.expect_register()?;
+        let comparison_token = self.expect_token()?;
+
+        let comparison = match Comparison::from_name(&comparison_token.text) {
+            Some(comparison) => comparison,
+            None => return Err(self.error(&format!("unknown comparison '{}'", comparison_token.text)))
+        };
+
+        let operand = match comparison {
+            Comparison::Key | Comparison::NotKey => None,
+            _ => {
+                let source = self.peek_text().and_then(|text| self.register(text));
+                match source {
+                    Some(source) => {
+                        self.next_token();
+                        Some(Operand::Register(source))
+                    },
+                    None => Some(Operand::Number(self.byte_value()?))
+                }
+            }
+        };
+
+        Ok(Condition { register, comparison, operand })
+    }
+
+    /// this fn emits the instructions that skip the next instruction when the condition is true
+    ///
+    /// the chip8 can only skip on equal, not equal and keys so the other comparisons subtract into vf first
+    fn emit_skip_if(&mut self, condition : &Condition) {
+        let x = (condition.register as u16) << 8;
+
+        match (condition.comparison, &condition.operand) {
+            (Comparison::Equal, Some(Operand::Number(number))) => self.emit_instruction(0x3000 | x | *number as u16),
+            (Comparison::NotEqual, Some(Operand::Number(number))) => self.emit_instruction(0x4000 | x | *number as u16),
+            (Comparison::Equal, Some(Operand::Register(source))) => self.emit_instruction(0x5000 | x | (*source as u16) << 4),
+            (Comparison::NotEqual, Some(Operand::Register(source))) => self.emit_instruction(0x9000 | x | (*source as u16) << 4),
+            (Comparison::Key, _) => self.emit_instruction(0xE09E | x),
+            (Comparison::NotKey, _) => self.emit_instruction(0xE0A1 | x),
+            (comparison, operand) => {
+                // vf := the right side
+                match operand {
+                    Some(Operand::Register(source)) => self.emit_instruction(0x8F00 | (*source as u16) << 4),
+                    Some(Operand::Number(number)) => self.emit_instruction(0x6F00 | *number as u16),
+                    None => {}
+                }
+
+                // vf =- vx leaves vf as 1 when vx >= the right side and vf -= vx leaves it as 1 when the right side >= vx
+                let (subtract, true_flag) = match comparison {
+                    Comparison::GreaterEqual => (0x7, 1),
+                    Comparison::Less => (0x7, 0),
+                    Comparison::LessEqual => (0x5, 1),
+                    _ => (0x5, 0),
+                };
+
+                self.emit_instruction(0x8F00 | (condition.register as u16) << 4 | subtract);
+                self.emit_instruction(0x3F00 | true_flag);
+            }
+        }
+    }
+
+    fn define_macro(&mut self) -> Result<(), String> {
+        let name = self.name()?;
+        let mut arguments : Vec<String> = Vec::new();
+
+        loop {
+            let token = self.expect_token()?;
+            if token.text == "{" {
+                break
+            }
+            arguments.push(token.text);
+        }
+
+        let body = self.block()?;
+        self.macros.insert(name, Macro { arguments, body, calls : 0 });
+
+        Ok(())
+    }
+
+    fn expand_macro(&mut self, name : &str) -> Result<(), String> {
+        let argument_count = self.macros[name].arguments.len();
+
+        let mut values : Vec<Token> = Vec::new();
+        for _ in 0..argument_count {
+            values.push(self.expect_token()?);
+        }
+
+        let line = self.line;
+        let expansion = {
+            let definition = self.macros.get_mut(name).unwrap();
+            let calls = definition.calls;
+            definition.calls += 1;
+
+            definition.body.iter()
+                .map(|token| {
+                    let text = match definition.arguments.iter().position(|argument| *argument == token.text) {
+                        Some(index) => values[index].text.clone(),
+                        None if token.text == "CALLS" => calls.to_string(),
+                        None => token.text.clone()
+                    };
+                    Token { text, line }
+                })
+                .collect::<Vec<Token>>()
+        };
+
+        for token in expansion.into_iter().rev() {
+            self.tokens.push_front(token);
+        }
+
+        Ok(())
+    }
+
+    fn define_string_mode(&mut self) -> Result<(), String> {
+        let name = self.name()?;
+        let alphabet = self.string()?.chars().collect::<Vec<char>>();
+        self.expect(&["{"])?;
+        let body = self.block()?;
+
+        self.string_modes.entry(name).or_default().push(StringMode { alphabet, body });
+
+        Ok(())
+    }
+
+    /// this fn expands the body of a string mode once for every character in the string after it
+    fn expand_string_mode(&mut self, name : &str) -> Result<(), String> {
+        let text = self.string()?;
+        let line = self.line;
+        let mut expansion : Vec<Token> = Vec::new();
+
+        for (index, character) in text.chars().enumerate() {
+            let found = self.string_modes[name].iter()
+                .find_map(|mode| mode.alphabet.iter().position(|letter| *letter == character).map(|value| (mode, value)));
+
+            let (mode, value) = match found {
+                Some(found) => found,
+                None => return Err(self.error(&format!("string mode '{}' has no '{}' in it", name, character)))
+            };
+
+            for token in mode.body.iter() {
+                let text = match token.text.as_str() {
+                    "CHAR" => (character as u32).to_string(),
+                    "INDEX" => index.to_string(),
+                    "VALUE" => value.to_string(),
+                    _ => token.text.clone()
+                };
+                expansion.push(Token { text, line });
+            }
+        }
+
+        for token in expansion.into_iter().rev() {
+            self.tokens.push_front(token);
+        }
+
+        Ok(())
+    }
+
+    /// this fn takes every token up to the } that matches a { that was already taken
+    fn block(&mut self) -> Result<Vec<Token>, String> {
+        let mut body : Vec<Token> = Vec::new();
+        let mut depth = 1;
+
+        loop {
+            let token = self.expect_token()?;
+            match token.text.as_str() {
+                "{" => depth += 1,
+                "}" => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(body)
+                    }
+                },
+                _ => {}
+            }
+            body.push(token);
+        }
+    }
+
+    /// this fn works out a calc expression after its { was taken
+    ///
+    /// like octo there is no operator precedence, everything is worked out right to left unless it is in brackets
+    fn calc(&mut self) -> Result<f64, String> {
+        let tokens = self.block()?;
+        let mut position = 0;
+        let value = self.calc_expression(&tokens, &mut position)?;
+
+        if position < tokens.len() {
+            return Err(self.error(&format!("unexpected '{}' in expression", tokens[position].text)))
+        }
+
+        Ok(value)
+    }
+
+    fn calc_expression(&self, tokens : &[Token], position : &mut usize) -> Result<f64, String> {
+        let left = self.calc_term(tokens, position)?;
+
+        let operator = match tokens.get(*position) {
+            Some(token) if token.text != ")" => token.text.clone(),
+            _ => return Ok(left)
+        };
+        *position += 1;
+
+        let right = self.calc_expression(tokens, position)?;
+
+        let boolean = |value : bool| if value { 1.0 } else { 0.0 };
+
+        let value = match operator.as_str() {
+            "+" => left + right,
+            "-" => left - right,
+            "*" => left * right,
+            "/" => left / right,
+            "%" => left % right,
+            "&" => (left as i64 & right as i64) as f64,
+            "|" => (left as i64 | right as i64) as f64,
+            "^" => (left as i64 ^ right as i64) as f64,
+            "<<" => ((left as i64) << (right as i64)) as f64,
+            ">>" => ((left as i64) >> (right as i64)) as f64,
+            "pow" => left.powf(right),
+            "min" => left.min(right),
+            "max" => left.max(right),
+            "<" => boolean(left < right),
+            ">" => boolean(left > right),
+            "<=" => boolean(left <= right),
+            ">=" => boolean(left >= right),
+            "==" => boolean(left == right),
+            "!=" => boolean(left != right),
+            _ => return Err(self.error(&format!("unknown operator '{}' in expression", operator)))
+        };
+
+        Ok(value)
+    }
+
+    fn calc_term(&self, tokens : &[Token], position : &mut usize) -> Result<f64, String> {
+        let token = match tokens.get(*position) {
+            Some(token) => token,
+            None => return Err(self.error("expression ended early"))
+        };
+        *position += 1;
+
+        let unary = |function : fn(f64) -> f64, position : &mut usize| -> Result<f64, String> {
+            Ok(function(self.calc_term(tokens, position)?))
+        };
+
+        match token.text.as_str() {
+            "(" => {
+                let value = self.calc_expression(tokens, position)?;
+                match tokens.get(*position) {
+                    Some(token) if token.text == ")" => {
+                        *position += 1;
+                        Ok(value)
+                    },
+                    _ => Err(self.error("missing ) in expression"))
+                }
+            },
+            "-" => unary(|value| -value, position),
+            "~" => unary(|value| !(value as i64) as f64, position),
+            "!" => unary(|value| if value == 0.0 { 1.0 } else { 0.0 }, position),
+            "sin" => unary(f64::sin, position),
+            "cos" => unary(f64::cos, position),
+            "tan" => unary(f64::tan, position),
+            "exp" => unary(f64::exp, position),
+            "log" => unary(f64::ln, position),
+            "abs" => unary(f64::abs, position),
+            "sqrt" => unary(f64::sqrt, position),
+            "sign" => unary(f64::signum, position),
+            "ceil" => unary(f64::ceil, position),
+            "floor" => unary(f64::floor, position),
+            "@" => {
+                let address = self.calc_term(tokens, position)? as usize;
+                Ok(self.memory.get(address).copied().unwrap_or(0) as f64)
+            },
+            "strlen" => match tokens.get(*position) {
+                Some(token) if token.text.starts_with('"') => {
+                    *position += 1;
+                    Ok(token.text.trim_matches('"').chars().count() as f64)
+                },
+                _ => Err(self.error("strlen needs a string"))
+            },
+            "HERE" => Ok(self.here as f64),
+            "PI" => Ok(std::f64::consts::PI),
+            "E" => Ok(std::f64::consts::E),
+            text => match self.known_value_of(text) {
+                Some(value) => Ok(value),
+                None => Err(self.error(&format!("'{}' is not defined yet", text)))
+            }
+        }
+    }
+
+    fn define_label(&mut self, name : String, address : usize) -> Result<(), String> {
+        if self.labels.contains_key(&name) {
+            return Err(self.error(&format!("the label '{}' is defined more than once", name)))
+        }
+        self.labels.insert(name, address);
+        Ok(())
+    }
+
+    fn define_constant(&mut self, name : String, value : f64) -> Result<(), String> {
+        if self.labels.contains_key(&name) {
+            return Err(self.error(&format!("'{}' is already a label", name)))
+        }
+        self.constants.insert(name, value);
+        Ok(())
+    }
+
+    /// this fn gives back what a name or number is if it is known already
+    fn known_value_of(&self, text : &str) -> Option<f64> {
+        if let Some(number) = Self::parse_number(text) {
+            return Some(number)
+        }
+
+        match self.constants.get(text) {
+            Some(value) => Some(*value),
+            None => self.labels.get(text).map(|address| *address as f64)
+        }
+    }
+
+    fn value_of(&mut self, token : &Token) -> Result<Value, String> {
+        if token.text == "{" {
+            return Ok(Value::Known(self.calc()?))
+        }
+
+        match self.known_value_of(&token.text) {
+            Some(value) => Ok(Value::Known(value)),
+            None if self.register(&token.text).is_some() => Err(self.error(&format!("expected a number but found the register '{}'", token.text))),
+            None if Self::is_name(&token.text) => Ok(Value::Forward(token.text.clone())),
+            None => Err(self.error(&format!("expected a number but found '{}'", token.text)))
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        let token = self.expect_token()?;
+        self.value_of(&token)
+    }
+
+    fn known_value(&mut self) -> Result<f64, String> {
+        match self.value()? {
+            Value::Known(value) => Ok(value),
+            Value::Forward(name) => Err(self.error(&format!("'{}' has to be defined before it is used here", name)))
+        }
+    }
+
+    fn byte_value(&mut self) -> Result<u8, String> {
+        let value = self.known_value()?;
+        self.to_byte(Value::Known(value))
+    }
+
+    fn nybble_value(&mut self) -> Result<u8, String> {
+        let value = self.known_value()?;
+        if !(0.0..16.0).contains(&value) {
+            return Err(self.error(&format!("{} doesn't fit in a nybble", value)))
+        }
+        Ok(value as u8)
+    }
+
+    fn to_byte(&self, value : Value) -> Result<u8, String> {
+        match value {
+            Value::Known(value) if (-128.0..256.0).contains(&value) => Ok(value as i64 as u8),
+            Value::Known(value) => Err(self.error(&format!("{} doesn't fit in a byte", value))),
+            Value::Forward(name) => Err(self.error(&format!("'{}' has to be defined before it is used here", name)))
+        }
+    }
+
+    /// this fn writes a value into bytes that were already emitted, or leaves a fixup if it is a label that comes later
+    fn emit_fixup(&mut self, value : Value, address : usize, kind : FixupKind) -> Result<(), String> {
+        match value {
+            Value::Known(number) => self.apply(kind, address, number.floor() as i64),
+            Value::Forward(name) => {
+                self.fixups.push(Fixup { address, name, kind, line : self.line });
+                Ok(())
+            }
+        }
+    }
+
+    fn apply(&mut self, kind : FixupKind, address : usize, value : i64) -> Result<(), String> {
+        let in_range = match kind {
+            FixupKind::Address => (0..=0xFFF).contains(&value),
+            FixupKind::Long => (0..=0xFFFF).contains(&value),
+            FixupKind::Byte => (-128..=0xFF).contains(&value),
+            _ => (0..=0xFFFF).contains(&value),
+        };
+
+        if !in_range {
+            return Err(self.error(&format!("{:#X} doesn't fit here", value)))
+        }
+
+        match kind {
+            FixupKind::Address => {
+                self.memory[address] |= (value >> 8) as u8;
+                self.memory[address + 1] = value as u8;
+            },
+            FixupKind::Long => {
+                self.memory[address] = (value >> 8) as u8;
+                self.memory[address + 1] = value as u8;
+            },
+            FixupKind::Byte | FixupKind::LowByte => self.memory[address] = value as u8,
+            FixupKind::HighByte => self.memory[address] = (value >> 8) as u8,
+            FixupKind::HighNybble => self.memory[address] |= ((value >> 8) & 0xF) as u8,
+        }
+
+        Ok(())
+    }
+
+    fn emit_address(&mut self, instruction : u16, value : Value) -> Result<(), String> {
+        self.emit_instruction(instruction);
+        self.emit_fixup(value, self.here - 2, FixupKind::Address)
+    }
+
+    fn patch_address(&mut self, address : usize, target : usize) {
+        self.memory[address] = (self.memory[address] & 0xF0) | ((target >> 8) & 0xF) as u8;
+        self.memory[address + 1] = target as u8;
+    }
+
+    fn emit_instruction(&mut self, instruction : u16) {
+        self.emit_byte((instruction >> 8) as u8);
+        self.emit_byte(instruction as u8);
+    }
+
+    fn emit_byte(&mut self, byte : u8) {
+        if self.here < Self::MEMORY_SIZE {
+            self.memory[self.here] = byte;
+        }
+        self.here += 1;
+        self.end = self.end.max(self.here.min(Self::MEMORY_SIZE));
+    }
+
+    fn register(&self, text : &str) -> Option<u8> {
+        if let Some(register) = self.aliases.get(text) {
+            return Some(*register)
+        }
+
+        let mut characters = text.chars();
+        match (characters.next(), characters.next(), characters.next()) {
+            (Some('v' | 'V'), Some(digit), None) => digit.to_digit(16).map(|digit| digit as u8),
+            _ => None
+        }
+    }
+
+    fn expect_register(&mut self) -> Result<u8, String> {
+        let token = self.expect_token()?;
+        match self.register(&token.text) {
+            Some(register) => Ok(register),
+            None => Err(self.error(&format!("expected a register but found '{}'", token.text)))
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        let token = self.expect_token()?;
+        if !Self::is_name(&token.text) || self.register(&token.text).is_some() {
+            return Err(self.error(&format!("'{}' can't be used as a name", token.text)))
+        }
+        Ok(token.text)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let token = self.expect_token()?;
+        if !token.text.starts_with('"') {
+            return Err(self.error(&format!("expected a string but found '{}'", token.text)))
+        }
+        Ok(token.text[1..token.text.len() - 1].to_string())
+    }
+
+    fn is_name(text : &str) -> bool {
+        text.chars().next().is_some_and(|character| character.is_alphabetic() || character == '_')
+            && text.chars().all(|character| character.is_alphanumeric() || character == '_' || character == '-')
+    }
+
+    fn parse_number(text : &str) -> Option<f64> {
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, text)
+        };
+
+        let number = if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+            i64::from_str_radix(hex, 16).ok()? as f64
+        } else if let Some(binary) = digits.strip_prefix("0b").or_else(|| digits.strip_prefix("0B")) {
+            i64::from_str_radix(binary, 2).ok()? as f64
+        } else if digits.starts_with(|character : char| character.is_ascii_digit()) {
+            digits.parse::<f64>().ok()?
+        } else {
+            return None
+        };
+
+        Some(if negative { -number } else { number })
+    }
+
+    fn next_token(&mut self) -> Option<Token> {
+        let token = self.tokens.pop_front()?;
+        self.line = token.line;
+        Some(token)
+    }
+
+    fn peek_text(&self) -> Option<&str> {
+        self.tokens.front().map(|token| token.text.as_str())
+    }
+
+    fn expect_token(&mut self) -> Result<Token, String> {
+        match self.next_token() {
+            Some(token) => Ok(token),
+            None => Err(self.error("the program ended in the middle of a statement"))
+        }
+    }
+
+    fn expect(&mut self, expected : &[&str]) -> Result<String, String> {
+        let token = self.expect_token()?;
+        if !expected.contains(&token.text.as_str()) {
+            return Err(self.error(&format!("expected {} but found '{}'", expected.join(" or "), token.text)))
+        }
+        Ok(token.text)
+    }
+
+    fn error(&self, message : &str) -> String {
+        format!("error : octo line {} : {}!", self.line, message)
+    }
+}
+
+impl Value {
+    fn clone_value(&self) -> Value {
+        match self {
+            Value::Known(value) => Value::Known(*value),
+            Value::Forward(name) => Value::Forward(name.clone()),
+        }
+    }
+}
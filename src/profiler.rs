@@ -0,0 +1,233 @@
+use std::collections::{
+    HashMap,
+    HashSet
+};
+
+use crate::{
+    Chip8,
+    Keyboard,
+    disassembler
+};
+
+/// this counts where a rom spends its instructions, for finding what to speed up in homebrew for slow hardware
+///
+/// record has to be called right before every instruction. subroutines are followed through 2NNN and 00EE, so the
+/// profile knows the stack of subroutines every instruction ran in. that is written out as folded stacks which
+/// flamegraph.pl and inferno read, EX : main;sub_2A4;sub_31C 1200
+pub struct Profiler {
+    instructions : u64,
+
+    // how many times the instruction at every address ran
+    address_counts : Vec<u64>,
+
+    // how many times each kind of instruction ran, by its pattern like DXYN
+    class_counts : HashMap<&'static str, u64>,
+
+    // FX0A instructions that ran without a key, every one of these is the chip8 waiting
+    key_wait_cycles : u64,
+
+    // instructions that didn't run because the chip8 was waiting for vblank after drawing
+    vblank_wait_cycles : u64,
+
+    // the subroutines the chip8 is in right now, by the address they start at
+    call_stack : Vec<u16>,
+
+    // how many instructions ran with each stack of subroutines
+    stacks : HashMap<Vec<u16>, u64>,
+
+    // how many times each subroutine called each other one, none is the main program
+    calls : HashMap<(Option<u16>, u16), u64>,
+}
+
+impl Profiler {
+    // how many addresses the report lists
+    const TOP_ADDRESSES : usize = 20;
+
+    pub fn new() -> Profiler {
+        Profiler {
+            instructions : 0,
+            address_counts : Vec::new(),
+            class_counts : HashMap::new(),
+            key_wait_cycles : 0,
+            vblank_wait_cycles : 0,
+            call_stack : Vec::new(),
+            stacks : HashMap::new(),
+            calls : HashMap::new(),
+        }
+    }
+
+    /// this fn counts the instruction the chip8 is about to run with the keys it is about to get
+    pub fn record(&mut self, chip8 : &Chip8, keyboard : Keyboard) {
+        let pc = chip8.pc_reg as usize;
+        if pc + 1 >= chip8.memory.len() {
+            return
+        }
+
+        self.sync_call_stack(chip8);
+
+        if chip8.waiting_for_vblank {
+            self.vblank_wait_cycles += 1;
+            return
+        }
+
+        let instruction = ((chip8.memory[pc] as u16) << 8) | chip8.memory[pc + 1] as u16;
+        let class = disassembler::opcode_class(instruction);
+
+        if self.address_counts.len() != chip8.memory.len() {
+            self.address_counts.resize(chip8.memory.len(), 0);
+        }
+
+        self.instructions += 1;
+        self.address_counts[pc] += 1;
+        *self.class_counts.entry(class).or_insert(0) += 1;
+
+        if class == "FX0A" && keyboard.get_keycode() > 0xF {
+            self.key_wait_cycles += 1;
+        }
+
+        // the instruction counts for the subroutine it is in, so a call counts for the caller and a return for the callee
+        match self.stacks.get_mut(self.call_stack.as_slice()) {
+            Some(count) => *count += 1,
+            None => { self.stacks.insert(self.call_stack.clone(), 1); }
+        }
+
+        match class {
+            "2NNN" => {
+                let callee = instruction & 0x0FFF;
+                *self.calls.entry((self.call_stack.last().copied(), callee)).or_insert(0) += 1;
+                self.call_stack.push(callee);
+            },
+            "00EE" => { self.call_stack.pop(); },
+            _ => {}
+        }
+    }
+
+    /// this fn rebuilds the call stack from the chip8's stack if they stopped matching, like after a reset or a load state
+    ///
+    /// every return address comes right after the 2NNN that pushed it, so that instruction says where the subroutine starts
+    fn sync_call_stack(&mut self, chip8 : &Chip8) {
+        if self.call_stack.len() == chip8.stack.len() {
+            return
+        }
+
+        self.call_stack = chip8.stack.iter()
+            .map(|return_address| {
+                let call = (*return_address as usize).wrapping_sub(2);
+                match (chip8.memory.get(call), chip8.memory.get(call + 1)) {
+                    (Some(high), Some(low)) => (((*high as u16) << 8) | *low as u16) & 0x0FFF,
+                    _ => 0
+                }
+            })
+            .collect();
+    }
+
+    /// this fn writes the stacks in the folded format flamegraph tools read, waits for vblank are their own frame
+    pub fn folded_stacks(&self) -> String {
+        let mut lines = self.stacks.iter()
+            .map(|(stack, count)| format!("{} {}", Self::stack_name(stack), count))
+            .collect::<Vec<String>>();
+
+        if self.vblank_wait_cycles != 0 {
+            lines.push(format!("main;vblank {}", self.vblank_wait_cycles));
+        }
+
+        lines.sort();
+        lines.join("\n") + "\n"
+    }
+
+    /// this fn writes a report of the hottest addresses, the kinds of instructions, the waits and the call graph
+    ///
+    /// the memory is only used to disassemble the addresses so it should be the memory of the chip8 that was profiled
+    pub fn report(&self, memory : &[u8]) -> String {
+        let cycles = self.instructions + self.vblank_wait_cycles;
+        let percent = |count : u64| if cycles == 0 { 0.0 } else { count as f64 * 100.0 / cycles as f64 };
+
+        let mut report = format!("profile of {} cycles, {} instructions ran\n\n", cycles, self.instructions);
+
+        let draw_cycles = self.class_counts.get("DXYN").copied().unwrap_or(0);
+        report += "waits and drawing\n";
+        report += &format!("  {:<24} {:>10} {:>6.2}%\n", "DXYN draws", draw_cycles, percent(draw_cycles));
+        report += &format!("  {:<24} {:>10} {:>6.2}%\n", "waits for vblank", self.vblank_wait_cycles, percent(self.vblank_wait_cycles));
+        report += &format!("  {:<24} {:>10} {:>6.2}%\n", "FX0A waits for a key", self.key_wait_cycles, percent(self.key_wait_cycles));
+
+        let mut addresses = self.address_counts.iter()
+            .enumerate()
+            .filter(|(_, count)| **count != 0)
+            .collect::<Vec<(usize, &u64)>>();
+        addresses.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(&b.0)));
+
+        report += "\nhottest addresses\n";
+        for (address, count) in addresses.iter().take(Self::TOP_ADDRESSES) {
+            let disassembly = disassembler::disassemble_at(memory, *address);
+            report += &format!("  {:03X}  {:<18} {:>10} {:>6.2}%\n", address, disassembly, count, percent(**count));
+        }
+
+        let mut classes = self.class_counts.iter().collect::<Vec<(&&str, &u64)>>();
+        classes.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+        report += "\ninstructions\n";
+        for (class, count) in classes {
+            report += &format!("  {:<24} {:>10} {:>6.2}%\n", class, count, percent(*count));
+        }
+
+        // a subroutine's total is every instruction that ran while it was on the stack, its self is only its own
+        let mut totals : HashMap<Option<u16>, (u64, u64)> = HashMap::new();
+        for (stack, count) in self.stacks.iter() {
+            let subroutines = std::iter::once(None).chain(stack.iter().map(|address| Some(*address))).collect::<HashSet<Option<u16>>>();
+            for subroutine in subroutines {
+                totals.entry(subroutine).or_insert((0, 0)).0 += count;
+            }
+            totals.entry(stack.last().copied()).or_insert((0, 0)).1 += count;
+        }
+
+        let mut subroutines = totals.into_iter().collect::<Vec<(Option<u16>, (u64, u64))>>();
+        subroutines.sort_by(|a, b| b.1.0.cmp(&a.1.0).then(a.0.cmp(&b.0)));
+
+        report += &format!("\n  {:<24} {:>10} {:>7} {:>7}\n", "subroutines", "calls", "total", "self");
+        for (subroutine, (total, own)) in subroutines {
+            // nothing calls the main program
+            let calls = match subroutine {
+                Some(_) => self.calls.iter()
+                    .filter(|((_, callee), _)| Some(*callee) == subroutine)
+                    .map(|(_, count)| count)
+                    .sum::<u64>()
+                    .to_string(),
+                None => String::from("-")
+            };
+            report += &format!(
+                "  {:<24} {:>10} {:>6.2}% {:>6.2}%\n",
+                Self::subroutine_name(subroutine), calls, percent(total), percent(own)
+            );
+        }
+
+        let mut calls = self.calls.iter().collect::<Vec<(&(Option<u16>, u16), &u64)>>();
+        calls.sort_by(|a, b| a.0.cmp(b.0));
+
+        report += "\ncall graph\n";
+        for ((caller, callee), count) in calls {
+            report += &format!("  {} -> {} {}\n", Self::subroutine_name(*caller), Self::subroutine_name(Some(*callee)), count);
+        }
+
+        report
+    }
+
+    fn stack_name(stack : &[u16]) -> String {
+        std::iter::once(String::from("main"))
+            .chain(stack.iter().map(|address| Self::subroutine_name(Some(*address))))
+            .collect::<Vec<String>>()
+            .join(";")
+    }
+
+    fn subroutine_name(subroutine : Option<u16>) -> String {
+        match subroutine {
+            Some(address) => format!("sub_{:03X}", address),
+            None => String::from("main")
+        }
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Profiler {
+        Profiler::new()
+    }
+}
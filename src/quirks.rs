@@ -0,0 +1,132 @@
+/// these are the behaviors that chip8 interpreters disagree on
+///
+/// games are written for one interpreter's behavior and can break on another, so these can be set per game.
+/// the names match the quirk options octo uses so cartridges can set them directly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quirks {
+    // 8XY6 and 8XYE shift vx in place instead of shifting vy into vx
+    pub shift : bool,
+
+    // FX55 and FX65 leave the index register alone instead of moving it past the last register
+    pub load_store : bool,
+
+    // BNNN jumps to XNN plus vx instead of NNN plus v0
+    pub jump : bool,
+
+    // 8XY1 8XY2 and 8XY3 set vf to 0 instead of leaving it alone
+    pub logic : bool,
+
+    // sprites are cut off at the edges of the screen instead of wrapping around to the other side
+    pub clip : bool,
+
+    // DXYN waits for the next frame before the chip8 keeps going, which limits games to 60 sprites a second
+    pub vblank : bool,
+}
+
+impl Quirks {
+    /// these are the quirks rip8 has always run with
+    pub fn new() -> Quirks {
+        Quirks {
+            shift : true,
+            load_store : true,
+            jump : false,
+            logic : false,
+            clip : true,
+            vblank : false,
+        }
+    }
+
+    /// these are the quirks of the original cosmac vip interpreter that old chip8 games were written for
+    pub fn chip8() -> Quirks {
+        Quirks {
+            shift : false,
+            load_store : false,
+            jump : false,
+            logic : true,
+            clip : true,
+            vblank : true,
+        }
+    }
+
+    /// these are the quirks of superchip on the hp48, which a lot of games from the 90s were written for
+    pub fn schip() -> Quirks {
+        Quirks {
+            shift : true,
+            load_store : true,
+            jump : true,
+            logic : false,
+            clip : true,
+            vblank : false,
+        }
+    }
+
+    /// this fn gives back the quirks that go by a name, EX : --quirks schip
+    pub fn from_name(name : &str) -> Option<Quirks> {
+        match name {
+            "rip8" => Some(Quirks::new()),
+            "chip8" | "vip" => Some(Quirks::chip8()),
+            "schip" | "superchip" => Some(Quirks::schip()),
+            "octo" | "xochip" => Some(Quirks::octo()),
+            _ => None
+        }
+    }
+
+    /// these are the quirks octo starts with, a cartridge only lists the ones it turns on
+    pub fn octo() -> Quirks {
+        Quirks {
+            shift : false,
+            load_store : false,
+            jump : false,
+            logic : false,
+            clip : false,
+            vblank : false,
+        }
+    }
+
+    /// this fn lists the quirks that are on by name, EX : shift,load_store,clip or none when they are all off
+    pub fn names(&self) -> String {
+        let names = [
+            ("shift", self.shift),
+            ("load_store", self.load_store),
+            ("jump", self.jump),
+            ("logic", self.logic),
+            ("clip", self.clip),
+            ("vblank", self.vblank),
+        ];
+
+        let names = names.iter().filter(|(_, on)| *on).map(|(name, _)| *name).collect::<Vec<&str>>();
+        match names.is_empty() {
+            true => String::from("none"),
+            false => names.join(",")
+        }
+    }
+
+    /// this fn turns a list made by names back into quirks, any quirk that isn't listed is off
+    pub fn from_names(names : &str) -> Option<Quirks> {
+        // octo has every quirk off
+        let mut quirks = Quirks::octo();
+        if names == "none" {
+            return Some(quirks)
+        }
+
+        for name in names.split(',') {
+            match name {
+                "shift" => quirks.shift = true,
+                "load_store" => quirks.load_store = true,
+                "jump" => quirks.jump = true,
+                "logic" => quirks.logic = true,
+                "clip" => quirks.clip = true,
+                "vblank" => quirks.vblank = true,
+                _ => return None
+            }
+        }
+
+        Some(quirks)
+    }
+}
+
+impl Default for Quirks {
+    fn default() -> Quirks {
+        Quirks::new()
+    }
+}
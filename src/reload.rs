@@ -0,0 +1,60 @@
+use std::{
+    path::Path,
+    time::{
+        Duration,
+        Instant,
+        SystemTime
+    },
+};
+
+use crate::rom;
+
+/// this watches a rom or its source for changes so it can be reloaded while a game is being worked on
+///
+/// the file's modified time is checked a few times a second instead of every frame since that is plenty for someone
+/// saving in an editor. a file that can't be read for a moment, like while an editor swaps it out, isn't a change
+pub struct RomWatcher {
+    file_path : String,
+    modified : Option<SystemTime>,
+    last_check : Instant,
+}
+
+impl RomWatcher {
+    // how long to wait between looking at the file
+    const CHECK_INTERVAL : Duration = Duration::from_millis(250);
+
+    /// this fn starts watching the file a rom path reads from, for a rom inside a zip that is the zip
+    pub fn new(rom_path : &str) -> Result<RomWatcher, String> {
+        let (file_path, _) = rom::split_zip_entry(rom_path);
+
+        if file_path == "-" {
+            return Err(String::from("error : a rom from stdin can't be watched for changes!"))
+        }
+
+        Ok(RomWatcher {
+            file_path : file_path.to_string(),
+            modified : Self::modified_time(file_path),
+            last_check : Instant::now(),
+        })
+    }
+
+    /// this fn gives back true once each time the file changes
+    pub fn changed(&mut self) -> bool {
+        if self.last_check.elapsed() < Self::CHECK_INTERVAL {
+            return false
+        }
+        self.last_check = Instant::now();
+
+        match Self::modified_time(&self.file_path) {
+            Some(modified) if Some(modified) != self.modified => {
+                self.modified = Some(modified);
+                true
+            },
+            _ => false
+        }
+    }
+
+    fn modified_time(file_path : &str) -> Option<SystemTime> {
+        Path::new(file_path).metadata().and_then(|metadata| metadata.modified()).ok()
+    }
+}
@@ -0,0 +1,216 @@
+use std::{
+    fs::File,
+    io::{
+        self,
+        Cursor,
+        Read
+    },
+    path::Path,
+};
+
+use flate2::read::GzDecoder;
+
+use crate::{
+    Cartridge,
+    Chip8,
+    cartridge,
+    octo
+};
+
+// the first bytes of zip and gzip files, roms are checked for these instead of trusting the extension
+const ZIP_MAGIC : [u8; 4] = [0x50, 0x4B, 0x03, 0x04];
+const GZIP_MAGIC : [u8; 2] = [0x1F, 0x8B];
+
+// the extensions a rom inside a zip can have
+const ROM_EXTENSIONS : [&str; 3] = ["ch8", "sc8", "xo8"];
+
+// the biggest file that is read before it is unpacked, a zip can hold a lot of roms but nothing needs more than this
+const MAX_FILE_SIZE : usize = 16 * 1024 * 1024;
+
+/// this fn reads the bytes of a rom so they can be given to Chip8::load_rom
+///
+/// the path can be :
+/// - a plain rom file
+/// - '-' to read the rom from stdin
+/// - a gzipped rom
+/// - an octo cartridge gif, only the program is loaded here, see Cartridge for its settings
+/// - a zip with one rom in it, or 'games.zip:pong.ch8' to pick one out of a zip with more than one
+/// - octo source ending in .8o, which is assembled
+/// - a radix hex file ending in .chip8, see parse_radix
+///
+/// zip, gzip and gif are found by looking at the start of the file so they work through stdin too, source files
+/// can only be told apart by their extension. a rom that is bigger than the memory it has to fit in gives an error,
+/// even after it is unpacked
+pub fn read_rom(rom_path : &str) -> Result<Vec<u8>, String> {
+    let (file_path, zip_entry) = split_zip_entry(rom_path);
+
+    if zip_entry.is_none() {
+        if let Some(rom) = read_source_rom(file_path)? {
+            if rom.len() > Chip8::MAX_ROM_SIZE {
+                return Err(too_large_error(file_path, Chip8::MAX_ROM_SIZE))
+            }
+            return Ok(rom)
+        }
+    }
+
+    let bytes = if file_path == "-" {
+        read_limited(io::stdin().lock(), "stdin", MAX_FILE_SIZE)?
+    } else {
+        let file_handle = match File::open(file_path) {
+            Ok(file) => file,
+            _ => return Err(format!("error : could not load rom '{}'!", file_path))
+        };
+        read_limited(file_handle, file_path, MAX_FILE_SIZE)?
+    };
+
+    if bytes.starts_with(&ZIP_MAGIC) {
+        read_zip_rom(bytes, file_path, zip_entry)
+    } else if zip_entry.is_some() {
+        Err(format!("error : '{}' is not a zip file!", file_path))
+    } else if cartridge::is_cartridge(&bytes) {
+        let rom = Cartridge::from_bytes(&bytes)?.rom;
+        if rom.len() > Chip8::MAX_ROM_SIZE {
+            return Err(too_large_error(file_path, Chip8::MAX_ROM_SIZE))
+        }
+        Ok(rom)
+    } else if bytes.starts_with(&GZIP_MAGIC) {
+        read_limited(GzDecoder::new(bytes.as_slice()), file_path, Chip8::MAX_ROM_SIZE)
+    } else if bytes.len() > Chip8::MAX_ROM_SIZE {
+        Err(too_large_error(file_path, Chip8::MAX_ROM_SIZE))
+    } else {
+        Ok(bytes)
+    }
+}
+
+/// this fn reads a program written as one hex instruction on each line, lines starting with ';;' are comments
+///
+/// ```text
+/// ;; registers to set the font location
+/// 600c
+/// 6105
+/// ```
+pub fn parse_radix(text : &str) -> Result<Vec<u8>, String> {
+    let mut program = Vec::new();
+
+    for line in text.lines().map(str::trim) {
+        if line.starts_with(";;") || line.is_empty() {
+            continue
+        }
+
+        if program.len() >= Chip8::MAX_ROM_SIZE {
+            return Err(String::from("error : your program is too large! 4096 bytes of program memory maximum"))
+        }
+
+        match u16::from_str_radix(line, 16) {
+            Ok(instruction) => program.extend(instruction.to_be_bytes()),
+            _ => return Err(String::from("error : instructions must be in radix hex like 'XXXX' !"))
+        }
+    }
+
+    Ok(program)
+}
+
+/// this fn assembles or parses a rom that is written as source, it gives back none for anything else
+fn read_source_rom(file_path : &str) -> Result<Option<Vec<u8>>, String> {
+    let extension = Path::new(file_path).extension().map(|extension| extension.to_string_lossy().to_lowercase());
+
+    let parse : fn(&str) -> Result<Vec<u8>, String> = match extension.as_deref() {
+        Some("8o") => octo::assemble,
+        Some("chip8") => parse_radix,
+        _ => return Ok(None)
+    };
+
+    match std::fs::read_to_string(file_path) {
+        Ok(source) => parse(&source).map(Some),
+        _ => Err(format!("error : could not load rom '{}'!", file_path))
+    }
+}
+
+/// this fn gives back the names of the roms in a zip file
+pub fn zip_rom_names(zip_path : &str) -> Result<Vec<String>, String> {
+    let file_handle = match File::open(zip_path) {
+        Ok(file) => file,
+        _ => return Err(format!("error : could not load zip '{}'!", zip_path))
+    };
+
+    let archive = match zip::ZipArchive::new(file_handle) {
+        Ok(archive) => archive,
+        Err(error) => return Err(format!("error : could not read zip '{}' : {}", zip_path, error))
+    };
+
+    Ok(rom_names(&archive))
+}
+
+/// this fn splits 'games.zip:pong.ch8' into the zip and the rom inside it
+///
+/// a path that is a file that exists is never split, so a rom with a ':' in its name still loads
+pub(crate) fn split_zip_entry(rom_path : &str) -> (&str, Option<&str>) {
+    if Path::new(rom_path).exists() {
+        return (rom_path, None)
+    }
+
+    match rom_path.rsplit_once(':') {
+        Some((zip_path, entry)) if zip_path.to_lowercase().ends_with(".zip") || zip_path == "-" => (zip_path, Some(entry)),
+        _ => (rom_path, None)
+    }
+}
+
+fn read_zip_rom(bytes : Vec<u8>, zip_path : &str, zip_entry : Option<&str>) -> Result<Vec<u8>, String> {
+    let mut archive = match zip::ZipArchive::new(Cursor::new(bytes)) {
+        Ok(archive) => archive,
+        Err(error) => return Err(format!("error : could not read zip '{}' : {}", zip_path, error))
+    };
+
+    let entry_name = match zip_entry {
+        Some(entry) => entry.to_string(),
+        None => {
+            let mut roms = rom_names(&archive);
+
+            match roms.len() {
+                0 => return Err(format!("error : there are no roms in '{}'!", zip_path)),
+                1 => roms.remove(0),
+                _ => return Err(format!(
+                    "error : '{}' has more than one rom, pick one like '{}:{}'. the roms in it are :\n    {}",
+                    zip_path, zip_path, roms[0], roms.join("\n    ")
+                ))
+            }
+        }
+    };
+
+    let entry = match archive.by_name(&entry_name) {
+        Ok(entry) => entry,
+        Err(_) => return Err(format!("error : there is no '{}' in '{}'!", entry_name, zip_path))
+    };
+
+    read_limited(entry, &format!("{}:{}", zip_path, entry_name), Chip8::MAX_ROM_SIZE)
+}
+
+fn rom_names<R : Read + io::Seek>(archive : &zip::ZipArchive<R>) -> Vec<String> {
+    archive.file_names()
+        .filter(|name| {
+            let extension = Path::new(name).extension().map(|extension| extension.to_string_lossy().to_lowercase());
+            extension.is_some_and(|extension| ROM_EXTENSIONS.contains(&extension.as_str()))
+        })
+        .map(|name| name.to_string())
+        .collect::<Vec<String>>()
+}
+
+/// this fn reads everything from a reader but gives an error instead of reading more than max_size bytes
+fn read_limited<R : Read>(reader : R, name : &str, max_size : usize) -> Result<Vec<u8>, String> {
+    let mut bytes : Vec<u8> = Vec::new();
+
+    match reader.take(max_size.saturating_add(1) as u64).read_to_end(&mut bytes) {
+        Ok(_) => {},
+        Err(error) => return Err(format!("error : could not read rom '{}' : {}", name, error))
+    }
+
+    if bytes.len() > max_size {
+        return Err(too_large_error(name, max_size))
+    }
+
+    Ok(bytes)
+}
+
+fn too_large_error(name : &str, max_size : usize) -> String {
+    format!("error : the rom '{}' is too large! {} bytes maximum", name, max_size)
+}
@@ -0,0 +1,131 @@
+use std::path::Path;
+
+use crate::rom;
+
+// how big a chip8 pixel is in the window, scaled screenshots are the same size so they look like what was on screen
+pub const WINDOW_PIXEL_SIZE : usize = 20;
+
+/// these are the two colors the display is drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub off_color : [u8; 3],
+    pub on_color : [u8; 3],
+}
+
+impl Palette {
+    /// this is the palette the window starts with, white pixels on black
+    pub fn new() -> Palette {
+        Palette {
+            off_color : [0, 0, 0],
+            on_color : [255, 255, 255],
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Palette {
+        Palette::new()
+    }
+}
+
+/// this is how a screenshot should look
+#[derive(Debug, Clone, Copy)]
+pub enum ScreenshotView {
+    // one png pixel for every chip8 pixel, grayscale with on pixels white
+    Native,
+
+    // what the window shows, every chip8 pixel is a square of pixel_size with the palette colors
+    Scaled { palette : Palette, pixel_size : usize },
+}
+
+/// this fn saves a display buffer as a png
+///
+/// the size of the png comes from the display buffer so it works for any display resolution
+pub fn save_png(buffer : &[Vec<bool>], file_path : &str, view : ScreenshotView) -> Result<(), String> {
+    let png_bytes = encode_png(buffer, view)?;
+
+    match std::fs::write(file_path, png_bytes) {
+        Ok(_) => Ok(()),
+        _ => Err(format!("error : could not create screenshot file '{}'!", file_path))
+    }
+}
+
+/// this fn makes a png out of a display buffer without saving it anywhere
+pub fn encode_png(buffer : &[Vec<bool>], view : ScreenshotView) -> Result<Vec<u8>, String> {
+    let height = buffer.len();
+    let width = buffer.first().map_or(0, |row| row.len());
+
+    let (pixel_size, color_type, image_data) = match view {
+        ScreenshotView::Native => {
+            let image_data = buffer.iter()
+                .flatten()
+                .map(|pixel| if *pixel { 255 } else { 0 })
+                .collect::<Vec<u8>>();
+
+            (1, png::ColorType::Grayscale, image_data)
+        },
+        ScreenshotView::Scaled { palette, pixel_size } => {
+            let mut image_data : Vec<u8> = Vec::with_capacity(width * height * pixel_size * pixel_size * 3);
+
+            for row in buffer.iter() {
+                let scaled_row = row.iter()
+                    .flat_map(|pixel| {
+                        let color = if *pixel { palette.on_color } else { palette.off_color };
+                        std::iter::repeat_n(color, pixel_size).flatten()
+                    })
+                    .collect::<Vec<u8>>();
+
+                for _ in 0..pixel_size {
+                    image_data.extend(scaled_row.iter());
+                }
+            }
+
+            (pixel_size, png::ColorType::Rgb, image_data)
+        }
+    };
+
+    let mut png_bytes : Vec<u8> = Vec::new();
+
+    let mut encoder = png::Encoder::new(
+        &mut png_bytes,
+        (width * pixel_size) as u32,
+        (height * pixel_size) as u32
+    );
+    encoder.set_color(color_type);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let result = encoder.write_header().and_then(|mut writer| writer.write_image_data(&image_data));
+
+    match result {
+        Ok(_) => Ok(png_bytes),
+        Err(error) => Err(format!("error : could not make screenshot png : {}", error))
+    }
+}
+
+/// this fn makes the file name for a screenshot out of the rom name and the frame it was taken on
+///
+/// EX : fullgames/tank.ch8 on frame 120 is tank_000120.png
+pub fn screenshot_file_name(rom_path : &str, frame : usize, view : ScreenshotView) -> String {
+    let rom_name = rom_name(rom_path);
+
+    match view {
+        ScreenshotView::Native => format!("{}_{:06}_native.png", rom_name, frame),
+        ScreenshotView::Scaled { .. } => format!("{}_{:06}.png", rom_name, frame),
+    }
+}
+
+/// this fn gets the name of a rom from its path without the folders or extension
+///
+/// a rom picked out of a zip is named after the rom inside it and a rom from stdin is just called rip8
+pub fn rom_name(rom_path : &str) -> String {
+    let (rom_path, zip_entry) = rom::split_zip_entry(rom_path);
+    let rom_path = zip_entry.unwrap_or(rom_path);
+
+    if rom_path == "-" {
+        return String::from("rip8")
+    }
+
+    Path::new(rom_path)
+        .file_stem()
+        .map_or(String::from("rip8"), |stem| stem.to_string_lossy().to_string())
+}
@@ -0,0 +1,330 @@
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+
+use rhai::{
+    AST,
+    CallFnOptions,
+    Dynamic,
+    Engine,
+    EvalAltResult,
+    FnPtr,
+    INT,
+    Scope
+};
+
+use crate::{
+    Chip8,
+    Keyboard
+};
+
+/// these are the things a script asked the emulator to do that the chip8 can't do itself
+#[derive(Debug, Clone, Default)]
+pub struct ScriptEvents {
+    // screenshots to save, none means a name is picked from the rom and frame
+    pub screenshots : Vec<Option<String>>,
+
+    // text for the frontend to show, from show and print
+    pub messages : Vec<String>,
+
+    // the script is done and the emulator should stop
+    pub quit : bool,
+
+    // the script turned the buzzer off or back on, none leaves it how it is
+    pub mute : Option<bool>,
+}
+
+/// this is the copy of the chip8 a script works on, it is copied in before the script runs and back out after
+#[derive(Default)]
+struct ScriptContext {
+    memory : Vec<u8>,
+    v_regs : Vec<u8>,
+    index_reg : u16,
+    pc_reg : u16,
+    delay_timer_register : u8,
+    sound_timer_register : u8,
+    display_buffer : Vec<Vec<bool>>,
+    frame : usize,
+
+    // the key the script is holding down, none lets the frontend's keys through
+    keyboard : Option<Keyboard>,
+
+    events : ScriptEvents,
+}
+
+impl ScriptContext {
+    fn copy_from(&mut self, chip8 : &Chip8, frame : usize) {
+        self.memory.clone_from(&chip8.memory);
+        self.v_regs.clone_from(&chip8.v_regs);
+        self.index_reg = chip8.index_reg;
+        self.pc_reg = chip8.pc_reg;
+        self.delay_timer_register = chip8.delay_timer_register;
+        self.sound_timer_register = chip8.sound_timer_register;
+        self.display_buffer.clone_from(&chip8.display_buffer);
+        self.frame = frame;
+    }
+
+    fn copy_to(&self, chip8 : &mut Chip8) {
+        chip8.memory.copy_from_slice(&self.memory);
+        chip8.v_regs.copy_from_slice(&self.v_regs);
+        chip8.index_reg = self.index_reg;
+        chip8.pc_reg = self.pc_reg;
+        chip8.delay_timer_register = self.delay_timer_register;
+        chip8.sound_timer_register = self.sound_timer_register;
+    }
+}
+
+/// this is a rhai script attached to the chip8, for bots, smoke tests and tools that watch a game
+///
+/// the top of the script runs once after the rom is loaded, then fn on_frame(frame) runs at the start of every frame
+/// if the script has one, and functions given to on_pc run right before the instruction at their pc. EX :
+///
+/// ```text
+/// on_pc(0x2a4, |pc| show(`score ${peek(0x3f0)}`));
+///
+/// fn on_frame(frame) {
+///     if reg(0xa) < 0x20 { press(4) } else { press(6) }
+///     if frame == 600 {
+///         assert(peek(0x3f1) > 0, "the ball should still be in play");
+///         screenshot();
+///         quit();
+///     }
+/// }
+/// ```
+///
+/// the functions scripts get are :
+/// peek(address) poke(address, byte) reg(x) set_reg(x, byte) index() set_index(address) pc() set_pc(address)
+/// delay_timer() sound_timer() pixel(x, y) frame() press(key) release() screenshot() screenshot(path) show(text)
+/// mute() unmute() assert(condition) assert(condition, message) quit() and on_pc(address, function)
+pub struct Script {
+    engine : Engine,
+    ast : AST,
+    scope : Scope<'static>,
+    context : Rc<RefCell<ScriptContext>>,
+
+    // the functions to call at each pc
+    pc_hooks : Rc<RefCell<Vec<(u16, FnPtr)>>>,
+
+    has_on_frame : bool,
+}
+
+impl Script {
+    /// this fn loads a script and runs the top of it against the chip8
+    pub fn load(file_path : &str, chip8 : &mut Chip8) -> Result<Script, String> {
+        match std::fs::read_to_string(file_path) {
+            Ok(source) => Script::from_source(&source, chip8),
+            _ => Err(format!("error : could not load script '{}'!", file_path))
+        }
+    }
+
+    pub fn from_source(source : &str, chip8 : &mut Chip8) -> Result<Script, String> {
+        let context = Rc::new(RefCell::new(ScriptContext::default()));
+        let pc_hooks = Rc::new(RefCell::new(Vec::new()));
+
+        let mut engine = Engine::new();
+        Self::register_functions(&mut engine, &context, &pc_hooks);
+
+        let ast = match engine.compile(source) {
+            Ok(ast) => ast,
+            Err(error) => return Err(format!("error : could not compile script : {}!", error))
+        };
+
+        let has_on_frame = ast.iter_functions().any(|function| function.name == "on_frame" && function.params.len() == 1);
+
+        let mut script = Script {
+            engine,
+            ast,
+            scope : Scope::new(),
+            context,
+            pc_hooks,
+            has_on_frame,
+        };
+
+        // what the top of the script asks for comes out with the next events
+        let events = script.run(chip8, 0, |script| script.engine.run_ast_with_scope(&mut script.scope, &script.ast))?;
+        script.context.borrow_mut().events = events;
+
+        Ok(script)
+    }
+
+    /// this fn runs on_frame at the start of a frame
+    pub fn run_frame(&mut self, chip8 : &mut Chip8, frame : usize) -> Result<ScriptEvents, String> {
+        if !self.has_on_frame {
+            return Ok(std::mem::take(&mut self.context.borrow_mut().events))
+        }
+
+        self.run(chip8, frame, |script| {
+            // the top of the script already ran when it was loaded so only on_frame runs here
+            let options = CallFnOptions::new().eval_ast(false).rewind_scope(false);
+            script.engine.call_fn_with_options::<Dynamic>(options, &mut script.scope, &script.ast, "on_frame", (frame as INT,)).map(|_| ())
+        })
+    }
+
+    /// this fn checks if a function wants to run before the instruction at an address
+    pub fn has_pc_hook(&self, pc : u16) -> bool {
+        self.pc_hooks.borrow().iter().any(|(address, _)| *address == pc)
+    }
+
+    /// this fn runs the functions waiting on the chip8's pc, it runs every time the chip8 gets there
+    pub fn run_pc_hooks(&mut self, chip8 : &mut Chip8, frame : usize) -> Result<ScriptEvents, String> {
+        let pc = chip8.pc_reg;
+
+        // the hooks are copied out first so a hook can add more hooks
+        let hooks = self.pc_hooks.borrow().iter()
+            .filter(|(address, _)| *address == pc)
+            .map(|(_, function)| function.clone())
+            .collect::<Vec<FnPtr>>();
+
+        self.run(chip8, frame, |script| {
+            for function in hooks.iter() {
+                function.call::<Dynamic>(&script.engine, &script.ast, (pc as INT,)).map(|_| ())?;
+            }
+            Ok(())
+        })
+    }
+
+    /// this fn gives back the key the script is holding down
+    pub fn keyboard(&self) -> Option<Keyboard> {
+        self.context.borrow().keyboard
+    }
+
+    /// this fn copies the chip8 in for the script, runs it and copies the chip8 back out with what the script changed
+    fn run<F>(&mut self, chip8 : &mut Chip8, frame : usize, run : F) -> Result<ScriptEvents, String>
+    where
+        F : FnOnce(&mut Script) -> Result<(), Box<EvalAltResult>>
+    {
+        self.context.borrow_mut().copy_from(chip8, frame);
+
+        let result = run(self);
+
+        let mut context = self.context.borrow_mut();
+        context.copy_to(chip8);
+        let events = std::mem::take(&mut context.events);
+
+        match result {
+            Ok(_) => Ok(events),
+            Err(error) => Err(format!("error : script failed on frame {} : {}!", frame, error))
+        }
+    }
+
+    fn register_functions(engine : &mut Engine, context : &Rc<RefCell<ScriptContext>>, pc_hooks : &Rc<RefCell<Vec<(u16, FnPtr)>>>) {
+        // print goes to the frontend too so it shows up in the terminal frontend instead of drawing over it
+        let print_context = context.clone();
+        engine.on_print(move |text| print_context.borrow_mut().events.messages.push(text.to_string()));
+
+        let shared = context.clone();
+        engine.register_fn("peek", move |address : INT| -> Result<INT, Box<EvalAltResult>> {
+            match shared.borrow().memory.get(address as usize) {
+                Some(byte) if address >= 0 => Ok(*byte as INT),
+                _ => Err(format!("peek at {:#X} is outside of memory", address).into())
+            }
+        });
+
+        let shared = context.clone();
+        engine.register_fn("poke", move |address : INT, value : INT| -> Result<(), Box<EvalAltResult>> {
+            match shared.borrow_mut().memory.get_mut(address as usize) {
+                Some(byte) if address >= 0 => {
+                    *byte = value as u8;
+                    Ok(())
+                },
+                _ => Err(format!("poke at {:#X} is outside of memory", address).into())
+            }
+        });
+
+        let shared = context.clone();
+        engine.register_fn("reg", move |register : INT| -> Result<INT, Box<EvalAltResult>> {
+            match shared.borrow().v_regs.get(register as usize) {
+                Some(value) if register >= 0 => Ok(*value as INT),
+                _ => Err(format!("there is no register v{}", register).into())
+            }
+        });
+
+        let shared = context.clone();
+        engine.register_fn("set_reg", move |register : INT, value : INT| -> Result<(), Box<EvalAltResult>> {
+            match shared.borrow_mut().v_regs.get_mut(register as usize) {
+                Some(byte) if register >= 0 => {
+                    *byte = value as u8;
+                    Ok(())
+                },
+                _ => Err(format!("there is no register v{}", register).into())
+            }
+        });
+
+        let shared = context.clone();
+        engine.register_fn("index", move || shared.borrow().index_reg as INT);
+
+        let shared = context.clone();
+        engine.register_fn("set_index", move |address : INT| shared.borrow_mut().index_reg = address as u16);
+
+        let shared = context.clone();
+        engine.register_fn("pc", move || shared.borrow().pc_reg as INT);
+
+        let shared = context.clone();
+        engine.register_fn("set_pc", move |address : INT| shared.borrow_mut().pc_reg = address as u16);
+
+        let shared = context.clone();
+        engine.register_fn("delay_timer", move || shared.borrow().delay_timer_register as INT);
+
+        let shared = context.clone();
+        engine.register_fn("sound_timer", move || shared.borrow().sound_timer_register as INT);
+
+        let shared = context.clone();
+        engine.register_fn("frame", move || shared.borrow().frame as INT);
+
+        let shared = context.clone();
+        engine.register_fn("pixel", move |x : INT, y : INT| {
+            let context = shared.borrow();
+            context.display_buffer.get(y as usize).and_then(|row| row.get(x as usize)).copied().unwrap_or(false)
+        });
+
+        let shared = context.clone();
+        engine.register_fn("press", move |key : INT| -> Result<(), Box<EvalAltResult>> {
+            match key {
+                0..=0xF => {
+                    shared.borrow_mut().keyboard = Some(Keyboard::from_keycode(key as u8));
+                    Ok(())
+                },
+                _ => Err(format!("{} is not a chip8 key", key).into())
+            }
+        });
+
+        let shared = context.clone();
+        engine.register_fn("release", move || shared.borrow_mut().keyboard = None);
+
+        let shared = context.clone();
+        engine.register_fn("screenshot", move || shared.borrow_mut().events.screenshots.push(None));
+
+        let shared = context.clone();
+        engine.register_fn("screenshot", move |path : &str| shared.borrow_mut().events.screenshots.push(Some(path.to_string())));
+
+        let shared = context.clone();
+        engine.register_fn("show", move |text : &str| shared.borrow_mut().events.messages.push(text.to_string()));
+
+        let shared = context.clone();
+        engine.register_fn("mute", move || shared.borrow_mut().events.mute = Some(true));
+
+        let shared = context.clone();
+        engine.register_fn("unmute", move || shared.borrow_mut().events.mute = Some(false));
+
+        let shared = context.clone();
+        engine.register_fn("quit", move || shared.borrow_mut().events.quit = true);
+
+        engine.register_fn("assert", |condition : bool| -> Result<(), Box<EvalAltResult>> {
+            match condition {
+                true => Ok(()),
+                false => Err("assert failed".into())
+            }
+        });
+
+        engine.register_fn("assert", |condition : bool, message : &str| -> Result<(), Box<EvalAltResult>> {
+            match condition {
+                true => Ok(()),
+                false => Err(format!("assert failed : {}", message).into())
+            }
+        });
+
+        let hooks = pc_hooks.clone();
+        engine.register_fn("on_pc", move |address : INT, function : FnPtr| hooks.borrow_mut().push((address as u16, function)));
+    }
+}
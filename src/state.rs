@@ -0,0 +1,166 @@
+use rand_chacha::ChaCha8Rng;
+use rand::SeedableRng;
+use serde_json::{
+    json,
+    Value
+};
+
+use crate::{
+    Chip8,
+    Quirks
+};
+
+impl Chip8 {
+    // the version of the save state layout, a state from a different version won't load
+    const STATE_VERSION : u64 = 1;
+
+    /// this fn saves everything about the chip8 the rom can see as json so it can be loaded again later
+    ///
+    /// memory is a hex string and the display is a hex string with 8 pixels to a byte, top left first.
+    /// the rng is saved as its seed and how far into it the chip8 is so random numbers carry on the same after loading
+    pub fn save_state(&self) -> Value {
+        let display = self.display_buffer.iter()
+            .flat_map(|row| row.chunks(8).map(|pixels| pixels.iter().fold(0u8, |byte, pixel| (byte << 1) | *pixel as u8)))
+            .collect::<Vec<u8>>();
+
+        json!({
+            "version" : Self::STATE_VERSION,
+            "memory" : encode_hex(&self.memory),
+            "display" : encode_hex(&display),
+            "display_width" : self.display_buffer.first().map_or(0, |row| row.len()),
+            "display_height" : self.display_buffer.len(),
+            "stack" : self.stack,
+            "pc" : self.pc_reg,
+            "i" : self.index_reg,
+            "v" : self.v_regs,
+            "delay_timer" : self.delay_timer_register,
+            "sound_timer" : self.sound_timer_register,
+            "seed" : self.seed,
+            // this can be bigger than json numbers can hold exactly so it is a string
+            "rng_position" : self.rng.get_word_pos().to_string(),
+            "waiting_for_vblank" : self.waiting_for_vblank,
+            "quirks" : {
+                "shift" : self.quirks.shift,
+                "load_store" : self.quirks.load_store,
+                "jump" : self.quirks.jump,
+                "logic" : self.quirks.logic,
+                "clip" : self.quirks.clip,
+                "vblank" : self.quirks.vblank,
+            },
+        })
+    }
+
+    /// this fn loads a state made by save_state, nothing changes if the state is bad
+    pub fn load_state(&mut self, state : &Value) -> Result<(), String> {
+        let bad_state = |field : &str| format!("error : the save state has a bad '{}'!", field);
+
+        let number = |field : &str| state.get(field).and_then(Value::as_u64).ok_or_else(|| bad_state(field));
+        let flag = |value : &Value, field : &str| value.get(field).and_then(Value::as_bool).ok_or_else(|| bad_state(field));
+        let bytes = |field : &str| -> Result<Vec<u8>, String> {
+            match state.get(field) {
+                Some(Value::Array(values)) => values.iter()
+                    .map(|value| value.as_u64().filter(|value| *value <= u8::MAX as u64).map(|value| value as u8))
+                    .collect::<Option<Vec<u8>>>()
+                    .ok_or_else(|| bad_state(field)),
+                Some(Value::String(hex)) => decode_hex(hex).ok_or_else(|| bad_state(field)),
+                _ => Err(bad_state(field))
+            }
+        };
+
+        if number("version")? != Self::STATE_VERSION {
+            return Err(String::from("error : the save state is from a different version of rip8!"))
+        }
+
+        let memory = bytes("memory")?;
+        if memory.len() != self.memory.len() {
+            return Err(bad_state("memory"))
+        }
+
+        // drawing only knows the one display size so a state with any other can't be loaded
+        let width = number("display_width")?;
+        let height = number("display_height")?;
+        let display = bytes("display")?;
+        if width != Self::SCREEN_WIDTH as u64 || height != Self::SCREEN_HEIGHT as u64
+            || display.len() != Self::SCREEN_WIDTH / 8 * Self::SCREEN_HEIGHT {
+            return Err(bad_state("display"))
+        }
+
+        let stack = match state.get("stack").and_then(Value::as_array) {
+            Some(stack) => stack.iter()
+                .map(|address| address.as_u64().filter(|address| *address <= u16::MAX as u64).map(|address| address as u16))
+                .collect::<Option<Vec<u16>>>()
+                .filter(|stack| stack.len() <= Chip8::STACK_SIZE)
+                .ok_or_else(|| bad_state("stack"))?,
+            None => return Err(bad_state("stack"))
+        };
+
+        let v_regs = bytes("v")?;
+        if v_regs.len() != self.v_regs.len() {
+            return Err(bad_state("v"))
+        }
+
+        let rng_position = state.get("rng_position")
+            .and_then(Value::as_str)
+            .and_then(|position| position.parse::<u128>().ok())
+            .ok_or_else(|| bad_state("rng_position"))?;
+
+        let quirks = match state.get("quirks") {
+            Some(quirks) => Quirks {
+                shift : flag(quirks, "shift")?,
+                load_store : flag(quirks, "load_store")?,
+                jump : flag(quirks, "jump")?,
+                logic : flag(quirks, "logic")?,
+                clip : flag(quirks, "clip")?,
+                vblank : flag(quirks, "vblank")?,
+            },
+            None => return Err(bad_state("quirks"))
+        };
+
+        // i can point past the end of memory because instructions wrap it, the pc can't
+        let in_range = |field : &str, limit : u64| state.get(field)
+            .and_then(Value::as_u64)
+            .filter(|value| *value <= limit)
+            .ok_or_else(|| bad_state(field));
+        let pc_reg = in_range("pc", self.memory.len() as u64 - 1)? as u16;
+        let index_reg = in_range("i", u16::MAX as u64)? as u16;
+        let delay_timer_register = in_range("delay_timer", u8::MAX as u64)? as u8;
+        let sound_timer_register = in_range("sound_timer", u8::MAX as u64)? as u8;
+        let seed = number("seed")?;
+        let waiting_for_vblank = flag(state, "waiting_for_vblank")?;
+
+        // everything was read without a problem so now the chip8 can change
+        self.memory = memory;
+        self.display_buffer = display.chunks(Self::SCREEN_WIDTH / 8)
+            .map(|row| row.iter().flat_map(|byte| (0..8).rev().map(move |bit| (byte >> bit) & 1 == 1)).collect())
+            .collect();
+        self.stack = stack;
+        self.pc_reg = pc_reg;
+        self.index_reg = index_reg;
+        self.v_regs = v_regs;
+        self.delay_timer_register = delay_timer_register;
+        self.sound_timer_register = sound_timer_register;
+        self.seed = seed;
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+        self.rng.set_word_pos(rng_position);
+        self.waiting_for_vblank = waiting_for_vblank;
+        self.quirks = quirks;
+
+        Ok(())
+    }
+}
+
+/// this fn writes bytes as lowercase hex, two digits to a byte
+pub(crate) fn encode_hex(bytes : &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// this fn reads hex made by encode_hex back into bytes, it gives back none if it isn't hex
+pub(crate) fn decode_hex(text : &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None
+    }
+
+    (0..text.len()).step_by(2)
+        .map(|index| u8::from_str_radix(&text[index..index + 2], 16).ok())
+        .collect()
+}
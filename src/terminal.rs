@@ -0,0 +1,389 @@
+use std::{
+    io::{
+        Stdout,
+        Write,
+        stdout
+    },
+    time::{
+        Duration,
+        Instant
+    },
+};
+
+use crossterm::{
+    cursor,
+    event::{
+        self,
+        Event,
+        KeyCode,
+        KeyEvent,
+        KeyEventKind,
+        KeyModifiers,
+        KeyboardEnhancementFlags,
+        PushKeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags
+    },
+    queue,
+    style::{
+        Color,
+        Print,
+        ResetColor,
+        SetBackgroundColor,
+        SetForegroundColor
+    },
+    terminal,
+};
+
+use crate::{ Keyboard, Keymap, Hotkey, Palette, Frontend };
+
+/// these are the ways the display can be drawn with text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminalStyle {
+    // every character is 1 pixel wide and 2 pixels tall using the upper half block with two colors
+    HalfBlock,
+
+    // every character is 2 pixels wide and 4 pixels tall using braille dots, this fits in small terminals
+    Braille,
+}
+
+/// these are the settings for the terminal frontend
+#[derive(Debug, Clone, Copy)]
+pub struct TerminalConfig {
+    pub style : TerminalStyle,
+
+    // if the terminal bell should ring when the buzzer starts, otherwise the terminal is silent
+    pub bell : bool,
+}
+
+impl TerminalConfig {
+    pub fn new() -> TerminalConfig {
+        TerminalConfig {
+            style : TerminalStyle::HalfBlock,
+            bell : false,
+        }
+    }
+}
+
+impl Default for TerminalConfig {
+    fn default() -> TerminalConfig {
+        TerminalConfig::new()
+    }
+}
+
+/// this is a frontend that draws the chip8 display in a terminal so games can be played over ssh
+///
+/// most terminals only send key presses and never key releases, so a key is treated as held until
+/// no press or repeat for it has come in for a little while. terminals that can report releases
+/// (the kitty keyboard protocol) get real key releases instead
+pub struct TerminalWindow {
+    pub config : TerminalConfig,
+    pub palette : Palette,
+
+    // which keys are the chip8 keypad
+    pub keymap : Keymap,
+
+    // emulator controls pressed since the last time they were taken
+    pub hotkeys : Vec<Hotkey>,
+
+    stdout : Stdout,
+
+    // the key being held and when it was last seen pressed
+    held_key : Option<(Keyboard, Instant)>,
+
+    // if the terminal reports key releases so held keys don't have to be guessed
+    reports_releases : bool,
+
+    // terminals can't report the tab key being let go of so fast forward is a toggle here
+    fast_forward : bool,
+
+    muted : bool,
+    last_sound_timer : u8,
+
+    title : String,
+
+    // the last message shown on the status line
+    message : String,
+
+    // the last buffer that was drawn so the screen is only redrawn when something changed
+    last_buffer : Vec<Vec<bool>>,
+}
+
+impl TerminalWindow {
+    // how long a key stays held after the last time the terminal sent it
+    // this has to be longer than the delay before key repeat starts or held keys flicker
+    const KEY_HOLD_TIME : Duration = Duration::from_millis(550);
+
+    pub fn new(config : TerminalConfig) -> Result<TerminalWindow, String> {
+        let mut stdout = stdout();
+
+        let setup_result = terminal::enable_raw_mode().and_then(|_| queue!(
+            stdout,
+            terminal::EnterAlternateScreen,
+            cursor::Hide,
+            terminal::Clear(terminal::ClearType::All)
+        ));
+
+        if setup_result.is_err() {
+            return Err(String::from("error : could not set up the terminal!"))
+        }
+
+        let reports_releases = matches!(terminal::supports_keyboard_enhancement(), Ok(true));
+        if reports_releases {
+            let _ = queue!(stdout, PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES));
+        }
+
+        let _ = stdout.flush();
+
+        Ok(TerminalWindow {
+            config,
+            palette : Palette::new(),
+            keymap : Keymap::new(),
+            hotkeys : Vec::new(),
+            stdout,
+            held_key : None,
+            reports_releases,
+            fast_forward : false,
+            muted : false,
+            last_sound_timer : 0,
+            title : String::from("rip8"),
+            message : String::new(),
+            last_buffer : Vec::new(),
+        })
+    }
+
+    fn handle_key_event(&mut self, key_event : KeyEvent) {
+        if key_event.kind == KeyEventKind::Release {
+            if let Some((keyboard, _)) = self.held_key {
+                if self.map_keypad(key_event.code).map(|key| key.get_keycode()) == Some(keyboard.get_keycode()) {
+                    self.held_key = None;
+                }
+            }
+            return
+        }
+
+        let is_repeat = key_event.kind == KeyEventKind::Repeat;
+
+        let hotkey = match key_event.code {
+            KeyCode::Esc => Some(Hotkey::Quit),
+            KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => Some(Hotkey::Quit),
+            KeyCode::Char('p') if !is_repeat => Some(Hotkey::Pause),
+            KeyCode::Char('n') => Some(Hotkey::FrameAdvance),
+            KeyCode::F(5) if !is_repeat => Some(Hotkey::Reset),
+            KeyCode::Char('=') => Some(Hotkey::SpeedUp),
+            KeyCode::Char('-') => Some(Hotkey::SlowDown),
+            KeyCode::Char('m') if !is_repeat => Some(Hotkey::ToggleMute),
+            KeyCode::F(12) if !is_repeat => Some(Hotkey::Screenshot { native : false }),
+            KeyCode::F(11) if !is_repeat => Some(Hotkey::Screenshot { native : true }),
+            KeyCode::F(9) if !is_repeat => Some(Hotkey::ToggleCapture),
+            KeyCode::Tab if !is_repeat => {
+                self.fast_forward = !self.fast_forward;
+                Some(Hotkey::FastForward(self.fast_forward))
+            },
+            _ => None
+        };
+
+        if let Some(hotkey) = hotkey {
+            self.hotkeys.push(hotkey);
+            return
+        }
+
+        if let Some(keyboard) = self.map_keypad(key_event.code) {
+            self.held_key = Some((keyboard, Instant::now()));
+        }
+    }
+
+    /// this fn maps a key to the chip8 keypad with the same keymap the sdl window uses
+    fn map_keypad(&self, key_code : KeyCode) -> Option<Keyboard> {
+        let name = match key_code {
+            KeyCode::Char(character) => character.to_lowercase().to_string(),
+            KeyCode::Up => String::from("ArrowUp"),
+            KeyCode::Down => String::from("ArrowDown"),
+            KeyCode::Left => String::from("ArrowLeft"),
+            KeyCode::Right => String::from("ArrowRight"),
+            KeyCode::Enter => String::from("Enter"),
+            _ => return None
+        };
+
+        self.keymap.key_for(&name)
+    }
+
+    /// this fn draws two rows of pixels per line with '▀', the top pixel is the text color and the bottom is the background
+    fn draw_half_blocks(&self, buffer : &[Vec<bool>]) -> Vec<String> {
+        let on_color = Self::to_color(self.palette.on_color);
+        let off_color = Self::to_color(self.palette.off_color);
+
+        buffer.chunks(2)
+            .map(|rows| {
+                let mut line = String::new();
+
+                for x in 0..rows[0].len() {
+                    let top = rows[0][x];
+                    let bottom = rows.get(1).is_some_and(|row| row[x]);
+
+                    let top_color = if top { on_color } else { off_color };
+                    let bottom_color = if bottom { on_color } else { off_color };
+
+                    line.push_str(&format!(
+                        "{}{}\u{2580}",
+                        SetForegroundColor(top_color),
+                        SetBackgroundColor(bottom_color)
+                    ));
+                }
+
+                line
+            })
+            .collect()
+    }
+
+    /// this fn draws 2 by 4 pixels per character with braille dots
+    fn draw_braille(&self, buffer : &[Vec<bool>]) -> Vec<String> {
+        // the bit for each dot in a braille character by [row][column]
+        const DOT_BITS : [[u32; 2]; 4] = [
+            [0x01, 0x08],
+            [0x02, 0x10],
+            [0x04, 0x20],
+            [0x40, 0x80],
+        ];
+
+        let width = buffer.first().map_or(0, |row| row.len());
+
+        buffer.chunks(4)
+            .map(|rows| {
+                (0..width).step_by(2)
+                    .map(|x| {
+                        let mut dots = 0;
+
+                        for (dot_row, row) in rows.iter().enumerate() {
+                            for (dot_column, dot_bit) in DOT_BITS[dot_row].iter().enumerate() {
+                                if row.get(x + dot_column).is_some_and(|pixel| *pixel) {
+                                    dots |= dot_bit;
+                                }
+                            }
+                        }
+
+                        char::from_u32(0x2800 + dots).unwrap_or(' ')
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn to_color(color : [u8; 3]) -> Color {
+        Color::Rgb { r : color[0], g : color[1], b : color[2] }
+    }
+}
+
+impl Frontend for TerminalWindow {
+    /// this fn sets the title of the terminal and the status line under the display
+    fn set_title(&mut self, title : &str) {
+        self.title = title.to_string();
+        let _ = queue!(self.stdout, terminal::SetTitle(title));
+
+        // the status line is part of the screen so force a redraw
+        self.last_buffer.clear();
+    }
+
+    /// this fn gives back every hotkey that was pressed since the last time it was called
+    fn take_hotkeys(&mut self) -> Vec<Hotkey> {
+        std::mem::take(&mut self.hotkeys)
+    }
+
+    /// this fn rings the bell when the buzzer starts if the bell is turned on
+    fn set_sound_timer(&mut self, sound_timer : u8) {
+        if self.config.bell && !self.muted && sound_timer != 0 && self.last_sound_timer == 0 {
+            let _ = queue!(self.stdout, Print('\u{7}'));
+        }
+
+        self.last_sound_timer = sound_timer;
+    }
+
+    fn set_muted(&mut self, muted : bool) {
+        self.muted = muted;
+    }
+
+    /// this fn reads every key the terminal sent since the last frame and gives back the chip8 key being held
+    fn handle_input(&mut self) -> Keyboard {
+        while let Ok(true) = event::poll(Duration::ZERO) {
+            match event::read() {
+                Ok(Event::Key(key_event)) => self.handle_key_event(key_event),
+                Ok(Event::Resize(..)) => {
+                    let _ = queue!(self.stdout, terminal::Clear(terminal::ClearType::All));
+                    self.last_buffer.clear();
+                },
+                _ => {}
+            }
+        }
+
+        match self.held_key {
+            Some((keyboard, last_seen)) if self.reports_releases || last_seen.elapsed() < Self::KEY_HOLD_TIME => keyboard,
+            _ => {
+                self.held_key = None;
+                Keyboard::None
+            }
+        }
+    }
+
+    fn draw_canvas(&mut self, buffer : &[Vec<bool>]) {
+        if self.last_buffer == buffer {
+            let _ = self.stdout.flush();
+            return
+        }
+
+        let lines = match self.config.style {
+            TerminalStyle::HalfBlock => self.draw_half_blocks(buffer),
+            TerminalStyle::Braille => self.draw_braille(buffer),
+        };
+
+        let on_color = Self::to_color(self.palette.on_color);
+        let off_color = Self::to_color(self.palette.off_color);
+
+        let _ = queue!(self.stdout, cursor::MoveTo(0, 0));
+
+        for (line_index, line) in lines.iter().enumerate() {
+            let _ = queue!(
+                self.stdout,
+                cursor::MoveTo(0, line_index as u16),
+                SetForegroundColor(on_color),
+                SetBackgroundColor(off_color),
+                Print(line),
+                ResetColor
+            );
+        }
+
+        let _ = queue!(
+            self.stdout,
+            cursor::MoveTo(0, lines.len() as u16),
+            terminal::Clear(terminal::ClearType::CurrentLine),
+            Print(&self.title),
+            Print("  "),
+            Print(&self.message)
+        );
+
+        let _ = self.stdout.flush();
+
+        self.last_buffer = buffer.to_vec();
+    }
+
+    fn palette(&self) -> Palette {
+        self.palette
+    }
+
+    /// this fn shows a message on the status line next to the title, the terminal can't print over its display
+    fn show_message(&mut self, message : &str) {
+        self.message = message.to_string();
+        self.last_buffer.clear();
+    }
+}
+
+impl Drop for TerminalWindow {
+    /// this puts the terminal back the way it was so the shell still works after quitting
+    fn drop(&mut self) {
+        if self.reports_releases {
+            let _ = queue!(self.stdout, PopKeyboardEnhancementFlags);
+        }
+
+        let _ = queue!(self.stdout, ResetColor, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = self.stdout.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
@@ -0,0 +1,292 @@
+use std::{
+    fs::File,
+    io::{
+        BufWriter,
+        Write
+    },
+};
+
+use crate::Palette;
+
+/// this fn scales a display buffer up so every chip8 pixel is a square of pixel_size
+///
+/// the result is one byte per pixel, 1 for on and 0 for off
+fn scale_buffer(buffer : &[Vec<bool>], pixel_size : usize) -> Vec<u8> {
+    let mut pixels : Vec<u8> = Vec::new();
+
+    for row in buffer.iter() {
+        let scaled_row = row.iter()
+            .flat_map(|pixel| std::iter::repeat_n(*pixel as u8, pixel_size))
+            .collect::<Vec<u8>>();
+
+        for _ in 0..pixel_size {
+            pixels.extend(scaled_row.iter());
+        }
+    }
+
+    pixels
+}
+
+/// this records frames of the display into an animated gif with the palette's two colors
+///
+/// the chip8 runs at 60 frames a second but gif delays are in hundredths of a second, so the frame
+/// times are rounded in a way that keeps the total in step. frames that are the same as the one before them
+/// just make the one before them last longer which keeps the files small
+pub struct GifRecorder {
+    encoder : gif::Encoder<BufWriter<File>>,
+    width : u16,
+    height : u16,
+    pixel_size : usize,
+
+    // the last frame is held onto until a different frame comes in so its delay is known
+    last_frame : Option<Vec<u8>>,
+    last_frame_delay : u16,
+
+    // how many hundredths of a second have been given out to frames so far and how many frames there have been
+    centiseconds_written : u64,
+    frames_seen : u64,
+}
+
+impl GifRecorder {
+    pub fn new(file_path : &str, palette : Palette, width : usize, height : usize, pixel_size : usize) -> Result<GifRecorder, String> {
+        let file_handle = match File::create(file_path) {
+            Ok(file) => file,
+            _ => return Err(format!("error : could not create gif file '{}'!", file_path))
+        };
+
+        let width = (width * pixel_size) as u16;
+        let height = (height * pixel_size) as u16;
+
+        let global_palette = [palette.off_color, palette.on_color].concat();
+
+        let mut encoder = match gif::Encoder::new(BufWriter::new(file_handle), width, height, &global_palette) {
+            Ok(encoder) => encoder,
+            Err(error) => return Err(format!("error : could not write gif '{}' : {}", file_path, error))
+        };
+
+        if let Err(error) = encoder.set_repeat(gif::Repeat::Infinite) {
+            return Err(format!("error : could not write gif '{}' : {}", file_path, error))
+        }
+
+        Ok(GifRecorder {
+            encoder,
+            width,
+            height,
+            pixel_size,
+            last_frame : None,
+            last_frame_delay : 0,
+            centiseconds_written : 0,
+            frames_seen : 0,
+        })
+    }
+
+    /// this fn adds one 60th of a second of the display to the gif
+    pub fn push_frame(&mut self, buffer : &[Vec<bool>]) -> Result<(), String> {
+        let pixels = scale_buffer(buffer, self.pixel_size);
+
+        // this is how long the gif should be after this frame in hundredths of a second
+        self.frames_seen += 1;
+        let delay = (self.frames_seen * 100 / 60 - self.centiseconds_written) as u16;
+        self.centiseconds_written += delay as u64;
+
+        match &self.last_frame {
+            Some(last_frame) if *last_frame == pixels => {
+                self.last_frame_delay += delay;
+            },
+            _ => {
+                self.write_last_frame()?;
+                self.last_frame = Some(pixels);
+                self.last_frame_delay = delay;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// this fn writes out the frame that was being held and flushes the file
+    pub fn finish(mut self) -> Result<(), String> {
+        self.write_last_frame()?;
+
+        match self.encoder.into_inner().and_then(|mut writer| writer.flush()) {
+            Ok(_) => Ok(()),
+            _ => Err(String::from("error : could not write to gif file!"))
+        }
+    }
+
+    fn write_last_frame(&mut self) -> Result<(), String> {
+        let pixels = match self.last_frame.take() {
+            Some(pixels) => pixels,
+            None => return Ok(())
+        };
+
+        let mut frame = gif::Frame::from_indexed_pixels(self.width, self.height, pixels, None);
+        // a gif delay of 0 plays as fast as the viewer can so the shortest delay is 1
+        frame.delay = self.last_frame_delay.max(1);
+
+        match self.encoder.write_frame(&frame) {
+            Ok(_) => Ok(()),
+            Err(error) => Err(format!("error : could not write gif frame : {}", error))
+        }
+    }
+}
+
+/// this writes frames of the display as a raw yuv4mpeg2 stream at 60 frames a second
+///
+/// y4m can be piped straight into an encoder, EX : rip8 ... --y4m /dev/stdout | ffmpeg -i - game.mp4
+pub struct Y4mWriter {
+    writer : BufWriter<File>,
+    palette : Palette,
+    width : usize,
+    height : usize,
+    pixel_size : usize,
+}
+
+impl Y4mWriter {
+    pub fn new(file_path : &str, palette : Palette, width : usize, height : usize, pixel_size : usize) -> Result<Y4mWriter, String> {
+        let file_handle = match File::create(file_path) {
+            Ok(file) => file,
+            _ => return Err(format!("error : could not create y4m file '{}'!", file_path))
+        };
+
+        // 4:2:0 needs the size to be even, doubling the pixel size keeps everything lined up with the chip8 pixels
+        let pixel_size = if pixel_size.is_multiple_of(2) { pixel_size } else { pixel_size * 2 };
+
+        let mut y4m_writer = Y4mWriter {
+            writer : BufWriter::new(file_handle),
+            palette,
+            width : width * pixel_size,
+            height : height * pixel_size,
+            pixel_size,
+        };
+
+        let header = format!("YUV4MPEG2 W{} H{} F60:1 Ip A1:1 C420jpeg\n", y4m_writer.width, y4m_writer.height);
+        y4m_writer.write(header.as_bytes())?;
+
+        Ok(y4m_writer)
+    }
+
+    /// this fn adds one frame of the display to the stream
+    pub fn push_frame(&mut self, buffer : &[Vec<bool>]) -> Result<(), String> {
+        let off_color = Self::to_ycbcr(self.palette.off_color);
+        let on_color = Self::to_ycbcr(self.palette.on_color);
+
+        let pixels = scale_buffer(buffer, self.pixel_size);
+
+        let luma = pixels.iter()
+            .map(|pixel| if *pixel == 1 { on_color[0] } else { off_color[0] })
+            .collect::<Vec<u8>>();
+
+        // every chroma sample covers 2 by 2 pixels, since the pixel size is even those 4 pixels are always the same color
+        let chroma_pixels = pixels.chunks(self.width)
+            .step_by(2)
+            .flat_map(|row| row.iter().step_by(2))
+            .collect::<Vec<&u8>>();
+
+        let blue_chroma = chroma_pixels.iter()
+            .map(|pixel| if **pixel == 1 { on_color[1] } else { off_color[1] })
+            .collect::<Vec<u8>>();
+
+        let red_chroma = chroma_pixels.iter()
+            .map(|pixel| if **pixel == 1 { on_color[2] } else { off_color[2] })
+            .collect::<Vec<u8>>();
+
+        self.write(b"FRAME\n")?;
+        self.write(&luma)?;
+        self.write(&blue_chroma)?;
+        self.write(&red_chroma)
+    }
+
+    pub fn finish(mut self) -> Result<(), String> {
+        match self.writer.flush() {
+            Ok(_) => Ok(()),
+            _ => Err(String::from("error : could not write to y4m file!"))
+        }
+    }
+
+    /// this fn converts an rgb color to full range ycbcr which is what C420jpeg means
+    fn to_ycbcr(color : [u8; 3]) -> [u8; 3] {
+        let [red, green, blue] = color.map(|channel| channel as f32);
+
+        let luma = 0.299 * red + 0.587 * green + 0.114 * blue;
+        let blue_chroma = 128.0 + (blue - luma) * 0.564;
+        let red_chroma = 128.0 + (red - luma) * 0.713;
+
+        [luma, blue_chroma, red_chroma].map(|channel| channel.round().clamp(0.0, 255.0) as u8)
+    }
+
+    fn write(&mut self, bytes : &[u8]) -> Result<(), String> {
+        match self.writer.write_all(bytes) {
+            Ok(_) => Ok(()),
+            _ => Err(String::from("error : could not write to y4m file!"))
+        }
+    }
+}
+
+/// this is everything that is recording video, the emulator loop just pushes every frame here
+pub struct VideoCapture {
+    pub gif_recorder : Option<GifRecorder>,
+    pub y4m_writer : Option<Y4mWriter>,
+
+    // the gif the capture hotkey started, it is kept apart so the hotkey only ever stops its own recording
+    pub hotkey_recorder : Option<GifRecorder>,
+}
+
+impl VideoCapture {
+    // how big a chip8 pixel is in captured video
+    pub const PIXEL_SIZE : usize = 8;
+
+    pub fn new() -> VideoCapture {
+        VideoCapture {
+            gif_recorder : None,
+            y4m_writer : None,
+            hotkey_recorder : None,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.gif_recorder.is_some() || self.y4m_writer.is_some() || self.hotkey_recorder.is_some()
+    }
+
+    pub fn push_frame(&mut self, buffer : &[Vec<bool>]) -> Result<(), String> {
+        if let Some(gif_recorder) = &mut self.gif_recorder {
+            gif_recorder.push_frame(buffer)?;
+        }
+
+        if let Some(y4m_writer) = &mut self.y4m_writer {
+            y4m_writer.push_frame(buffer)?;
+        }
+
+        if let Some(hotkey_recorder) = &mut self.hotkey_recorder {
+            hotkey_recorder.push_frame(buffer)?;
+        }
+
+        Ok(())
+    }
+
+    /// this fn finishes the gif the capture hotkey started and leaves everything else recording
+    pub fn finish_hotkey_recording(&mut self) -> Result<(), String> {
+        match self.hotkey_recorder.take() {
+            Some(hotkey_recorder) => hotkey_recorder.finish(),
+            None => Ok(())
+        }
+    }
+
+    /// this fn finishes every recording and stops capturing
+    pub fn finish(&mut self) -> Result<(), String> {
+        if let Some(gif_recorder) = self.gif_recorder.take() {
+            gif_recorder.finish()?;
+        }
+
+        if let Some(y4m_writer) = self.y4m_writer.take() {
+            y4m_writer.finish()?;
+        }
+
+        self.finish_hotkey_recording()
+    }
+}
+
+impl Default for VideoCapture {
+    fn default() -> VideoCapture {
+        VideoCapture::new()
+    }
+}
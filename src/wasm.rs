@@ -0,0 +1,133 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    Chip8,
+    Keyboard
+};
+
+/// this is the chip8 as javascript sees it in the wasm build
+///
+/// the page owns the timing, it calls step_frame 60 times a second (requestAnimationFrame is close enough)
+/// and then reads the framebuffer straight out of the wasm memory to draw it. see examples/web for a page that does this
+#[wasm_bindgen]
+pub struct WasmChip8 {
+    chip8 : Chip8,
+
+    // how many instructions are run every frame
+    instructions_per_frame : u32,
+
+    // one bit for every chip8 key that is held down
+    held_keys : u16,
+
+    // the last key that was pressed while it is still held, this is the key the chip8 sees
+    last_key : Option<u8>,
+
+    // one byte for every pixel, 1 for on and 0 for off, row by row
+    framebuffer : Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl WasmChip8 {
+    /// this fn makes a chip8 that runs at hertz instructions a second with its rng seeded with seed
+    #[wasm_bindgen(constructor)]
+    pub fn new(hertz : u32, seed : u32) -> WasmChip8 {
+        let chip8 = Chip8::with_seed(seed as u64);
+        let framebuffer = vec![0; chip8.display_buffer.len() * chip8.display_buffer[0].len()];
+
+        WasmChip8 {
+            chip8,
+            instructions_per_frame : (hertz as f64 / 60_f64).round() as u32,
+            held_keys : 0,
+            last_key : None,
+            framebuffer,
+        }
+    }
+
+    /// this fn resets the chip8 and loads a rom from its bytes, EX : new Uint8Array(await response.arrayBuffer())
+    #[wasm_bindgen(js_name = loadRom)]
+    pub fn load_rom(&mut self, rom : &[u8]) -> Result<(), JsValue> {
+        let mut chip8 = Chip8::with_seed(self.chip8.seed);
+
+        chip8.load_rom(rom).map_err(|error| JsValue::from_str(&error))?;
+        chip8.load_font();
+
+        self.chip8 = chip8;
+        self.update_framebuffer();
+
+        Ok(())
+    }
+
+    /// this fn runs one 60th of a second of the chip8 and gives back false once it has run out of memory
+    #[wasm_bindgen(js_name = stepFrame)]
+    pub fn step_frame(&mut self) -> bool {
+        let keyboard = match self.last_key {
+            Some(key) => Keyboard::from_keycode(key),
+            None => Keyboard::None
+        };
+
+        for _ in 0..self.instructions_per_frame {
+            if !self.chip8.processor_frame(keyboard) {
+                return false
+            }
+        }
+
+        self.chip8.update_timers();
+        self.update_framebuffer();
+
+        true
+    }
+
+    /// this fn presses or lets go of a chip8 key from 0x0 to 0xF, other keys are ignored
+    #[wasm_bindgen(js_name = setKey)]
+    pub fn set_key(&mut self, key : u8, pressed : bool) {
+        if key > 0xF {
+            return
+        }
+
+        if pressed {
+            self.held_keys |= 1 << key;
+            self.last_key = Some(key);
+        } else {
+            self.held_keys &= !(1 << key);
+
+            // the chip8 only sees one key at a time so letting go falls back to another key that is still held
+            if self.last_key == Some(key) {
+                self.last_key = (0..=0xF).find(|key| self.held_keys & (1 << key) != 0);
+            }
+        }
+    }
+
+    /// this fn gives back where the framebuffer is in the wasm memory
+    ///
+    /// the framebuffer is framebufferWidth * framebufferHeight bytes, 1 for on and 0 for off
+    #[wasm_bindgen(js_name = framebufferPtr)]
+    pub fn framebuffer_ptr(&self) -> *const u8 {
+        self.framebuffer.as_ptr()
+    }
+
+    #[wasm_bindgen(js_name = framebufferWidth)]
+    pub fn framebuffer_width(&self) -> usize {
+        self.chip8.display_buffer[0].len()
+    }
+
+    #[wasm_bindgen(js_name = framebufferHeight)]
+    pub fn framebuffer_height(&self) -> usize {
+        self.chip8.display_buffer.len()
+    }
+
+    /// this fn gives back the sound timer, the buzzer should be on while it isn't 0
+    #[wasm_bindgen(js_name = soundTimer)]
+    pub fn sound_timer(&self) -> u8 {
+        self.chip8.sound_timer_register
+    }
+}
+
+impl WasmChip8 {
+    fn update_framebuffer(&mut self) {
+        let pixels = self.chip8.display_buffer.iter().flatten();
+
+        for (byte, pixel) in self.framebuffer.iter_mut().zip(pixels) {
+            *byte = *pixel as u8;
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use crate::Chip8;
+
+/// these are the ways an instruction can touch memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryAccess {
+    // DXYN reading a sprite and FX65 loading registers
+    Read,
+
+    // FX33 and FX55 storing into memory
+    Write,
+
+    // the instruction about to run
+    Execute,
+}
+
+/// this is a range of memory to watch, touching it in one of the ways it watches for pauses the chip8
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Watchpoint {
+    // the first and last address watched, both are included
+    pub start : usize,
+    pub end : usize,
+
+    pub read : bool,
+    pub write : bool,
+    pub execute : bool,
+}
+
+impl Watchpoint {
+    pub fn new(start : usize, end : usize, read : bool, write : bool, execute : bool) -> Watchpoint {
+        Watchpoint { start, end, read, write, execute }
+    }
+
+    /// this fn checks if an access to an address is one this watchpoint is watching for
+    pub fn watches(&self, address : usize, access : MemoryAccess) -> bool {
+        let watched = match access {
+            MemoryAccess::Read => self.read,
+            MemoryAccess::Write => self.write,
+            MemoryAccess::Execute => self.execute,
+        };
+
+        watched && (self.start..=self.end).contains(&address)
+    }
+}
+
+/// this is what happened when a watchpoint was hit
+///
+/// reads and executes leave memory alone so their old and new values are the same
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchHit {
+    pub access : MemoryAccess,
+
+    // the address of the instruction that touched the memory
+    pub pc : u16,
+
+    pub address : usize,
+    pub old_value : u8,
+    pub new_value : u8,
+}
+
+impl Chip8 {
+    /// this fn reads a byte of memory for an instruction, every read an instruction does goes through here
+    pub fn read_memory(&mut self, address : usize) -> u8 {
+        let value = self.memory[address];
+        self.check_watchpoints(address, MemoryAccess::Read, value, value);
+        self.mark_coverage(address, MemoryAccess::Read);
+        value
+    }
+
+    /// this fn writes a byte of memory for an instruction, every write an instruction does goes through here
+    pub fn write_memory(&mut self, address : usize, value : u8) {
+        let old_value = self.memory[address];
+        self.memory[address] = value;
+        self.check_watchpoints(address, MemoryAccess::Write, old_value, value);
+        self.mark_coverage(address, MemoryAccess::Write);
+    }
+
+    /// this fn fetches the instruction at the program counter the same as fetch but it counts as executing it
+    pub fn fetch_instruction(&mut self) -> u16 {
+        // coverage counts the instruction once at the address it starts at
+        self.mark_coverage(self.pc_reg as usize, MemoryAccess::Execute);
+
+        self.fetch()
+    }
+
+    /// this fn checks the execute watchpoints before the instruction at the program counter runs
+    ///
+    /// it gives back true if one was hit, then the instruction doesn't run so the chip8 pauses with the pc still on it.
+    /// it runs without being checked again once the chip8 is resumed
+    pub fn check_execute_watchpoints(&mut self) -> bool {
+        if std::mem::take(&mut self.resuming_execute) {
+            return false
+        }
+
+        let pc = self.pc_reg as usize;
+        for address in pc..pc + 2 {
+            let value = self.memory[address];
+            self.check_watchpoints(address, MemoryAccess::Execute, value, value);
+        }
+
+        self.hit_watchpoint()
+    }
+
+    /// this fn gives back true if a watchpoint was hit, the chip8 doesn't run any more instructions until it is resumed
+    pub fn hit_watchpoint(&self) -> bool {
+        !self.watch_hits.is_empty()
+    }
+
+    /// this fn clears the watchpoints that were hit so the chip8 can keep going, it gives them back to be reported
+    pub fn resume_from_watchpoints(&mut self) -> Vec<WatchHit> {
+        if self.watch_hits.iter().any(|hit| hit.access == MemoryAccess::Execute) {
+            self.resuming_execute = true;
+        }
+
+        std::mem::take(&mut self.watch_hits)
+    }
+
+    fn mark_coverage(&mut self, address : usize, access : MemoryAccess) {
+        if let Some(coverage) = &mut self.coverage {
+            coverage.mark(address, access);
+        }
+    }
+
+    fn check_watchpoints(&mut self, address : usize, access : MemoryAccess, old_value : u8, new_value : u8) {
+        // this is on every memory access so don't do anything else when nothing is being watched
+        if self.watchpoints.is_empty() {
+            return
+        }
+
+        if self.watchpoints.iter().any(|watchpoint| watchpoint.watches(address, access)) {
+            self.watch_hits.push(WatchHit { access, pc : self.pc_reg, address, old_value, new_value });
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::{
+    fs::File,
+    io::{
+        BufWriter,
+        Seek,
+        SeekFrom,
+        Write
+    },
+};
+
+use crate::{
+    AudioConfig,
+    ToneGenerator
+};
+
+/// this renders the buzzer to a wav file from the sound timer instead of playing it on a sound card
+///
+/// the sound timer is given once per frame, the same way the emulator loop gives it to the audio callback,
+/// so the wav file sounds exactly like a real run would. rip8 doesn't emulate xo-chip audio patterns
+/// so only the buzzer ends up in the file
+///
+/// the samples are written as they are made so long runs don't have to fit in memory,
+/// the sizes in the header get filled in by finish, or when the writer is dropped if the run stopped with an error
+pub struct WavWriter {
+    writer : BufWriter<File>,
+    tone : ToneGenerator,
+    sample_rate : u32,
+
+    // the part of a sample left over from the last frame when the sample rate doesn't divide by 60
+    leftover_samples : f64,
+
+    samples_written : u32,
+
+    // if finish already filled in the header so dropping the writer doesn't do it again
+    finished : bool,
+}
+
+impl WavWriter {
+    const HEADER_SIZE : u32 = 44;
+    const BITS_PER_SAMPLE : u16 = 16;
+
+    pub fn new(file_path : &String, audio_config : AudioConfig, sample_rate : u32) -> Result<WavWriter, String> {
+        let file_handle = match File::create(file_path) {
+            Ok(file) => file,
+            _ => return Err(format!("error : could not create wav file '{}'!", file_path))
+        };
+
+        let mut wav_writer = WavWriter {
+            writer : BufWriter::new(file_handle),
+            tone : ToneGenerator::new(audio_config, sample_rate),
+            sample_rate,
+            leftover_samples : 0.0,
+            samples_written : 0,
+            finished : false,
+        };
+
+        // the header is written with sizes of 0 here and written again once the sizes are known
+        wav_writer.write_header()?;
+
+        Ok(wav_writer)
+    }
+
+    /// this fn renders one frame of audio for the current value of the sound timer
+    pub fn push_frame(&mut self, sound_timer : u8) -> Result<(), String> {
+        self.tone.set_sound_timer(sound_timer);
+
+        let samples = self.sample_rate as f64 / 60.0 + self.leftover_samples;
+        self.leftover_samples = samples.fract();
+
+        for _ in 0..samples as u32 {
+            let sample = (self.tone.next_sample().clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+            self.write(&sample.to_le_bytes())?;
+        }
+
+        self.samples_written += samples as u32;
+
+        Ok(())
+    }
+
+    /// this fn fills in the header and flushes the file
+    pub fn finish(mut self) -> Result<(), String> {
+        self.finished = true;
+        self.fill_in_header()
+    }
+
+    fn fill_in_header(&mut self) -> Result<(), String> {
+        match self.writer.seek(SeekFrom::Start(0)) {
+            Ok(_) => {},
+            _ => return Err(String::from("error : could not write to wav file!"))
+        }
+
+        self.write_header()?;
+
+        match self.writer.flush() {
+            Ok(_) => Ok(()),
+            _ => Err(String::from("error : could not write to wav file!"))
+        }
+    }
+
+    fn write_header(&mut self) -> Result<(), String> {
+        let bytes_per_sample = Self::BITS_PER_SAMPLE as u32 / 8;
+        let data_size = self.samples_written * bytes_per_sample;
+
+        let mut header : Vec<u8> = Vec::with_capacity(Self::HEADER_SIZE as usize);
+        header.extend(b"RIFF");
+        header.extend((Self::HEADER_SIZE - 8 + data_size).to_le_bytes());
+        header.extend(b"WAVE");
+
+        // format chunk for mono 16 bit pcm
+        header.extend(b"fmt ");
+        header.extend(16_u32.to_le_bytes());
+        header.extend(1_u16.to_le_bytes());
+        header.extend(1_u16.to_le_bytes());
+        header.extend(self.sample_rate.to_le_bytes());
+        header.extend((self.sample_rate * bytes_per_sample).to_le_bytes());
+        header.extend((bytes_per_sample as u16).to_le_bytes());
+        header.extend(Self::BITS_PER_SAMPLE.to_le_bytes());
+
+        header.extend(b"data");
+        header.extend(data_size.to_le_bytes());
+
+        self.write(&header)
+    }
+
+    fn write(&mut self, bytes : &[u8]) -> Result<(), String> {
+        match self.writer.write_all(bytes) {
+            Ok(_) => Ok(()),
+            _ => Err(String::from("error : could not write to wav file!"))
+        }
+    }
+}
+
+impl Drop for WavWriter {
+    /// this fills in the header when the emulator stopped with an error before finish was called
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.fill_in_header();
+        }
+    }
+}
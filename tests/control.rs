@@ -0,0 +1,172 @@
+// these drive the control server from a client on localhost while the test plays the emulation loop, answering requests
+// between "frames" the same way the emulator does
+
+use std::{
+    io::{ BufRead, BufReader, Write },
+    net::{ TcpListener, TcpStream },
+    thread,
+    time::Duration,
+};
+
+use serde_json::{ json, Value };
+
+use rip8::{ Chip8, ControlServer, ControlStatus, Palette };
+
+// v0 := 1, v1 := 2, then jump to the jump forever
+const ROM : [u8; 6] = [0x60, 0x01, 0x61, 0x02, 0x12, 0x04];
+
+/// this fn finds a port nothing is listening on by letting the os pick one
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+struct Client {
+    reader : BufReader<TcpStream>,
+    writer : TcpStream,
+    id : u64,
+}
+
+impl Client {
+    fn connect(port : u16) -> Client {
+        let writer = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        writer.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+        Client { reader : BufReader::new(writer.try_clone().unwrap()), writer, id : 0 }
+    }
+
+    /// this fn sends one line as it is and gives back the reply
+    fn send_line(&mut self, line : &str) -> Value {
+        writeln!(self.writer, "{}", line).unwrap();
+
+        let mut reply = String::new();
+        self.reader.read_line(&mut reply).unwrap();
+        serde_json::from_str(&reply).unwrap()
+    }
+
+    /// this fn calls a method and gives back the whole reply, which has either a result or an error
+    fn call(&mut self, method : &str, params : Value) -> Value {
+        self.id += 1;
+        let reply = self.send_line(&json!({ "jsonrpc" : "2.0", "id" : self.id, "method" : method, "params" : params }).to_string());
+        assert_eq!(reply["id"], json!(self.id));
+        reply
+    }
+
+    fn result(&mut self, method : &str, params : Value) -> Value {
+        let reply = self.call(method, params);
+        assert!(reply.get("error").is_none(), "{} failed : {}", method, reply);
+        reply["result"].clone()
+    }
+
+    fn error_code(&mut self, method : &str, params : Value) -> i64 {
+        let reply = self.call(method, params);
+        reply["error"]["code"].as_i64().unwrap_or_else(|| panic!("{} should have failed : {}", method, reply))
+    }
+}
+
+/// this fn runs the client's requests against the server while answering them the way the emulation loop would
+fn serve(client : impl FnOnce(u16) + Send + 'static) -> Chip8 {
+    let mut chip8 = Chip8::with_seed(1);
+    chip8.load_rom(&ROM).unwrap();
+
+    let port = free_port();
+    let mut server = ControlServer::start(port).unwrap();
+    let status = ControlStatus { frame : 0, paused : true, palette : Palette::new() };
+
+    let client = thread::spawn(move || client(port));
+    while !client.is_finished() {
+        server.handle_requests(&mut chip8, status);
+        thread::sleep(Duration::from_millis(1));
+    }
+    client.join().unwrap();
+
+    chip8
+}
+
+#[test]
+fn memory_and_registers() {
+    let chip8 = serve(|port| {
+        let mut client = Client::connect(port);
+
+        assert_eq!(client.result("write_memory", json!({ "address" : 0x300, "bytes" : [1, 2, 3] })), json!(true));
+        assert_eq!(client.result("read_memory", json!({ "address" : 0x300, "length" : 3 })), json!({ "bytes" : [1, 2, 3] }));
+
+        let registers = client.result("registers", Value::Null);
+        assert_eq!(registers["pc"], json!(0x200));
+        assert_eq!(registers["stack"], json!([]));
+
+        assert_eq!(client.result("step", json!({ "instructions" : 2 })), json!(true));
+        let registers = client.result("registers", Value::Null);
+        assert_eq!(registers["pc"], json!(0x204));
+        assert_eq!((&registers["v"][0], &registers["v"][1]), (&json!(1), &json!(2)));
+    });
+
+    assert_eq!(&chip8.memory[0x300..0x303], &[1, 2, 3]);
+}
+
+#[test]
+fn save_and_load_state() {
+    serve(|port| {
+        let mut client = Client::connect(port);
+
+        client.result("write_memory", json!({ "address" : 0x300, "bytes" : [7] }));
+        let state = client.result("save_state", Value::Null)["state"].clone();
+
+        client.result("write_memory", json!({ "address" : 0x300, "bytes" : [9] }));
+        client.result("step", json!({ "instructions" : 1 }));
+        assert_eq!(client.result("load_state", json!({ "state" : state })), json!(true));
+
+        assert_eq!(client.result("read_memory", json!({ "address" : 0x300 })), json!({ "bytes" : [7] }));
+        assert_eq!(client.result("registers", Value::Null)["pc"], json!(0x200));
+    });
+}
+
+#[test]
+fn errors() {
+    serve(|port| {
+        let mut client = Client::connect(port);
+
+        // json-rpc's codes, broken json can't be matched to a request so it has no id
+        let reply = client.send_line("{ this isn't json");
+        assert_eq!(reply["error"]["code"], json!(-32700));
+        assert_eq!(reply["id"], Value::Null);
+
+        let reply = client.send_line(r#"{"jsonrpc" : "2.0", "id" : 7}"#);
+        assert_eq!(reply["error"]["code"], json!(-32600));
+        assert_eq!(reply["id"], json!(7));
+
+        assert_eq!(client.error_code("fly", Value::Null), -32601);
+
+        assert_eq!(client.error_code("read_memory", Value::Null), -32602);
+        assert_eq!(client.error_code("read_memory", json!({ "address" : 0xFFF, "length" : 2 })), -32602);
+        assert_eq!(client.error_code("read_memory", json!({ "address" : u64::MAX, "length" : 2 })), -32602);
+        assert_eq!(client.error_code("write_memory", json!({ "address" : 0x300, "bytes" : [256] })), -32602);
+        assert_eq!(client.error_code("write_memory", json!({ "address" : u64::MAX, "bytes" : [1] })), -32602);
+        assert_eq!(client.error_code("load_state", json!({ "state" : { "pc" : "nowhere" } })), -32602);
+        assert_eq!(client.error_code("load_state", Value::Null), -32602);
+
+        // a state has to be for the real display and keep its registers in range, anything else is turned away
+        let state = client.result("save_state", Value::Null)["state"].clone();
+        let mut small_display = state.clone();
+        small_display["display_width"] = json!(8);
+        small_display["display_height"] = json!(1);
+        small_display["display"] = json!("00");
+        assert_eq!(client.error_code("load_state", json!({ "state" : small_display })), -32602);
+
+        let mut pc_outside = state.clone();
+        pc_outside["pc"] = json!(0x1000);
+        assert_eq!(client.error_code("load_state", json!({ "state" : pc_outside })), -32602);
+
+        let mut i_too_big = state.clone();
+        i_too_big["i"] = json!(0x10000);
+        assert_eq!(client.error_code("load_state", json!({ "state" : i_too_big })), -32602);
+
+        // a step that would hold up drawing for too long is turned away instead of run
+        let too_many = ControlServer::MAX_STEP_INSTRUCTIONS + 1;
+        assert_eq!(client.error_code("step", json!({ "instructions" : too_many })), -32602);
+        assert_eq!(client.error_code("step", json!({ "frames" : u64::MAX })), -32602);
+        assert_eq!(client.error_code("step", json!({ "frames" : ControlServer::MAX_STEP_FRAMES + 1 })), -32602);
+        assert_eq!(client.result("registers", Value::Null)["pc"], json!(0x200));
+
+        // the connection still works after all of that
+        assert_eq!(client.result("status", Value::Null), json!({ "frame" : 0, "paused" : true }));
+    });
+}
@@ -0,0 +1,277 @@
+// these run rip8 in lockstep with the reference chip8 in tests/reference for every quirk profile, comparing the
+// registers, memory and display after every instruction. the first instruction they disagree on fails the test with
+// a trace of the instructions leading up to it
+//
+// RIP8_DIFFERENTIAL_PROGRAMS sets how many random programs are run, EX : RIP8_DIFFERENTIAL_PROGRAMS=20000 cargo test
+
+mod reference;
+
+use std::{
+    collections::VecDeque,
+    fs
+};
+
+use rip8::{
+    Chip8,
+    Keyboard,
+    Quirks,
+    disassembler,
+    rom
+};
+
+use reference::{
+    Reference,
+    SCREEN_HEIGHT,
+    SCREEN_WIDTH
+};
+
+const SEED : u64 = 0x5eed;
+
+// 700hz at 60 frames a second like the emulator runs by default
+const INSTRUCTIONS_PER_FRAME : usize = 12;
+
+// how many instructions the trace shows before the one that diverged
+const TRACE_LENGTH : usize = 16;
+
+const PROFILES : [&str; 4] = ["rip8", "chip8", "schip", "octo"];
+
+/// this runs a rom on rip8 and the reference until one of them runs off the end of memory or the instructions run out
+///
+/// the key held on each frame comes from keys, none is no key
+fn run_lockstep(rom : &[u8], quirks : Quirks, instructions : usize, keys : impl Fn(usize) -> Option<u8>) -> Result<(), String> {
+    let mut chip8 = Chip8::with_seed(SEED);
+    chip8.quirks = quirks;
+    chip8.load_font();
+    chip8.load_rom(rom)?;
+
+    let mut reference = Reference::new(rom, quirks, SEED);
+    let mut trace = VecDeque::with_capacity(TRACE_LENGTH);
+
+    for step in 0..instructions {
+        let frame = step / INSTRUCTIONS_PER_FRAME;
+        let key = keys(frame);
+        let keyboard = key.map_or(Keyboard::None, Keyboard::from_keycode);
+
+        if trace.len() == TRACE_LENGTH {
+            trace.pop_front();
+        }
+        trace.push_back(TraceLine::new(&reference, step, frame, key));
+
+        let running = (chip8.processor_frame(keyboard), reference.step(key));
+
+        let differences = differences(&chip8, &reference);
+        if !differences.is_empty() || running.0 != running.1 {
+            return Err(format!(
+                "diverged on instruction {} of frame {}\n{}\nrip8 running : {}, reference running : {}\n\nlast instructions :\n{}",
+                step, frame, differences.join("\n"), running.0, running.1, trace.iter().map(TraceLine::text).collect::<Vec<String>>().join("\n")
+            ))
+        }
+
+        if !running.0 {
+            break
+        }
+
+        if (step + 1) % INSTRUCTIONS_PER_FRAME == 0 {
+            chip8.update_timers();
+            reference.update_timers();
+        }
+    }
+
+    Ok(())
+}
+
+/// this is the state right before an instruction, it is only turned into text if the run diverges
+struct TraceLine {
+    step : usize,
+    frame : usize,
+    key : Option<u8>,
+    pc : u16,
+    instruction : Option<u16>,
+    i : u16,
+    v : [u8; 16],
+}
+
+impl TraceLine {
+    fn new(reference : &Reference, step : usize, frame : usize, key : Option<u8>) -> TraceLine {
+        let instruction = if reference.pc as usize + 1 < reference.memory.len() { Some(reference.fetch()) } else { None };
+        TraceLine { step, frame, key, pc : reference.pc, instruction, i : reference.i, v : reference.v }
+    }
+
+    fn text(&self) -> String {
+        let instruction = match self.instruction {
+            Some(instruction) => format!("{:04X}  {:<18}", instruction, disassembler::disassemble(instruction)),
+            None => format!("----  {:<18}", "past memory")
+        };
+
+        format!(
+            "  {:>7} {:>5}  {:03X}  {} key {}  I {:03X}  V {}",
+            self.step, self.frame, self.pc, instruction, self.key.map_or(String::from("-"), |key| format!("{:X}", key)), self.i,
+            self.v.iter().map(|v| format!("{:02X}", v)).collect::<Vec<String>>().join(" ")
+        )
+    }
+}
+
+/// this fn lists everything that is different between rip8 and the reference, rip8 first
+fn differences(chip8 : &Chip8, reference : &Reference) -> Vec<String> {
+    let mut differences = Vec::new();
+
+    // this runs after every instruction so the usual case of nothing being different is checked without making any text
+    let same = chip8.pc_reg == reference.pc
+        && chip8.index_reg == reference.i
+        && chip8.v_regs == reference.v
+        && chip8.stack == reference.stack
+        && chip8.delay_timer_register == reference.delay_timer
+        && chip8.sound_timer_register == reference.sound_timer
+        && chip8.waiting_for_vblank == reference.waiting_for_vblank
+        && chip8.memory == reference.memory
+        && chip8.display_buffer.iter().zip(reference.display.iter()).all(|(rip8, reference)| rip8 == reference);
+
+    if same {
+        return differences
+    }
+
+    let mut compare = |name : &str, rip8 : String, reference : String| {
+        if rip8 != reference {
+            differences.push(format!("{} : rip8 {} reference {}", name, rip8, reference));
+        }
+    };
+
+    compare("pc", format!("{:03X}", chip8.pc_reg), format!("{:03X}", reference.pc));
+    compare("I", format!("{:03X}", chip8.index_reg), format!("{:03X}", reference.i));
+    for register in 0..16 {
+        compare(&format!("V{:X}", register), format!("{:02X}", chip8.v_regs[register]), format!("{:02X}", reference.v[register]));
+    }
+    compare("stack", format!("{:03X?}", chip8.stack), format!("{:03X?}", reference.stack));
+    compare("delay timer", chip8.delay_timer_register.to_string(), reference.delay_timer.to_string());
+    compare("sound timer", chip8.sound_timer_register.to_string(), reference.sound_timer.to_string());
+    compare("waiting for vblank", chip8.waiting_for_vblank.to_string(), reference.waiting_for_vblank.to_string());
+
+    for (address, (rip8, reference)) in chip8.memory.iter().zip(reference.memory.iter()).enumerate() {
+        compare(&format!("memory {:03X}", address), format!("{:02X}", rip8), format!("{:02X}", reference));
+    }
+
+    for y in 0..SCREEN_HEIGHT {
+        for x in 0..SCREEN_WIDTH {
+            compare(&format!("pixel {},{}", x, y), chip8.display_buffer[y][x].to_string(), reference.display[y][x].to_string());
+        }
+    }
+
+    differences
+}
+
+/// this fn runs a rom with every quirk profile and panics with the trace of the first divergence
+fn check_rom(name : &str, rom : &[u8], instructions : usize, keys : impl Fn(usize) -> Option<u8> + Copy) {
+    for profile in PROFILES {
+        let quirks = Quirks::from_name(profile).unwrap();
+        if let Err(error) = run_lockstep(rom, quirks, instructions, keys) {
+            panic!("{} with the {} quirks : {}", name, profile, error);
+        }
+    }
+}
+
+#[test]
+fn roms_match_reference() {
+    let roms = fs::read_dir("testroms").unwrap()
+        .chain(fs::read_dir("fullgames").unwrap())
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+
+    for rom_path in roms {
+        let name = rom_path.display().to_string();
+        let rom = rom::read_rom(&name).unwrap();
+
+        // every key gets held for a few frames in turn with no key in between, so key waits and key checks both run
+        check_rom(&name, &rom, 20_000, |frame| match (frame / 8) % 20 {
+            key if key < 16 => Some(key as u8),
+            _ => None
+        });
+    }
+}
+
+/// a small xorshift so the random programs are the same on every run without another dependency
+struct Xorshift(u64);
+
+impl Xorshift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, limit : u64) -> u64 {
+        self.next() % limit
+    }
+}
+
+/// this fn makes a program out of random instructions, mostly ones the chip8 knows so it doesn't just stop at the
+/// first one it doesn't. jumps and calls mostly land inside the program and I lands anywhere, up to the end of memory
+fn random_program(rng : &mut Xorshift) -> Vec<u8> {
+    const PATTERNS : [u16; 34] = [
+        0x00E0, 0x00EE, 0x1000, 0x2000, 0x3000, 0x4000, 0x5000, 0x6000, 0x7000, 0x8000, 0x8001, 0x8002, 0x8003, 0x8004,
+        0x8005, 0x8006, 0x8007, 0x800E, 0x9000, 0xA000, 0xB000, 0xC000, 0xD000, 0xE09E, 0xE0A1, 0xF007, 0xF00A, 0xF015,
+        0xF018, 0xF01E, 0xF029, 0xF033, 0xF055, 0xF065,
+    ];
+
+    let length = 16 + rng.below(240) as u16;
+    let mut program = Vec::new();
+
+    for _ in 0..length {
+        let pattern = PATTERNS[rng.below(PATTERNS.len() as u64) as usize];
+        let inside_program = 0x200 + rng.below(length as u64) as u16 * 2;
+
+        let instruction = match pattern {
+            // a few of everything is left completely random so the instructions the chip8 doesn't know come up too
+            _ if rng.below(64) == 0 => rng.next() as u16,
+            0x00E0 | 0x00EE => pattern,
+            0x1000 | 0x2000 if rng.below(8) != 0 => pattern | inside_program,
+            0xB000 => pattern | inside_program,
+            _ if pattern >> 12 == 0x8 => pattern | (rng.below(0x100) as u16) << 4,
+            _ if pattern >> 12 == 0xE || pattern >> 12 == 0xF => pattern | (rng.below(0x10) as u16) << 8,
+            _ => pattern | rng.below(0x1000) as u16
+        };
+
+        program.extend(instruction.to_be_bytes());
+    }
+
+    program
+}
+
+#[test]
+fn random_programs_match_reference() {
+    let programs = std::env::var("RIP8_DIFFERENTIAL_PROGRAMS").ok()
+        .and_then(|programs| programs.parse::<u64>().ok())
+        .unwrap_or(300);
+
+    let mut rng = Xorshift(0x9E37_79B9_7F4A_7C15);
+
+    for number in 0..programs {
+        let program = random_program(&mut rng);
+        let hex = program.iter().map(|byte| format!("{:02X}", byte)).collect::<String>();
+        let key_seed = rng.next();
+
+        // the key changes every couple of frames and is sometimes no key
+        check_rom(&format!("random program {} ({})", number, hex), &program, 3_000, move |frame| {
+            match (key_seed >> ((frame / 2) % 48)) & 0x1F {
+                key if key < 16 => Some(key as u8),
+                _ => None
+            }
+        });
+    }
+}
+
+#[test]
+fn random_bytes_cover_every_value() {
+    // CXFF keeps all of the random byte so 255 has to come up as often as any other value
+    let mut chip8 = Chip8::with_seed(SEED);
+    chip8.load_rom(&[0xC0, 0xFF, 0x12, 0x00]).unwrap();
+
+    let mut seen = [false; 256];
+    for _ in 0..20_000 {
+        chip8.processor_frame(Keyboard::None);
+        seen[chip8.v_regs[0] as usize] = true;
+    }
+
+    let missing = (0..256).filter(|value| !seen[*value]).collect::<Vec<_>>();
+    assert!(missing.is_empty(), "CXFF never gave back {:?}", missing);
+}
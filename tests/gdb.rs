@@ -0,0 +1,131 @@
+// these attach to the gdb stub over localhost the same way gdb would and check its replies, a rom runs on the other
+// end of the connection so breakpoints and watchpoints stop it for real
+
+use std::{
+    io::{ Read, Write },
+    net::{ TcpListener, TcpStream },
+    thread,
+    time::Duration,
+};
+
+use rip8::{ Chip8, GdbStub, Keyboard, Watchpoint };
+
+// v0 := 1, v1 := 2, i := 0x300, save v1, then jump to the jump forever
+const ROM : [u8; 10] = [0x60, 0x01, 0x61, 0x02, 0xA3, 0x00, 0xF1, 0x55, 0x12, 0x08];
+
+/// this fn finds a port nothing is listening on by letting the os pick one
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+/// this fn runs the rom with the stub attached the same way the emulator loop does until gdb kills it
+fn run_stub(port : u16) {
+    let mut chip8 = Chip8::with_seed(1);
+    chip8.load_rom(&ROM).unwrap();
+
+    // stops before the jump runs
+    chip8.watchpoints.push(Watchpoint::new(0x208, 0x208, false, false, true));
+
+    let mut gdb = GdbStub::listen(port).unwrap();
+    while gdb.before_instruction(&mut chip8).unwrap() {
+        chip8.processor_frame(Keyboard::None);
+    }
+}
+
+/// this fn connects to the stub, it can take a moment to start listening
+fn connect(port : u16) -> TcpStream {
+    for _ in 0..100 {
+        if let Ok(stream) = TcpStream::connect(("127.0.0.1", port)) {
+            stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+            return stream
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    panic!("the gdb stub never started listening on port {}", port)
+}
+
+fn read_byte(stream : &mut TcpStream) -> u8 {
+    let mut byte = [0];
+    stream.read_exact(&mut byte).unwrap();
+    byte[0]
+}
+
+/// this fn sends a packet and waits for the stub to acknowledge it
+fn send(stream : &mut TcpStream, data : &str) {
+    let checksum = data.bytes().fold(0u8, |sum, byte| sum.wrapping_add(byte));
+    stream.write_all(format!("${}#{:02x}", data, checksum).as_bytes()).unwrap();
+    assert_eq!(read_byte(stream), b'+', "the stub didn't acknowledge {}", data);
+}
+
+/// this fn reads the next reply, checks its checksum and acknowledges it
+fn receive(stream : &mut TcpStream) -> String {
+    while read_byte(stream) != b'$' {}
+
+    let mut data = Vec::new();
+    loop {
+        match read_byte(stream) {
+            b'#' => break,
+            byte => data.push(byte)
+        }
+    }
+
+    let checksum = [read_byte(stream), read_byte(stream)];
+    let checksum = u8::from_str_radix(std::str::from_utf8(&checksum).unwrap(), 16).unwrap();
+    assert_eq!(checksum, data.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)));
+
+    stream.write_all(b"+").unwrap();
+    String::from_utf8(data).unwrap()
+}
+
+fn request(stream : &mut TcpStream, data : &str) -> String {
+    send(stream, data);
+    receive(stream)
+}
+
+/// this fn gives back the pc out of the registers, it is little endian after v0 to vf and i
+fn pc(stream : &mut TcpStream) -> u16 {
+    let registers = request(stream, "g");
+    u16::from_str_radix(&format!("{}{}", &registers[38..40], &registers[36..38]), 16).unwrap()
+}
+
+#[test]
+fn gdb_session() {
+    let port = free_port();
+    let stub = thread::spawn(move || run_stub(port));
+    let mut stream = connect(port);
+
+    // the chip8 starts halted before the first instruction
+    assert_eq!(request(&mut stream, "?"), "S05");
+    let registers = request(&mut stream, "g");
+    assert_eq!(registers.len(), 46);
+    assert_eq!(&registers[36..40], "0002");
+    assert_eq!(request(&mut stream, "m200,4"), "60016102");
+
+    // broken packets get an error instead of taking the stub down
+    assert_eq!(request(&mut stream, "mffffffffffffffff,2"), "E01");
+    assert_eq!(request(&mut stream, "Mffffffffffffffff,1:00"), "E01");
+    assert_eq!(request(&mut stream, "Z2,300,ffffffffffffffff"), "E01");
+    assert_eq!(request(&mut stream, "m200"), "E01");
+    assert_eq!(request(&mut stream, "P12=ff"), "E01");
+    assert_eq!(request(&mut stream, "vUnknownPacket"), "");
+
+    // a breakpoint stops before its instruction runs
+    assert_eq!(request(&mut stream, "Z0,204,2"), "OK");
+    assert_eq!(request(&mut stream, "c"), "S05");
+    assert_eq!(pc(&mut stream), 0x204);
+    assert!(request(&mut stream, "g").starts_with("0102"));
+
+    // a write watchpoint stops after the save and says where it wrote
+    assert_eq!(request(&mut stream, "z0,204,2"), "OK");
+    assert_eq!(request(&mut stream, "Z2,300,2"), "OK");
+    assert_eq!(request(&mut stream, "c"), "T05watch:300;");
+    assert_eq!(request(&mut stream, "m300,2"), "0102");
+
+    // the execute watchpoint on the jump is a breakpoint as far as gdb knows
+    assert_eq!(request(&mut stream, "c"), "T05hwbreak:;");
+    assert_eq!(pc(&mut stream), 0x208);
+
+    send(&mut stream, "k");
+    stub.join().unwrap();
+}
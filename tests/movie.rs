@@ -0,0 +1,81 @@
+// these record a short movie to a file and load it back to check playback only goes ahead with the same rom and config
+
+use std::fs;
+
+use rip8::{
+    Chip8,
+    Keyboard,
+    Movie,
+    MovieRecorder,
+    Quirks,
+    movie
+};
+
+const ROM : [u8; 4] = [0x70, 0x01, 0x12, 0x00];
+
+/// this fn gives back a path in the temp directory that no other test uses
+fn temp_path(name : &str) -> String {
+    std::env::temp_dir().join(format!("rip8_{}_{}.movie", name, std::process::id())).display().to_string()
+}
+
+/// this fn records a few frames of the rom with some quirks and loads the movie back
+fn record(name : &str, quirks : Quirks) -> Movie {
+    let path = temp_path(name);
+    let mut chip8 = Chip8::with_seed(7);
+    chip8.quirks = quirks;
+    chip8.load_rom(&ROM).unwrap();
+
+    let mut recorder = MovieRecorder::new(&path, movie::rom_hash(&ROM), chip8.seed, 700, quirks).unwrap();
+    for frame in 0..3 {
+        chip8.processor_frame(Keyboard::from_keycode(frame));
+        recorder.record_frame(Keyboard::from_keycode(frame), &chip8).unwrap();
+    }
+    drop(recorder);
+
+    let movie = Movie::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    movie
+}
+
+#[test]
+fn header_round_trips() {
+    let movie = record("header", Quirks::chip8());
+
+    assert_eq!(movie.rom_hash, movie::rom_hash(&ROM));
+    assert_eq!(movie.seed, 7);
+    assert_eq!(movie.hertz, 700);
+    assert_eq!(movie.quirks, Quirks::chip8());
+    assert_eq!(movie.frames.len(), 3);
+
+    assert_eq!(record("no_quirks", Quirks::octo()).quirks, Quirks::octo());
+}
+
+#[test]
+fn different_config_is_a_desync() {
+    let movie = record("config", Quirks::schip());
+    let rom_hash = movie::rom_hash(&ROM);
+
+    assert_eq!(movie.check_sync(rom_hash, 700, Quirks::schip()), Ok(()));
+    assert!(movie.check_sync(rom_hash + 1, 700, Quirks::schip()).unwrap_err().contains("rom hash"));
+    assert!(movie.check_sync(rom_hash, 1000, Quirks::schip()).unwrap_err().contains("1000 hz"));
+    assert_eq!(
+        movie.check_sync(rom_hash, 700, Quirks::chip8()),
+        Err(String::from("desync : movie was recorded with the quirks shift,load_store,jump,clip but is being played back with logic,clip,vblank"))
+    );
+}
+
+#[test]
+fn old_movies_ran_with_rip8_quirks() {
+    let path = temp_path("old");
+    fs::write(&path, "rip8-movie 1\nrom_hash 0\nseed 1\nhertz 700\nframes\n- 0\n").unwrap();
+    let movie = Movie::load(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(movie.unwrap().quirks, Quirks::new());
+
+    fs::write(&path, "rip8-movie 1\nquirks shift,warp\nframes\n").unwrap();
+    let movie = Movie::load(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(movie.err(), Some(String::from("error : bad quirks in movie 'shift,warp'!")));
+}
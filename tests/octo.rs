@@ -0,0 +1,181 @@
+// these assemble small octo programs and compare the bytes with the instructions they should turn into, then check
+// that broken programs fail with an error that points at the right line
+
+use rip8::octo::assemble;
+
+/// this fn assembles a program that should assemble and checks its bytes
+fn check(source : &str, expected : &[u8]) {
+    match assemble(source) {
+        Ok(bytes) => assert_eq!(bytes, expected, "wrong bytes for :\n{}", source),
+        Err(error) => panic!("{} for :\n{}", error, source)
+    }
+}
+
+/// this fn assembles a program that shouldn't assemble and checks the error is the one expected
+fn check_error(source : &str, expected : &str) {
+    match assemble(source) {
+        Ok(bytes) => panic!("expected '{}' but it assembled to {:02X?} for :\n{}", expected, bytes, source),
+        Err(error) => assert_eq!(error, expected, "wrong error for :\n{}", source)
+    }
+}
+
+#[test]
+fn main_first_has_no_jump() {
+    check(
+        ": main
+            clear
+            v0 := 5
+            v1 += 2
+            loop again",
+        &[0x00, 0xE0, 0x60, 0x05, 0x71, 0x02, 0x12, 0x06]
+    );
+}
+
+#[test]
+fn labels_and_subroutines() {
+    // main isn't first so the program starts with a jump to it, face is used before it is defined
+    check(
+        ": draw-face
+            sprite v0 v1 2
+        ;
+        : main
+            i := face
+            draw-face
+            jump main
+        : face
+            0xFF 0b10000001",
+        &[0x12, 0x06, 0xD0, 0x12, 0x00, 0xEE, 0xA2, 0x0C, 0x22, 0x02, 0x12, 0x06, 0xFF, 0x81]
+    );
+}
+
+#[test]
+fn constants_calc_and_aliases() {
+    check(
+        ":const SPEED 3
+        :calc DOUBLE { SPEED * 2 }
+        :alias x v4
+        : main
+            v0 := SPEED
+            v1 := DOUBLE
+            x := 7
+            x += x
+            x -= v0
+            x <<= x",
+        &[0x60, 0x03, 0x61, 0x06, 0x64, 0x07, 0x84, 0x44, 0x84, 0x05, 0x84, 0x4E]
+    );
+}
+
+#[test]
+fn macros() {
+    check(
+        ":macro bump A B { A += 1 B += CALLS }
+        : main
+            bump v0 v1
+            bump v2 v3",
+        &[0x70, 0x01, 0x71, 0x00, 0x72, 0x01, 0x73, 0x01]
+    );
+}
+
+#[test]
+fn branches() {
+    // then skips the next statement when the condition is false, begin jumps over to the else or end
+    check(
+        ": main
+            if v0 == 3 then v1 := 1
+            if v2 != v3 begin
+                v4 := 4
+            else
+                v5 := 5
+            end",
+        &[0x40, 0x03, 0x61, 0x01, 0x92, 0x30, 0x12, 0x0C, 0x64, 0x04, 0x12, 0x0E, 0x65, 0x05]
+    );
+
+    // the chip8 can't compare with > so vf gets the difference and the carry is checked
+    check(
+        ": main if v0 > 5 then v1 := 1",
+        &[0x6F, 0x05, 0x8F, 0x05, 0x3F, 0x01, 0x61, 0x01]
+    );
+
+    check(
+        ": main if v6 key then v1 := 1 if v7 -key then v1 := 2",
+        &[0xE6, 0xA1, 0x61, 0x01, 0xE7, 0x9E, 0x61, 0x02]
+    );
+}
+
+#[test]
+fn loops() {
+    check(
+        ": main
+            loop
+                while v0 != 10
+                v0 += 1
+            again",
+        &[0x40, 0x0A, 0x12, 0x08, 0x70, 0x01, 0x12, 0x00]
+    );
+}
+
+#[test]
+fn data() {
+    check(
+        ": main
+            :unpack 0xA label
+            :byte { 2 + 3 }
+            :pointer label
+        : label",
+        &[0x60, 0xA2, 0x61, 0x07, 0x05, 0x02, 0x07]
+    );
+
+    check(
+        ": main
+            :org 0x204
+            :byte 7",
+        &[0x00, 0x00, 0x00, 0x00, 0x07]
+    );
+}
+
+#[test]
+fn unknown_label() {
+    check_error(
+        ": main
+            jump nowhere",
+        "error : octo line 2 : 'nowhere' is never defined!"
+    );
+
+    check_error(": start clear", "error : the octo program has no main label!");
+}
+
+#[test]
+fn bad_register() {
+    check_error(
+        ": main
+            v0 := 1
+            save vx",
+        "error : octo line 3 : expected a register but found 'vx'!"
+    );
+
+    check_error(":alias x v10 : main", "error : octo line 1 : 'v10' is not a register!");
+}
+
+#[test]
+fn address_out_of_range() {
+    check_error(
+        ": main
+            jump 0x1000",
+        "error : octo line 2 : 0x1000 doesn't fit here!"
+    );
+
+    check_error(": main :org 0x10000", "error : octo line 1 : :org 65536 is outside of memory!");
+}
+
+#[test]
+fn unfinished_blocks() {
+    check_error(
+        ": main
+            if v0 == 1 begin
+                clear",
+        "error : octo line 2 : this if ... begin never has an end!"
+    );
+
+    check_error(": main end", "error : octo line 1 : end without an if ... begin!");
+    check_error(": main loop clear", "error : octo line 1 : this loop never has an again!");
+}
@@ -0,0 +1,253 @@
+use rand::{
+    Rng,
+    SeedableRng
+};
+
+use rand_chacha::ChaCha8Rng;
+
+use rip8::Quirks;
+
+pub const MEMORY_SIZE : usize = 4096;
+pub const SCREEN_WIDTH : usize = 64;
+pub const SCREEN_HEIGHT : usize = 32;
+
+const PROGRAM_START : u16 = 0x200;
+const FONT_START : usize = 0x50;
+const STACK_SIZE : usize = 16;
+
+const FONT : [u8; 80] = [
+    0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
+    0x20, 0x60, 0x20, 0x20, 0x70, // 1
+    0xF0, 0x10, 0xF0, 0x80, 0xF0, // 2
+    0xF0, 0x10, 0xF0, 0x10, 0xF0, // 3
+    0x90, 0x90, 0xF0, 0x10, 0x10, // 4
+    0xF0, 0x80, 0xF0, 0x10, 0xF0, // 5
+    0xF0, 0x80, 0xF0, 0x90, 0xF0, // 6
+    0xF0, 0x10, 0x20, 0x40, 0x40, // 7
+    0xF0, 0x90, 0xF0, 0x90, 0xF0, // 8
+    0xF0, 0x90, 0xF0, 0x10, 0xF0, // 9
+    0xF0, 0x90, 0xF0, 0x90, 0x90, // A
+    0xE0, 0x90, 0xE0, 0x90, 0xE0, // B
+    0xF0, 0x80, 0x80, 0x80, 0xF0, // C
+    0xE0, 0x90, 0x90, 0x90, 0xE0, // D
+    0xF0, 0x80, 0xF0, 0x80, 0xF0, // E
+    0xF0, 0x80, 0xF0, 0x80, 0x80, // F
+];
+
+/// this is a chip8 written straight from the spec to check rip8 against, it is kept as plain as possible so it is easy
+/// to trust. it doesn't share any code with rip8, only the quirks it is told to run with
+///
+/// where the spec leaves something open this does what rip8 chose to do :
+/// an instruction it doesn't know, a return with nothing on the stack or a call with 16 addresses on it already stays
+/// where it is instead of moving on, addresses made from I wrap around memory, and 5XYN, 9XYN, EX9E, EXA1 and FX29
+/// ignore the nybbles the cosmac vip ignored
+pub struct Reference {
+    pub memory : Vec<u8>,
+    pub v : [u8; 16],
+    pub i : u16,
+    pub pc : u16,
+    pub stack : Vec<u16>,
+    pub delay_timer : u8,
+    pub sound_timer : u8,
+    pub display : [[bool; SCREEN_WIDTH]; SCREEN_HEIGHT],
+    pub waiting_for_vblank : bool,
+    pub quirks : Quirks,
+    rng : ChaCha8Rng,
+}
+
+impl Reference {
+    pub fn new(rom : &[u8], quirks : Quirks, seed : u64) -> Reference {
+        let mut memory = vec![0; MEMORY_SIZE];
+        memory[FONT_START..FONT_START + FONT.len()].copy_from_slice(&FONT);
+        memory[PROGRAM_START as usize..PROGRAM_START as usize + rom.len()].copy_from_slice(rom);
+
+        Reference {
+            memory,
+            v : [0; 16],
+            i : 0,
+            pc : PROGRAM_START,
+            stack : Vec::new(),
+            delay_timer : 0,
+            sound_timer : 0,
+            display : [[false; SCREEN_WIDTH]; SCREEN_HEIGHT],
+            waiting_for_vblank : false,
+            quirks,
+            rng : ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn fetch(&self) -> u16 {
+        ((self.memory[self.pc as usize] as u16) << 8) | self.memory[self.pc as usize + 1] as u16
+    }
+
+    /// this fn runs one instruction with a key held down, it gives back false once the pc has run off the end of memory
+    pub fn step(&mut self, key : Option<u8>) -> bool {
+        if self.pc as usize + 1 >= MEMORY_SIZE {
+            return false
+        }
+
+        if self.waiting_for_vblank {
+            return true
+        }
+
+        let instruction = self.fetch();
+        let x = ((instruction >> 8) & 0xF) as usize;
+        let y = ((instruction >> 4) & 0xF) as usize;
+        let n = instruction & 0xF;
+        let nn = (instruction & 0xFF) as u8;
+        let nnn = instruction & 0xFFF;
+        let (vx, vy) = (self.v[x], self.v[y]);
+
+        let here = self.pc;
+        self.pc += 2;
+
+        match instruction >> 12 {
+            0x0 if instruction == 0x00E0 => self.display = [[false; SCREEN_WIDTH]; SCREEN_HEIGHT],
+            0x0 if instruction == 0x00EE => self.pc = self.stack.pop().unwrap_or(here),
+            0x1 => self.pc = nnn,
+            0x2 if self.stack.len() == STACK_SIZE => self.pc = here,
+            0x2 => {
+                self.stack.push(self.pc);
+                self.pc = nnn;
+            },
+            0x3 => self.skip_if(vx == nn),
+            0x4 => self.skip_if(vx != nn),
+            0x5 => self.skip_if(vx == vy),
+            0x6 => self.v[x] = nn,
+            0x7 => self.v[x] = vx.wrapping_add(nn),
+            0x8 => match n {
+                0x0 => self.v[x] = vy,
+                0x1 => self.logic(vx | vy, x),
+                0x2 => self.logic(vx & vy, x),
+                0x3 => self.logic(vx ^ vy, x),
+                0x4 => {
+                    let (result, carry) = vx.overflowing_add(vy);
+                    self.set_with_flag(x, result, carry as u8);
+                },
+                0x5 => self.set_with_flag(x, vx.wrapping_sub(vy), (vx >= vy) as u8),
+                0x7 => self.set_with_flag(x, vy.wrapping_sub(vx), (vy >= vx) as u8),
+                0x6 => {
+                    let value = if self.quirks.shift { vx } else { vy };
+                    self.set_with_flag(x, value >> 1, value & 1);
+                },
+                0xE => {
+                    let value = if self.quirks.shift { vx } else { vy };
+                    self.set_with_flag(x, value << 1, value >> 7);
+                },
+                _ => self.pc = here
+            },
+            0x9 => self.skip_if(vx != vy),
+            0xA => self.i = nnn,
+            0xB => {
+                let offset = if self.quirks.jump { vx } else { self.v[0] };
+                self.pc = nnn + offset as u16;
+            },
+            // the spec says any byte 0 to 255, both sides draw it from the same seed so they agree on which one
+            0xC => self.v[x] = self.rng.gen::<u8>() & nn,
+            0xD => self.draw(vx, vy, n as usize),
+            0xE if nn == 0x9E => self.skip_if(key == Some(vx & 0xF)),
+            0xE if nn == 0xA1 => self.skip_if(key != Some(vx & 0xF)),
+            0xF => match nn {
+                0x07 => self.v[x] = self.delay_timer,
+                0x0A => match key {
+                    Some(key) => self.v[x] = key,
+                    None => self.pc = here
+                },
+                0x15 => self.delay_timer = vx,
+                0x18 => self.sound_timer = vx,
+                0x1E => self.i = self.i.wrapping_add(vx as u16),
+                0x29 => self.i = (FONT_START + (vx & 0xF) as usize * 5) as u16,
+                0x33 => {
+                    for (offset, digit) in [vx / 100, vx / 10 % 10, vx % 10].into_iter().enumerate() {
+                        let address = self.index_address(offset);
+                        self.memory[address] = digit;
+                    }
+                },
+                0x55 => {
+                    for register in 0..=x {
+                        let address = self.index_address(register);
+                        self.memory[address] = self.v[register];
+                    }
+                    self.move_index_past(x);
+                },
+                0x65 => {
+                    for register in 0..=x {
+                        self.v[register] = self.memory[self.index_address(register)];
+                    }
+                    self.move_index_past(x);
+                },
+                _ => self.pc = here
+            },
+            _ => self.pc = here
+        }
+
+        true
+    }
+
+    pub fn update_timers(&mut self) {
+        self.waiting_for_vblank = false;
+        self.delay_timer = self.delay_timer.saturating_sub(1);
+        self.sound_timer = self.sound_timer.saturating_sub(1);
+    }
+
+    fn skip_if(&mut self, skip : bool) {
+        if skip {
+            self.pc += 2;
+        }
+    }
+
+    fn logic(&mut self, result : u8, x : usize) {
+        self.v[x] = result;
+        if self.quirks.logic {
+            self.v[0xF] = 0;
+        }
+    }
+
+    // the flag is written after the result so it is what ends up in vf when vf is the register being set
+    fn set_with_flag(&mut self, x : usize, result : u8, flag : u8) {
+        self.v[x] = result;
+        self.v[0xF] = flag;
+    }
+
+    fn index_address(&self, offset : usize) -> usize {
+        (self.i as usize + offset) % MEMORY_SIZE
+    }
+
+    fn move_index_past(&mut self, x : usize) {
+        if !self.quirks.load_store {
+            self.i = self.i.wrapping_add(x as u16 + 1);
+        }
+    }
+
+    fn draw(&mut self, vx : u8, vy : u8, height : usize) {
+        let left = vx as usize % SCREEN_WIDTH;
+        let top = vy as usize % SCREEN_HEIGHT;
+        self.v[0xF] = 0;
+
+        for row in 0..height {
+            let sprite_row = self.memory[self.index_address(row)];
+
+            for column in 0..8 {
+                let (mut x, mut y) = (left + column, top + row);
+
+                if self.quirks.clip {
+                    if x >= SCREEN_WIDTH || y >= SCREEN_HEIGHT {
+                        continue
+                    }
+                } else {
+                    x %= SCREEN_WIDTH;
+                    y %= SCREEN_HEIGHT;
+                }
+
+                if sprite_row & (0x80 >> column) != 0 {
+                    if self.display[y][x] {
+                        self.v[0xF] = 1;
+                    }
+                    self.display[y][x] = !self.display[y][x];
+                }
+            }
+        }
+
+        self.waiting_for_vblank = self.quirks.vblank;
+    }
+}
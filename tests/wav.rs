@@ -0,0 +1,104 @@
+// these render a known tone with the wav writer and read the file back to check the header and the samples
+
+use std::fs;
+
+use rip8::{
+    AudioConfig,
+    WavWriter,
+    Waveform
+};
+
+/// this fn gives back a path in the temp directory that no other test uses
+fn temp_path(name : &str) -> String {
+    std::env::temp_dir().join(format!("rip8_{}_{}.wav", name, std::process::id())).display().to_string()
+}
+
+fn u16_at(bytes : &[u8], offset : usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn u32_at(bytes : &[u8], offset : usize) -> u32 {
+    u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+}
+
+fn square_tone() -> AudioConfig {
+    AudioConfig { frequency : 441.0, waveform : Waveform::Square, volume : 0.5 }
+}
+
+/// this fn checks every field of the header for a mono 16 bit file and gives back the samples after it
+fn read_wav(path : &str, sample_rate : u32) -> Vec<i16> {
+    let bytes = fs::read(path).unwrap();
+    fs::remove_file(path).unwrap();
+
+    assert_eq!(&bytes[0..4], b"RIFF");
+    assert_eq!(u32_at(&bytes, 4) as usize, bytes.len() - 8);
+    assert_eq!(&bytes[8..12], b"WAVE");
+
+    assert_eq!(&bytes[12..16], b"fmt ");
+    assert_eq!(u32_at(&bytes, 16), 16);
+    assert_eq!(u16_at(&bytes, 20), 1, "the format should be pcm");
+    assert_eq!(u16_at(&bytes, 22), 1, "there should be one channel");
+    assert_eq!(u32_at(&bytes, 24), sample_rate);
+    assert_eq!(u32_at(&bytes, 28), sample_rate * 2, "the byte rate should be two bytes a sample");
+    assert_eq!(u16_at(&bytes, 32), 2);
+    assert_eq!(u16_at(&bytes, 34), 16);
+
+    assert_eq!(&bytes[36..40], b"data");
+    assert_eq!(u32_at(&bytes, 40) as usize, bytes.len() - 44);
+
+    bytes[44..].chunks(2).map(|sample| i16::from_le_bytes([sample[0], sample[1]])).collect()
+}
+
+#[test]
+fn header_and_samples_match_tone() {
+    let path = temp_path("tone");
+
+    // 44100 is 735 samples a frame, the buzzer is on for the first 30 of the 60 frames
+    let mut wav_writer = WavWriter::new(&path, square_tone(), 44100).unwrap();
+    for frame in 0..60 {
+        wav_writer.push_frame(30_u8.saturating_sub(frame)).unwrap();
+    }
+    wav_writer.finish().unwrap();
+
+    let samples = read_wav(&path, 44100);
+    assert_eq!(samples.len(), 60 * 735);
+
+    // the envelope fades over a few milliseconds so the middle of the tone is at full volume and the end is silent
+    let tone = &samples[1000..30 * 735 - 1000];
+    let loudest = (i16::MAX as f32 * 0.5) as i16;
+    assert!(tone.iter().all(|sample| sample.abs() == loudest), "the square wave should only be at its full volume");
+
+    // 441hz at 44100 is a cycle every 100 samples, so the wave should change sign twice in every 100
+    let sign_changes = tone.windows(2).filter(|pair| (pair[0] > 0) != (pair[1] > 0)).count();
+    assert_eq!(sign_changes, tone.len() / 50);
+
+    assert!(samples[31 * 735..].iter().all(|sample| *sample == 0), "the buzzer should be silent after the sound timer runs out");
+}
+
+#[test]
+fn leftover_samples_carry_between_frames() {
+    let path = temp_path("leftover");
+
+    // 22050 is 367.5 samples a frame so every other frame gets the half sample left over
+    let mut wav_writer = WavWriter::new(&path, square_tone(), 22050).unwrap();
+    for _ in 0..10 {
+        wav_writer.push_frame(0).unwrap();
+    }
+    wav_writer.finish().unwrap();
+
+    assert_eq!(read_wav(&path, 22050).len(), 3675);
+}
+
+#[test]
+fn dropping_fills_in_header() {
+    let path = temp_path("dropped");
+
+    // a run that stops with an error never calls finish, the header still has to end up with the right sizes
+    let mut wav_writer = WavWriter::new(&path, square_tone(), 44100).unwrap();
+    for _ in 0..4 {
+        wav_writer.push_frame(10).unwrap();
+    }
+    drop(wav_writer);
+
+    assert_eq!(read_wav(&path, 44100).len(), 4 * 735);
+}